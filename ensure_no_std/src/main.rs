@@ -42,5 +42,18 @@ pub extern "C" fn _start() -> ! {
     assert_eq!(decoder.decode_symbol(model), Ok(5));
     assert_eq!(decoder.decode_symbol(model), Ok(3));
 
+    // Streaming through a fixed-capacity ring buffer doesn't allocate.
+    let ring = constriction::backends::RingBuffer::<u32, 4>::new();
+    let mut encoder = constriction::stream::queue::DefaultRangeEncoder::with_backend(&ring);
+    encoder.encode_symbol(3u32, model).unwrap();
+    encoder.encode_symbol(5u32, model).unwrap();
+    encoder.into_compressed().unwrap();
+    ring.close();
+
+    let mut decoder =
+        constriction::stream::queue::DefaultRangeDecoder::with_backend(&ring).unwrap();
+    assert_eq!(decoder.decode_symbol(model), Ok(3));
+    assert_eq!(decoder.decode_symbol(model), Ok(5));
+
     loop {}
 }
@@ -184,9 +184,10 @@ use core::{
     fmt::{Debug, Display},
     marker::PhantomData,
 };
+use num_traits::AsPrimitive;
 use smallvec::SmallVec;
 
-use crate::{Pos, PosSeek, Queue, Seek, Semantics, Stack};
+use crate::{BitArray, Pos, PosSeek, Queue, Seek, Semantics, Stack};
 
 // MAIN TRAITS FOR CAPABILITIES OF BACKENDS ===================================
 
@@ -237,6 +238,19 @@ pub trait ReadWords<Word, S: Semantics> {
     fn maybe_exhausted(&self) -> bool {
         true
     }
+
+    /// Tries to undo the last `num_words` successful calls to [`read`](Self::read), so that
+    /// the next `num_words` calls to `read` return the same `Word`s again.
+    ///
+    /// Returns `true` on success. Decoders call this method to undo the reads for a
+    /// partially decoded symbol when a subsequent read failed with a recoverable error,
+    /// so that the caller can retry decoding the symbol later (see, e.g.,
+    /// [`PushReadWords`]). The default implementation doesn't support undoing reads and
+    /// always returns `false` without doing anything.
+    #[inline(always)]
+    fn unread(&mut self, _num_words: usize) -> bool {
+        false
+    }
 }
 
 /// A trait for sinks of compressed data (mainly used by encoders).
@@ -1819,74 +1833,4749 @@ where
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use crate::stream::{model::DefaultLeakyQuantizer, stack::DefaultAnsCoder, Decode};
-    use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
-    use probability::distribution::Gaussian;
-    use std::{
-        fs::File,
-        io::{BufReader, BufWriter},
-    };
+// ADAPTER FOR WRITING TO TWO DATA SINKS AT ONCE ==============================
 
-    #[test]
-    #[cfg_attr(miri, ignore)]
-    fn decode_on_the_fly_stack() {
-        fn encode_to_file(amt: u32) {
-            let quantizer = DefaultLeakyQuantizer::new(-256..=255);
-            let model = quantizer.quantize(Gaussian::new(0.0, 100.0));
+/// Data sink that forwards each `Word` to two data sinks.
+///
+/// Writing a `Word` to a `Tee(first, second)` writes a copy of the `Word` to `first` and
+/// then writes the `Word` to `second`. This is useful if you want to, e.g., write
+/// compressed data to a file while at the same time feeding it into a hasher or a network
+/// connection. Errors from either data sink are reported through a [`TeeWriteError`],
+/// which tells you which of the two data sinks failed.
+///
+/// If `first` accepts a `Word` but `second` fails then `first` will contain one more
+/// `Word` than `second`. There's no way to undo the write to `first` in general, so you
+/// typically shouldn't continue to use a `Tee` after a failed write.
+///
+/// `Tee` implements [`BoundedWriteWords`] if both data sinks do, in which case the space
+/// left is the minimum of the space left in either data sink.
+///
+/// # Example
+///
+/// ```
+/// use constriction::{
+///     backends::{CountingWriteWords, Tee},
+///     stream::{model::DefaultLeakyQuantizer, queue::DefaultRangeEncoder, Encode},
+/// };
+///
+/// let quantizer = DefaultLeakyQuantizer::new(-100..=100);
+/// let model = quantizer.quantize(probability::distribution::Gaussian::new(0.0, 10.0));
+/// let symbols = [3, -8, 12, 0, 5];
+///
+/// // Write the compressed data to a `Vec` and count the written words at the same time.
+/// let backend = Tee(Vec::new(), CountingWriteWords::new());
+/// let mut encoder = DefaultRangeEncoder::with_backend(backend);
+/// encoder.encode_iid_symbols(&symbols, &model).unwrap();
+/// let Tee(compressed, counter) = encoder.into_compressed().unwrap();
+/// assert_eq!(counter.num_words(), compressed.len());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Tee<First, Second>(pub First, pub Second);
 
-            let symbols = (0..amt).map(|i| {
-                let cheap_hash = i.wrapping_mul(0x6979_E2F3).wrapping_add(0x0059_0E91);
-                (cheap_hash >> (32 - 9)) as i32 - 256
-            });
+impl<Word, First, Second> WriteWords<Word> for Tee<First, Second>
+where
+    Word: Clone,
+    First: WriteWords<Word>,
+    Second: WriteWords<Word>,
+{
+    type WriteError = TeeWriteError<First::WriteError, Second::WriteError>;
 
-            let mut encoder = DefaultAnsCoder::new();
-            encoder.encode_iid_symbols_reverse(symbols, model).unwrap();
-            let compressed = encoder.into_compressed().unwrap();
+    #[inline]
+    fn write(&mut self, word: Word) -> Result<(), Self::WriteError> {
+        self.0.write(word.clone()).map_err(TeeWriteError::First)?;
+        self.1.write(word).map_err(TeeWriteError::Second)
+    }
 
-            let mut file = BufWriter::new(File::create("backend_stack_example.tmp").unwrap());
-            for &word in compressed.iter().rev() {
-                file.write_u32::<LittleEndian>(word).unwrap();
-            }
+    #[inline(always)]
+    fn maybe_full(&self) -> bool {
+        self.0.maybe_full() || self.1.maybe_full()
+    }
+}
+
+impl<Word, First, Second> BoundedWriteWords<Word> for Tee<First, Second>
+where
+    Word: Clone,
+    First: BoundedWriteWords<Word>,
+    Second: BoundedWriteWords<Word>,
+{
+    #[inline(always)]
+    fn space_left(&self) -> usize {
+        self.0.space_left().min(self.1.space_left())
+    }
+}
+
+/// Error type for [`Tee`], indicating which of the two data sinks failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TeeWriteError<FirstError, SecondError> {
+    /// Writing to the first data sink failed (and nothing was written to the second one).
+    First(FirstError),
+
+    /// Writing to the second data sink failed (after the first one accepted the `Word`).
+    Second(SecondError),
+}
+
+impl<FirstError: Display, SecondError: Display> Display for TeeWriteError<FirstError, SecondError> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::First(err) => write!(f, "Error while writing to first data sink: {err}"),
+            Self::Second(err) => write!(f, "Error while writing to second data sink: {err}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<FirstError, SecondError> std::error::Error for TeeWriteError<FirstError, SecondError>
+where
+    FirstError: std::error::Error + 'static,
+    SecondError: std::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::First(err) => Some(err),
+            Self::Second(err) => Some(err),
         }
+    }
+}
 
-        fn decode_from_file_on_the_fly(amt: u32) {
-            let quantizer = DefaultLeakyQuantizer::new(-256..=255);
-            let model = quantizer.quantize(Gaussian::new(0.0, 100.0));
+// COUNTING DATA SINK =========================================================
 
-            let mut file = BufReader::new(File::open("backend_stack_example.tmp").unwrap());
-            let word_iterator = std::iter::from_fn(move || match file.read_u32::<LittleEndian>() {
-                Ok(word) => Some(Ok(word)),
-                Err(err) => {
-                    if err.kind() == std::io::ErrorKind::UnexpectedEof {
-                        None
-                    } else {
-                        Some(Err(err))
-                    }
-                }
-            });
+/// A data sink that discards all `Word`s written to it and only counts them.
+///
+/// Use a `CountingWriteWords` as the backend of an entropy coder if you're only interested
+/// in the exact size of the compressed data but not in the compressed data itself, e.g.,
+/// when evaluating bitrates during a large parameter sweep. It requires only constant
+/// memory regardless of how much data you encode.
+///
+/// A `CountingWriteWords` implements [`Pos`], [`Seek`], and [`BoundedReadWords`] (for both
+/// [`Stack`] and [`Queue`] semantics) so that methods like [`AnsCoder::num_bits`],
+/// [`RangeEncoder::num_bits`], and [`RangeEncoder::pos`] work as usual and report the same
+/// values as they would for a `Vec<Word>` backend. However, since the actual `Word`s are
+/// discarded, any attempt to read a `Word` fails with a [`DiscardedWordsError`] (unless no
+/// `Word`s have been written, in which case reading returns `Ok(None)`).
+///
+/// # Example
+///
+/// ```
+/// use constriction::{
+///     backends::CountingWriteWords,
+///     stream::{
+///         model::DefaultLeakyQuantizer, queue::DefaultRangeEncoder, stack::DefaultAnsCoder,
+///         Encode,
+///     },
+///     UnwrapInfallible,
+/// };
+///
+/// let quantizer = DefaultLeakyQuantizer::new(-100..=100);
+/// let model = quantizer.quantize(probability::distribution::Gaussian::new(0.0, 10.0));
+///
+/// let mut reference_encoder = DefaultRangeEncoder::new();
+/// reference_encoder.encode_iid_symbols(-100..=100, &model).unwrap();
+///
+/// let mut counting_encoder = DefaultRangeEncoder::with_backend(CountingWriteWords::new());
+/// counting_encoder.encode_iid_symbols(-100..=100, &model).unwrap();
+/// assert_eq!(counting_encoder.num_bits(), reference_encoder.num_bits());
+///
+/// // The same works for an `AnsCoder`. Sealing the coder also just counts words.
+/// let mut counting_coder = DefaultAnsCoder::from_compressed(CountingWriteWords::new()).unwrap();
+/// counting_coder.encode_iid_symbols_reverse(-100..=100, &model).unwrap();
+/// let num_bits = counting_coder.num_bits();
+/// assert_eq!(counting_coder.into_compressed().unwrap_infallible().num_bits(), num_bits);
+/// ```
+///
+/// [`AnsCoder::num_bits`]: crate::stream::stack::AnsCoder::num_bits
+/// [`RangeEncoder::num_bits`]: crate::stream::queue::RangeEncoder::num_bits
+/// [`RangeEncoder::pos`]: crate::stream::queue::RangeEncoder#impl-Pos
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CountingWriteWords<Word> {
+    num_words: usize,
+    phantom: PhantomData<Word>,
+}
 
-            let mut decoder =
-                DefaultAnsCoder::from_reversed_compressed_iter(word_iterator).unwrap();
+impl<Word> CountingWriteWords<Word> {
+    /// Creates a counter that starts at zero.
+    pub fn new() -> Self {
+        Self::with_num_words(0)
+    }
 
-            for (i, symbol) in decoder.decode_iid_symbols(amt as usize, &model).enumerate() {
-                let cheap_hash = (i as u32)
-                    .wrapping_mul(0x6979_E2F3)
-                    .wrapping_add(0x0059_0E91);
-                let expected = (cheap_hash >> (32 - 9)) as i32 - 256;
-                assert_eq!(symbol.unwrap(), expected);
-            }
-            assert!(decoder.is_empty());
+    /// Creates a counter that starts at `num_words`, as if `num_words` words had already
+    /// been written to it.
+    pub fn with_num_words(num_words: usize) -> Self {
+        Self {
+            num_words,
+            phantom: PhantomData,
+        }
+    }
 
-            let mut word_iterator = decoder.into_raw_parts().0.into_iter();
-            assert!(word_iterator.next().is_none());
+    /// Returns the number of words that have been written to the counter.
+    pub fn num_words(&self) -> usize {
+        self.num_words
+    }
 
-            std::mem::drop(word_iterator);
-            std::fs::remove_file("backend_stack_example.tmp").unwrap();
+    /// Returns the number of bits that have been written to the counter.
+    pub fn num_bits(&self) -> usize
+    where
+        Word: BitArray,
+    {
+        Word::BITS * self.num_words
+    }
+}
+
+impl<Word> WriteWords<Word> for CountingWriteWords<Word> {
+    type WriteError = Infallible;
+
+    #[inline(always)]
+    fn write(&mut self, _word: Word) -> Result<(), Self::WriteError> {
+        self.num_words += 1;
+        Ok(())
+    }
+
+    fn extend_from_iter(
+        &mut self,
+        iter: impl Iterator<Item = Word>,
+    ) -> Result<(), Self::WriteError> {
+        self.num_words += iter.count();
+        Ok(())
+    }
+
+    fn maybe_full(&self) -> bool {
+        false
+    }
+}
+
+/// Since `CountingWriteWords` doesn't store any data, reading always fails (unless there's
+/// nothing to read), so it is allowed to implement `ReadWords` for all `Semantics`.
+impl<Word, S: Semantics> ReadWords<Word, S> for CountingWriteWords<Word> {
+    type ReadError = DiscardedWordsError;
+
+    #[inline(always)]
+    fn read(&mut self) -> Result<Option<Word>, Self::ReadError> {
+        if self.num_words == 0 {
+            Ok(None)
+        } else {
+            Err(DiscardedWordsError)
         }
+    }
 
-        encode_to_file(1000);
-        decode_from_file_on_the_fly(1000);
+    #[inline(always)]
+    fn maybe_exhausted(&self) -> bool {
+        self.num_words == 0
+    }
+}
+
+impl<Word, S: Semantics> BoundedReadWords<Word, S> for CountingWriteWords<Word> {
+    #[inline(always)]
+    fn remaining(&self) -> usize {
+        self.num_words
+    }
+}
+
+impl<Word> PosSeek for CountingWriteWords<Word> {
+    type Position = usize;
+}
+
+impl<Word> Pos for CountingWriteWords<Word> {
+    /// Returns the number of words that have been written to the counter since that's the
+    /// position where a `Vec<Word>` backend would be at this point.
+    fn pos(&self) -> usize {
+        self.num_words
+    }
+}
+
+impl<Word> Seek for CountingWriteWords<Word> {
+    /// Only succeeds if `pos` is smaller than or equal to the number of counted words, in
+    /// which case the counter is reset to `pos` (analogous to [seeking in a
+    /// `Vec`](Vec#impl-Seek)).
+    fn seek(&mut self, pos: usize) -> Result<(), ()> {
+        if pos <= self.num_words {
+            self.num_words = pos;
+            Ok(())
+        } else {
+            Err(())
+        }
+    }
+}
+
+impl<Word, S: Semantics> IntoReadWords<Word, S> for CountingWriteWords<Word> {
+    type IntoReadWords = Self;
+
+    fn into_read_words(self) -> Self::IntoReadWords {
+        self
+    }
+}
+
+impl<'a, Word: 'a, S: Semantics> AsReadWords<'a, Word, S> for CountingWriteWords<Word> {
+    type AsReadWords = Self;
+
+    fn as_read_words(&'a self) -> Self::AsReadWords {
+        Self::with_num_words(self.num_words)
+    }
+}
+
+/// Error type for attempts to read from a [`CountingWriteWords`].
+///
+/// A `CountingWriteWords` discards all words that are written to it, so they can't be read
+/// back.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct DiscardedWordsError;
+
+impl Display for DiscardedWordsError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Attempted to read data that was discarded.")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DiscardedWordsError {}
+
+// BYTE ORDER OF WORDS ========================================================
+
+/// A trait for marking the order in which the bytes of a `Word` are serialized.
+///
+/// Some backends (e.g., [`ByteVec`], [`ByteCursor`], [`IoWriteWords`], and [`IoReadWords`])
+/// convert between `Word`s and sequences of bytes. The type parameter `E: Endianness` of
+/// these backends specifies the byte order. There are two predefined `Endianness`es:
+/// [`LittleEndian`] and [`BigEndian`].
+///
+/// Note that the byte order only affects how each individual `Word` is serialized. It does
+/// not affect the order of `Word`s, which is determined by the entropy coder and by its
+/// read/write [`Semantics`].
+pub trait Endianness: Copy + Debug + Default {
+    /// Serializes `word` into `bytes`.
+    ///
+    /// The slice `bytes` must have length `Word::BITS / 8`.
+    fn word_to_bytes<Word>(word: Word, bytes: &mut [u8])
+    where
+        Word: BitArray + AsPrimitive<u8>;
+
+    /// Deserializes a `Word` from `bytes`.
+    ///
+    /// The slice `bytes` must have length `Word::BITS / 8`. This is the inverse of
+    /// [`word_to_bytes`](Self::word_to_bytes).
+    fn word_from_bytes<Word>(bytes: &[u8]) -> Word
+    where
+        Word: BitArray,
+        u8: AsPrimitive<Word>;
+}
+
+/// Zero sized marker type for [`Endianness`] that serializes the least significant byte of
+/// a `Word` first.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LittleEndian {}
+
+/// Zero sized marker type for [`Endianness`] that serializes the most significant byte of a
+/// `Word` first.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BigEndian {}
+
+impl Endianness for LittleEndian {
+    #[inline(always)]
+    fn word_to_bytes<Word>(word: Word, bytes: &mut [u8])
+    where
+        Word: BitArray + AsPrimitive<u8>,
+    {
+        debug_assert_eq!(bytes.len(), Word::BITS / 8);
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = (word >> (8 * i)).as_();
+        }
+    }
+
+    #[inline(always)]
+    fn word_from_bytes<Word>(bytes: &[u8]) -> Word
+    where
+        Word: BitArray,
+        u8: AsPrimitive<Word>,
+    {
+        debug_assert_eq!(bytes.len(), Word::BITS / 8);
+        bytes
+            .iter()
+            .enumerate()
+            .fold(Word::zero(), |word, (i, &byte)| {
+                word | byte.as_() << (8 * i)
+            })
+    }
+}
+
+impl Endianness for BigEndian {
+    #[inline(always)]
+    fn word_to_bytes<Word>(word: Word, bytes: &mut [u8])
+    where
+        Word: BitArray + AsPrimitive<u8>,
+    {
+        debug_assert_eq!(bytes.len(), Word::BITS / 8);
+        for (i, byte) in bytes.iter_mut().rev().enumerate() {
+            *byte = (word >> (8 * i)).as_();
+        }
+    }
+
+    #[inline(always)]
+    fn word_from_bytes<Word>(bytes: &[u8]) -> Word
+    where
+        Word: BitArray,
+        u8: AsPrimitive<Word>,
+    {
+        debug_assert_eq!(bytes.len(), Word::BITS / 8);
+        bytes
+            .iter()
+            .rev()
+            .enumerate()
+            .fold(Word::zero(), |word, (i, &byte)| {
+                word | byte.as_() << (8 * i)
+            })
+    }
+}
+
+// ADAPTERS FOR `std::io::Read` AND `std::io::Write` ==========================
+
+/// Adapter that turns a [`std::io::Write`] into a data sink.
+///
+/// Wraps a `std::io::Write` and implements [`WriteWords<Word,
+/// WriteError=std::io::Error>`](WriteWords) by serializing each `Word` into `Word::BITS / 8`
+/// bytes with the byte order `E` (see [`Endianness`]) and writing these bytes to the wrapped
+/// writer. You'll usually want to wrap unbuffered writers like a [`std::fs::File`] or a
+/// [`std::net::TcpStream`] in a [`std::io::BufWriter`] first since entropy coders write
+/// one `Word` at a time.
+///
+/// See also [`IoReadWords`] for the reverse direction.
+///
+/// # Example
+///
+/// ```
+/// use constriction::{
+///     backends::{BigEndian, IoReadWords, IoWriteWords},
+///     stream::{
+///         model::DefaultLeakyQuantizer,
+///         queue::{DefaultRangeDecoder, DefaultRangeEncoder},
+///         Decode, Encode,
+///     },
+/// };
+///
+/// let quantizer = DefaultLeakyQuantizer::new(-100..=100);
+/// let model = quantizer.quantize(probability::distribution::Gaussian::new(0.0, 10.0));
+/// let symbols = [3, -8, 12, 0, 5];
+///
+/// // Encode directly into anything that implements `std::io::Write` (here: a `Vec<u8>`).
+/// let backend = IoWriteWords::<_, BigEndian>::new(Vec::new());
+/// let mut encoder = DefaultRangeEncoder::with_backend(backend);
+/// encoder.encode_iid_symbols(&symbols, &model).unwrap();
+/// let bytes: Vec<u8> = encoder.into_compressed().unwrap().into_inner();
+/// assert_eq!(bytes.len() % 4, 0);
+///
+/// // Decode directly from anything that implements `std::io::Read` (here: a `&[u8]`).
+/// let backend = IoReadWords::<_, BigEndian>::new(&bytes[..]);
+/// let mut decoder = DefaultRangeDecoder::with_backend(backend).unwrap();
+/// let decoded = decoder.decode_iid_symbols(5, &model).collect::<Result<Vec<_>, _>>().unwrap();
+/// assert_eq!(decoded, symbols);
+/// assert!(decoder.maybe_exhausted());
+/// ```
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, Default)]
+pub struct IoWriteWords<W, E: Endianness = LittleEndian> {
+    inner: W,
+    endianness: PhantomData<E>,
+}
+
+#[cfg(feature = "std")]
+impl<W, E: Endianness> IoWriteWords<W, E> {
+    /// Creates the adapter for the provided writer.
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            endianness: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the wrapped writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the wrapped writer.
+    ///
+    /// Writing to the wrapped writer directly will likely corrupt any compressed data that
+    /// an entropy coder writes to this adapter.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Consumes the adapter and returns the wrapped writer.
+    ///
+    /// This does not flush the writer. Call [`std::io::Write::flush`] on the returned
+    /// writer if necessary.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+#[cfg(feature = "std")]
+impl<Word, W, E> WriteWords<Word> for IoWriteWords<W, E>
+where
+    Word: BitArray + AsPrimitive<u8>,
+    W: std::io::Write,
+    E: Endianness,
+{
+    type WriteError = std::io::Error;
+
+    #[inline]
+    fn write(&mut self, word: Word) -> Result<(), Self::WriteError> {
+        let mut bytes = [0u8; 16];
+        let bytes = &mut bytes[..Word::BITS / 8];
+        E::word_to_bytes(word, bytes);
+        self.inner.write_all(bytes)
+    }
+}
+
+/// Adapter that turns a [`std::io::Read`] into a data source.
+///
+/// Wraps a `std::io::Read` and implements [`ReadWords<Word, S,
+/// ReadError=std::io::Error>`](ReadWords) by reading `Word::BITS / 8` bytes at a time from
+/// the wrapped reader and deserializing them into a `Word` with the byte order `E` (see
+/// [`Endianness`]). You'll usually want to wrap unbuffered readers like a
+/// [`std::fs::File`] or a [`std::net::TcpStream`] in a [`std::io::BufReader`] first since
+/// entropy coders read one `Word` at a time.
+///
+/// Reaching the end of the wrapped reader at a `Word` boundary is reported as `Ok(None)`
+/// (i.e., "end of file", see [`ReadWords::read`]). Reaching the end of the wrapped reader
+/// in the middle of a `Word` is reported as an error of kind
+/// [`std::io::ErrorKind::UnexpectedEof`]. Once the end has been reached, the adapter won't
+/// try to read from the wrapped reader again (as required by the contract of `ReadWords`).
+///
+/// Implements `ReadWords` for arbitrary [`Semantics`]. This is legal since it doesn't
+/// implement `WriteWords`, so the question how reads relate to writes is moot.
+///
+/// See also [`IoWriteWords`] for the reverse direction and for an example.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, Default)]
+pub struct IoReadWords<R, E: Endianness = LittleEndian> {
+    inner: R,
+    exhausted: bool,
+    endianness: PhantomData<E>,
+}
+
+#[cfg(feature = "std")]
+impl<R, E: Endianness> IoReadWords<R, E> {
+    /// Creates the adapter for the provided reader.
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            exhausted: false,
+            endianness: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the wrapped reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the wrapped reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Consumes the adapter and returns the wrapped reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+/// Since `IoReadWords` doesn't implement `WriteWords`, it is allowed to implement
+/// `ReadWords` for all `Semantics`.
+#[cfg(feature = "std")]
+impl<Word, R, E, S> ReadWords<Word, S> for IoReadWords<R, E>
+where
+    Word: BitArray,
+    u8: AsPrimitive<Word>,
+    R: std::io::Read,
+    E: Endianness,
+    S: Semantics,
+{
+    type ReadError = std::io::Error;
+
+    fn read(&mut self) -> Result<Option<Word>, Self::ReadError> {
+        if self.exhausted {
+            return Ok(None);
+        }
+
+        let mut bytes = [0u8; 16];
+        let bytes = &mut bytes[..Word::BITS / 8];
+        let mut num_read = 0;
+        while num_read != bytes.len() {
+            match self.inner.read(&mut bytes[num_read..]) {
+                Ok(0) => break,
+                Ok(n) => num_read += n,
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+
+        if num_read == bytes.len() {
+            Ok(Some(E::word_from_bytes(bytes)))
+        } else {
+            self.exhausted = true;
+            if num_read == 0 {
+                Ok(None)
+            } else {
+                Err(std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    "data source ended in the middle of a word",
+                ))
+            }
+        }
+    }
+}
+
+// ADAPTERS FOR `futures::io::AsyncRead` AND `futures::io::AsyncWrite` ========
+
+/// Adapter that turns a [`futures_io::AsyncRead`] into a data source.
+///
+/// Entropy coders read compressed data synchronously, one `Word` at a time, so they can't
+/// `.await` on an asynchronous reader themselves. An `AsyncReadWords` therefore separates
+/// the asynchronous part from the synchronous part: the `async` method [`fill`] reads from
+/// the wrapped reader into an internal buffer of `Word`s (yielding whenever the wrapped
+/// reader would block), and the implementation of [`ReadWords`] serves `Word`s only from
+/// this buffer and never blocks.
+///
+/// If the buffer runs dry before the wrapped reader reached its end then
+/// [`ReadWords::read`] returns an error of kind [`std::io::ErrorKind::WouldBlock`]. An
+/// entropy coder may be left in an inconsistent state after such an error, so you'll
+/// usually not want to call `fill` yourself but instead use the `*_async` methods of
+/// [`RangeDecoder`], which fill the buffer with just as many `Word`s as they need before
+/// decoding each symbol. This allows you to pipeline decompression with network I/O
+/// without first buffering the entire compressed data.
+///
+/// Bytes are deserialized into `Word`s with the byte order `E` (see [`Endianness`]).
+/// Reaching the end of the wrapped reader in the middle of a `Word` is reported as an error
+/// of kind [`std::io::ErrorKind::UnexpectedEof`].
+///
+/// See also [`AsyncWriteWords`] for the reverse direction and for an example.
+///
+/// [`fill`]: Self::fill
+/// [`RangeDecoder`]: crate::stream::queue::RangeDecoder
+#[cfg(feature = "async")]
+#[derive(Clone, Debug)]
+pub struct AsyncReadWords<Word, R, E: Endianness = LittleEndian> {
+    inner: R,
+    words: alloc::collections::VecDeque<Word>,
+    partial_word: [u8; 16],
+    num_partial_bytes: usize,
+    exhausted: bool,
+    endianness: PhantomData<E>,
+}
+
+#[cfg(feature = "async")]
+impl<Word, R, E> AsyncReadWords<Word, R, E>
+where
+    Word: BitArray,
+    u8: AsPrimitive<Word>,
+    E: Endianness,
+{
+    /// Creates the adapter for the provided reader. Doesn't read anything yet.
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            words: alloc::collections::VecDeque::new(),
+            partial_word: [0; 16],
+            num_partial_bytes: 0,
+            exhausted: false,
+            endianness: PhantomData,
+        }
+    }
+
+    /// Reads from the wrapped reader until at least `num_words` `Word`s are buffered or
+    /// the wrapped reader reached its end.
+    ///
+    /// May buffer more than `num_words` `Word`s if the wrapped reader has more data
+    /// readily available.
+    pub async fn fill(&mut self, num_words: usize) -> std::io::Result<()>
+    where
+        R: futures_io::AsyncRead + Unpin,
+    {
+        let word_size = Word::BITS / 8;
+        let mut chunk = [0u8; 256];
+        while self.words.len() < num_words && !self.exhausted {
+            let result = core::future::poll_fn(|cx| {
+                core::pin::Pin::new(&mut self.inner).poll_read(cx, &mut chunk)
+            })
+            .await;
+
+            match result {
+                Ok(0) => {
+                    self.exhausted = true;
+                    if self.num_partial_bytes != 0 {
+                        return Err(std::io::Error::new(
+                            std::io::ErrorKind::UnexpectedEof,
+                            "data source ended in the middle of a word",
+                        ));
+                    }
+                }
+                Ok(num_read) => {
+                    for &byte in &chunk[..num_read] {
+                        self.partial_word[self.num_partial_bytes] = byte;
+                        self.num_partial_bytes += 1;
+                        if self.num_partial_bytes == word_size {
+                            let word = E::word_from_bytes(&self.partial_word[..word_size]);
+                            self.words.push_back(word);
+                            self.num_partial_bytes = 0;
+                        }
+                    }
+                }
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the number of `Word`s that can currently be read without blocking.
+    pub fn num_buffered_words(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Returns `true` if the wrapped reader has reached its end.
+    ///
+    /// There may still be buffered `Word`s left to read (see [`num_buffered_words`]).
+    ///
+    /// [`num_buffered_words`]: Self::num_buffered_words
+    pub fn reached_end(&self) -> bool {
+        self.exhausted
+    }
+
+    /// Returns a reference to the wrapped reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the wrapped reader.
+    ///
+    /// Reading from the wrapped reader directly will skip over some compressed data.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Consumes the adapter and returns the wrapped reader.
+    ///
+    /// Any `Word`s that were already buffered are lost.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+/// Since `AsyncReadWords` doesn't implement `WriteWords`, it is allowed to implement
+/// `ReadWords` for all `Semantics`.
+#[cfg(feature = "async")]
+impl<Word, R, E, S> ReadWords<Word, S> for AsyncReadWords<Word, R, E>
+where
+    Word: BitArray,
+    E: Endianness,
+    S: Semantics,
+{
+    type ReadError = std::io::Error;
+
+    #[inline]
+    fn read(&mut self) -> Result<Option<Word>, Self::ReadError> {
+        if let Some(word) = self.words.pop_front() {
+            Ok(Some(word))
+        } else if self.exhausted {
+            Ok(None)
+        } else {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::WouldBlock,
+                "no buffered words left; call `AsyncReadWords::fill` first",
+            ))
+        }
+    }
+
+    #[inline(always)]
+    fn maybe_exhausted(&self) -> bool {
+        self.words.is_empty()
+    }
+}
+
+/// Adapter that turns a [`futures_io::AsyncWrite`] into a data sink.
+///
+/// Implements [`WriteWords<Word, WriteError=Infallible>`](WriteWords) by serializing each
+/// `Word` into `Word::BITS / 8` bytes with the byte order `E` (see [`Endianness`]) and
+/// appending them to an internal buffer. The `async` methods [`write_out`], [`flush`], and
+/// [`close`] then write the buffered bytes to the wrapped writer, yielding whenever the
+/// wrapped writer would block.
+///
+/// You'll usually not call these methods yourself but instead use the `*_async` methods
+/// of [`RangeEncoder`], which write out the buffer whenever it reaches the `buffer_size`
+/// provided to the constructor.
+///
+/// # Example
+///
+/// ```
+/// use constriction::{
+///     backends::{AsyncReadWords, AsyncWriteWords, BigEndian},
+///     stream::{
+///         model::DefaultLeakyQuantizer,
+///         queue::{DefaultRangeDecoder, DefaultRangeEncoder},
+///     },
+/// };
+///
+/// let quantizer = DefaultLeakyQuantizer::new(-100..=100);
+/// let model = quantizer.quantize(probability::distribution::Gaussian::new(0.0, 10.0));
+/// let symbols = [3, -8, 12, 0, 5];
+///
+/// futures::executor::block_on(async {
+///     // Encode into anything that implements `AsyncWrite` (here: an in-memory cursor).
+///     let writer = futures::io::Cursor::new(Vec::new());
+///     let backend = AsyncWriteWords::<_, BigEndian>::new(writer);
+///     let mut encoder = DefaultRangeEncoder::with_backend(backend);
+///     encoder.encode_iid_symbols_async(&symbols, &model).await.unwrap();
+///     let bytes = encoder.into_compressed_async().await.unwrap().into_inner();
+///
+///     // Decode from anything that implements `AsyncRead` (here: a `&[u8]`).
+///     let backend = AsyncReadWords::<u32, _, BigEndian>::new(&bytes[..]);
+///     let mut decoder = DefaultRangeDecoder::with_async_backend(backend).await.unwrap();
+///     let decoded = decoder.decode_iid_symbols_async(5, &model).await.unwrap();
+///     assert_eq!(decoded, symbols);
+/// });
+/// ```
+///
+/// [`write_out`]: Self::write_out
+/// [`flush`]: Self::flush
+/// [`close`]: Self::close
+/// [`RangeEncoder`]: crate::stream::queue::RangeEncoder
+#[cfg(feature = "async")]
+#[derive(Clone, Debug)]
+pub struct AsyncWriteWords<W, E: Endianness = LittleEndian> {
+    inner: W,
+    buf: Vec<u8>,
+    buffer_size: usize,
+    endianness: PhantomData<E>,
+}
+
+#[cfg(feature = "async")]
+impl<W, E: Endianness> AsyncWriteWords<W, E> {
+    /// The buffer size used by [`new`](Self::new), in bytes.
+    pub const DEFAULT_BUFFER_SIZE: usize = 1 << 13;
+
+    /// Creates the adapter for the provided writer with the default buffer size.
+    pub fn new(inner: W) -> Self {
+        Self::with_buffer_size(inner, Self::DEFAULT_BUFFER_SIZE)
+    }
+
+    /// Creates the adapter for the provided writer with a custom buffer size (in bytes).
+    ///
+    /// The buffer size is only a hint for the `*_async` methods of entropy coders,
+    /// which write out the buffer once it holds at least `buffer_size` bytes. The buffer
+    /// itself grows as necessary.
+    pub fn with_buffer_size(inner: W, buffer_size: usize) -> Self {
+        Self {
+            inner,
+            buf: Vec::with_capacity(buffer_size),
+            buffer_size,
+            endianness: PhantomData,
+        }
+    }
+
+    /// Returns the buffer size that was provided to the constructor, in bytes.
+    pub fn buffer_size(&self) -> usize {
+        self.buffer_size
+    }
+
+    /// Returns the number of bytes that are buffered but not yet written to the wrapped
+    /// writer.
+    pub fn num_buffered_bytes(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Writes all buffered bytes to the wrapped writer (without flushing it).
+    pub async fn write_out(&mut self) -> std::io::Result<()>
+    where
+        W: futures_io::AsyncWrite + Unpin,
+    {
+        let mut num_written = 0;
+        while num_written != self.buf.len() {
+            let result = core::future::poll_fn(|cx| {
+                core::pin::Pin::new(&mut self.inner).poll_write(cx, &self.buf[num_written..])
+            })
+            .await;
+
+            match result {
+                Ok(0) => {
+                    self.buf.drain(..num_written);
+                    return Err(std::io::ErrorKind::WriteZero.into());
+                }
+                Ok(n) => num_written += n,
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(err) => {
+                    self.buf.drain(..num_written);
+                    return Err(err);
+                }
+            }
+        }
+
+        self.buf.clear();
+        Ok(())
+    }
+
+    /// Writes all buffered bytes to the wrapped writer and flushes it.
+    pub async fn flush(&mut self) -> std::io::Result<()>
+    where
+        W: futures_io::AsyncWrite + Unpin,
+    {
+        self.write_out().await?;
+        core::future::poll_fn(|cx| core::pin::Pin::new(&mut self.inner).poll_flush(cx)).await
+    }
+
+    /// Writes all buffered bytes to the wrapped writer and closes it.
+    pub async fn close(&mut self) -> std::io::Result<()>
+    where
+        W: futures_io::AsyncWrite + Unpin,
+    {
+        self.write_out().await?;
+        core::future::poll_fn(|cx| core::pin::Pin::new(&mut self.inner).poll_close(cx)).await
+    }
+
+    /// Returns a reference to the wrapped writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the wrapped writer.
+    ///
+    /// Writing to the wrapped writer directly will likely corrupt any compressed data that
+    /// an entropy coder writes to this adapter.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Consumes the adapter and returns the wrapped writer.
+    ///
+    /// Any bytes that are still buffered are lost, so you'll usually want to call
+    /// [`flush`](Self::flush) or [`close`](Self::close) first.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+#[cfg(feature = "async")]
+impl<Word, W, E> WriteWords<Word> for AsyncWriteWords<W, E>
+where
+    Word: BitArray + AsPrimitive<u8>,
+    E: Endianness,
+{
+    type WriteError = Infallible;
+
+    #[inline]
+    fn write(&mut self, word: Word) -> Result<(), Self::WriteError> {
+        let mut bytes = [0u8; 16];
+        let bytes = &mut bytes[..Word::BITS / 8];
+        E::word_to_bytes(word, bytes);
+        self.buf.extend_from_slice(bytes);
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn maybe_full(&self) -> bool {
+        false
+    }
+}
+
+// IN-MEMORY BUFFERS OF BYTES =================================================
+
+/// A growable in-memory buffer that stores `Word`s as a sequence of bytes.
+///
+/// A `ByteVec<Word, E>` is the byte-oriented counterpart of a `Vec<Word>` backend. It
+/// implements [`WriteWords<Word>`] by serializing each `Word` into `Word::BITS / 8` bytes
+/// with the byte order `E` (see [`Endianness`]) and appending these bytes to an internal
+/// `Vec<u8>`. Like a `Vec<Word>`, it also implements [`ReadWords<Word, Stack>`] (reading
+/// pops `Word`s off the end of the buffer), so you can use it directly as the backend of an
+/// [`AnsCoder`]. For decoding with `Queue` semantics (e.g., with a [`RangeDecoder`]), or for
+/// decoding without consuming the data, convert it into a [`ByteCursor`] (e.g., via
+/// [`IntoReadWords`] or [`AsReadWords`], which entropy coders do automatically in methods
+/// like [`RangeEncoder::into_decoder`]).
+///
+/// The compressed data is accessible as a slice of bytes via [`as_bytes`] or as an owned
+/// `Vec<u8>` via [`into_bytes`], so there's no need to convert a `Vec<Word>` to bytes
+/// manually. If you write to a `ByteVec` only with [`write`](WriteWords::write) then its
+/// length in bytes is always a multiple of `Word::BITS / 8`. However, a `ByteVec` (as well
+/// as a `ByteCursor`) can also hold data whose length is not a multiple of the word size,
+/// e.g., the output of [`RangeEncoder::into_trimmed_compressed`]. In this case, the last
+/// `Word` is incomplete and reading it pads it with zero bytes at the missing positions.
+/// Writing a `Word` to a `ByteVec` that ends in an incomplete `Word` first pads the
+/// incomplete `Word` with zero bytes, so that `Word`s always start at byte offsets that
+/// are multiples of `Word::BITS / 8`.
+///
+/// Positions (see [`Pos`] and [`Seek`]) are measured in units of `Word`s (an incomplete
+/// `Word` at the end counts as a full `Word`), so that they are interchangeable with the
+/// positions of a `Vec<Word>` backend.
+///
+/// # Example
+///
+/// ```
+/// use constriction::{
+///     backends::{BigEndian, ByteVec},
+///     stream::{model::DefaultLeakyQuantizer, stack::AnsCoder, Decode},
+///     UnwrapInfallible,
+/// };
+///
+/// let quantizer = DefaultLeakyQuantizer::new(-100..=100);
+/// let model = quantizer.quantize(probability::distribution::Gaussian::new(0.0, 10.0));
+/// let symbols = [3, -8, 12, 0, 5];
+///
+/// let mut encoder = AnsCoder::<u32, u64, _>::from_compressed(ByteVec::<u32, BigEndian>::new())
+///     .unwrap();
+/// encoder.encode_iid_symbols_reverse(&symbols, &model).unwrap();
+/// let compressed = encoder.into_compressed().unwrap_infallible();
+/// let bytes: &[u8] = compressed.as_bytes(); // Ready to be written to a file or socket.
+/// assert_eq!(bytes.len(), 4 * compressed.num_words());
+///
+/// // Decode again (this time from a fresh `ByteVec` that we reconstruct from the bytes).
+/// let compressed = ByteVec::<u32, BigEndian>::from_bytes(bytes.to_vec());
+/// let mut decoder = AnsCoder::<u32, u64, _>::from_compressed(compressed).unwrap();
+/// let decoded = decoder.decode_iid_symbols(5, &model).collect::<Result<Vec<_>, _>>();
+/// assert_eq!(decoded.unwrap_infallible(), symbols);
+/// assert!(decoder.is_empty());
+/// ```
+///
+/// [`AnsCoder`]: crate::stream::stack::AnsCoder
+/// [`RangeDecoder`]: crate::stream::queue::RangeDecoder
+/// [`RangeEncoder::into_decoder`]: crate::stream::queue::RangeEncoder::into_decoder
+/// [`RangeEncoder::into_trimmed_compressed`]:
+/// crate::stream::queue::RangeEncoder::into_trimmed_compressed
+/// [`as_bytes`]: Self::as_bytes
+/// [`into_bytes`]: Self::into_bytes
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ByteVec<Word, E: Endianness = LittleEndian> {
+    bytes: Vec<u8>,
+    phantom: PhantomData<(Word, E)>,
+}
+
+impl<Word, E: Endianness> ByteVec<Word, E> {
+    /// Creates an empty `ByteVec`.
+    pub fn new() -> Self {
+        Self::from_bytes(Vec::new())
+    }
+
+    /// Wraps an existing sequence of bytes, e.g., compressed data that was read from a file.
+    ///
+    /// The length of `bytes` doesn't have to be a multiple of `Word::BITS / 8` (see
+    /// discussion of incomplete `Word`s in the [struct level documentation](Self)).
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        Self {
+            bytes,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns the contents of the buffer as a slice of bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Consumes the `ByteVec` and returns the contents of the buffer as a vector of bytes.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    /// Returns the length of the buffer in bytes.
+    ///
+    /// See also [`num_words`](Self::num_words).
+    pub fn num_bytes(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Returns the length of the buffer in units of `Word`s, counting an incomplete `Word`
+    /// at the end (if any) as a full `Word`.
+    ///
+    /// See also [`num_bytes`](Self::num_bytes).
+    pub fn num_words(&self) -> usize
+    where
+        Word: BitArray,
+    {
+        self.bytes.len().div_ceil(Word::BITS / 8)
+    }
+
+    /// Returns a (read-only) [`ByteCursor`] that shares its buffer with the `ByteVec`.
+    ///
+    /// The returned `ByteCursor` points to the beginning of the buffer, i.e., it's ready
+    /// for reading with [`Queue`] semantics. Call [`Seek::seek`] on it to move it to a
+    /// different position.
+    pub fn as_cursor(&self) -> ByteCursor<Word, &[u8], E>
+    where
+        Word: BitArray,
+    {
+        ByteCursor::new_at_write_beginning(&self.bytes)
+    }
+
+    /// Removes all trailing zero bytes.
+    ///
+    /// This is only legal for data sources that are read with zero padding, i.e., if the
+    /// consumer treats reading beyond the end of the data the same as reading zero bytes.
+    pub(crate) fn trim_trailing_zeros(&mut self) {
+        let len = self
+            .bytes
+            .iter()
+            .rposition(|&byte| byte != 0)
+            .map_or(0, |last_nonzero| last_nonzero + 1);
+        self.bytes.truncate(len);
+    }
+}
+
+/// Deserializes a `Word` from up to `Word::BITS / 8` bytes, padding missing bytes at the end
+/// with zeros.
+#[inline(always)]
+fn word_from_padded_bytes<Word, E>(bytes: &[u8]) -> Word
+where
+    Word: BitArray,
+    u8: AsPrimitive<Word>,
+    E: Endianness,
+{
+    let word_size = Word::BITS / 8;
+    if bytes.len() == word_size {
+        E::word_from_bytes(bytes)
+    } else {
+        let mut padded = [0u8; 16];
+        padded[..bytes.len()].copy_from_slice(bytes);
+        E::word_from_bytes(&padded[..word_size])
+    }
+}
+
+impl<Word, E> WriteWords<Word> for ByteVec<Word, E>
+where
+    Word: BitArray + AsPrimitive<u8>,
+    E: Endianness,
+{
+    /// The only way how writing to a `ByteVec` can fail is if a memory allocation fails,
+    /// which is typically treated as a fatal error (i.e., aborts) in Rust.
+    type WriteError = Infallible;
+
+    /// Appends the serialized word to the end of the buffer (= top of the stack).
+    #[inline(always)]
+    fn write(&mut self, word: Word) -> Result<(), Self::WriteError> {
+        let word_size = Word::BITS / 8;
+        let start = self.bytes.len().next_multiple_of(word_size);
+        self.bytes.resize(start + word_size, 0);
+        E::word_to_bytes(word, &mut self.bytes[start..]);
+        Ok(())
+    }
+
+    fn maybe_full(&self) -> bool {
+        false
+    }
+}
+
+impl<Word, E> ReadWords<Word, Stack> for ByteVec<Word, E>
+where
+    Word: BitArray,
+    u8: AsPrimitive<Word>,
+    E: Endianness,
+{
+    /// The only way how reading from a `ByteVec` can fail is if the buffer is empty, but
+    /// that's not considered an error (it returns `Ok(None)` instead).
+    type ReadError = Infallible;
+
+    /// Pops the last (possibly incomplete) word off the end of the buffer (= top of the
+    /// stack).
+    #[inline(always)]
+    fn read(&mut self) -> Result<Option<Word>, Self::ReadError> {
+        if self.bytes.is_empty() {
+            Ok(None)
+        } else {
+            let word_size = Word::BITS / 8;
+            let start = (self.bytes.len() - 1) / word_size * word_size;
+            let word = word_from_padded_bytes::<Word, E>(&self.bytes[start..]);
+            self.bytes.truncate(start);
+            Ok(Some(word))
+        }
+    }
+
+    #[inline(always)]
+    fn maybe_exhausted(&self) -> bool {
+        self.bytes.is_empty()
+    }
+}
+
+impl<Word, E> BoundedReadWords<Word, Stack> for ByteVec<Word, E>
+where
+    Word: BitArray,
+    u8: AsPrimitive<Word>,
+    E: Endianness,
+{
+    #[inline(always)]
+    fn remaining(&self) -> usize {
+        self.num_words()
+    }
+}
+
+impl<Word, E: Endianness> PosSeek for ByteVec<Word, E> {
+    type Position = usize;
+}
+
+impl<Word: BitArray, E: Endianness> Pos for ByteVec<Word, E> {
+    /// Returns the length of the buffer in units of `Word`s since that's the current read
+    /// and write position (`ByteVec`s have [`Stack`] semantics, just like `Vec`s).
+    fn pos(&self) -> usize {
+        self.num_words()
+    }
+}
+
+impl<Word: BitArray, E: Endianness> Seek for ByteVec<Word, E> {
+    /// Seeking in a `ByteVec` only succeeds if the provided position `pos` is smaller than
+    /// or equal to the current length in units of `Word`s, in which case the buffer gets
+    /// truncated to `pos` `Word`s (analogous to [seeking in a `Vec`](Vec#impl-Seek)).
+    fn seek(&mut self, pos: usize) -> Result<(), ()> {
+        if pos <= self.num_words() {
+            self.bytes.truncate(pos * (Word::BITS / 8));
+            Ok(())
+        } else {
+            Err(())
+        }
+    }
+}
+
+impl<Word, E> IntoReadWords<Word, Stack> for ByteVec<Word, E>
+where
+    Word: BitArray,
+    u8: AsPrimitive<Word>,
+    E: Endianness,
+{
+    type IntoReadWords = ByteCursor<Word, Vec<u8>, E>;
+
+    fn into_read_words(self) -> Self::IntoReadWords {
+        ByteCursor::new_at_write_end(self.bytes)
+    }
+}
+
+impl<Word, E> IntoReadWords<Word, Queue> for ByteVec<Word, E>
+where
+    Word: BitArray,
+    u8: AsPrimitive<Word>,
+    E: Endianness,
+{
+    type IntoReadWords = ByteCursor<Word, Vec<u8>, E>;
+
+    fn into_read_words(self) -> Self::IntoReadWords {
+        ByteCursor::new_at_write_beginning(self.bytes)
+    }
+}
+
+impl<'a, Word, E> AsReadWords<'a, Word, Stack> for ByteVec<Word, E>
+where
+    Word: BitArray,
+    u8: AsPrimitive<Word>,
+    E: Endianness + 'a,
+{
+    type AsReadWords = ByteCursor<Word, &'a [u8], E>;
+
+    fn as_read_words(&'a self) -> Self::AsReadWords {
+        ByteCursor::new_at_write_end(&self.bytes)
+    }
+}
+
+impl<'a, Word, E> AsReadWords<'a, Word, Queue> for ByteVec<Word, E>
+where
+    Word: BitArray,
+    u8: AsPrimitive<Word>,
+    E: Endianness + 'a,
+{
+    type AsReadWords = ByteCursor<Word, &'a [u8], E>;
+
+    fn as_read_words(&'a self) -> Self::AsReadWords {
+        ByteCursor::new_at_write_beginning(&self.bytes)
+    }
+}
+
+impl<Word, E, S: Semantics> IntoSeekReadWords<Word, S> for ByteVec<Word, E>
+where
+    E: Endianness,
+    Self: IntoReadWords<Word, S, IntoReadWords = ByteCursor<Word, Vec<u8>, E>>,
+    ByteCursor<Word, Vec<u8>, E>: ReadWords<Word, S> + Seek,
+{
+    type IntoSeekReadWords = ByteCursor<Word, Vec<u8>, E>;
+
+    fn into_seek_read_words(self) -> Self::IntoSeekReadWords {
+        self.into_read_words()
+    }
+}
+
+/// Adapter that turns an in-memory buffer of bytes into an `impl ReadWords` and/or an
+/// `impl WriteWords` for multi-byte `Word`s.
+///
+/// A `ByteCursor<Word, Buf, E>` is the byte-oriented counterpart of a [`Cursor`]. It reads
+/// `Word`s from (and, if `Buf` implements `AsMut<[u8]>`, writes `Word`s to) a buffer
+/// `Buf: AsRef<[u8]>` in the byte order `E` (see [`Endianness`]). In the most typical use
+/// case, `Buf` is either a `&[u8]` (e.g., a memory region that you received from the
+/// network) or a `Vec<u8>`. Just like a `Cursor`, a `ByteCursor` implements `ReadWords`
+/// for both [`Queue`] and [`Stack`] semantics, where reading with `Queue` semantics
+/// advances the cursor towards the end of the buffer and reading with `Stack` semantics
+/// moves it towards the beginning. It also implements [`Pos`] and [`Seek`], which measure
+/// positions in units of `Word`s (not bytes).
+///
+/// The `Word`s are aligned at byte offsets that are multiples of `Word::BITS / 8`, counted
+/// from the beginning of the buffer. If the length of the buffer is not a multiple of
+/// `Word::BITS / 8` then the last `Word` is incomplete and reading it pads it with zero
+/// bytes at the missing positions (see also [`RangeEncoder::into_trimmed_compressed`]).
+/// An incomplete `Word` counts as a full `Word` for the purpose of positions.
+///
+/// # Example
+///
+/// ```
+/// use constriction::{
+///     backends::{ByteCursor, LittleEndian},
+///     stream::{
+///         model::DefaultLeakyQuantizer,
+///         queue::{DefaultRangeDecoder, DefaultRangeEncoder},
+///         Decode, Encode,
+///     },
+///     UnwrapInfallible,
+/// };
+///
+/// let quantizer = DefaultLeakyQuantizer::new(-100..=100);
+/// let model = quantizer.quantize(probability::distribution::Gaussian::new(0.0, 10.0));
+/// let symbols = [3, -8, 12, 0, 5];
+///
+/// let mut encoder = DefaultRangeEncoder::new();
+/// encoder.encode_iid_symbols(&symbols, &model).unwrap();
+/// let compressed = encoder.into_compressed().unwrap_infallible();
+/// let bytes = compressed.iter().flat_map(|word| word.to_le_bytes()).collect::<Vec<u8>>();
+///
+/// // Decode directly from the slice of bytes without copying it.
+/// let cursor = ByteCursor::<u32, _, LittleEndian>::new_at_write_beginning(&bytes[..]);
+/// let mut decoder = DefaultRangeDecoder::with_backend(cursor).unwrap_infallible();
+/// let decoded = decoder.decode_iid_symbols(5, &model).collect::<Result<Vec<_>, _>>();
+/// assert_eq!(decoded.unwrap(), symbols);
+/// assert!(decoder.maybe_exhausted());
+/// ```
+///
+/// [`RangeEncoder::into_trimmed_compressed`]:
+/// crate::stream::queue::RangeEncoder::into_trimmed_compressed
+#[derive(Clone, Debug)]
+pub struct ByteCursor<Word, Buf, E: Endianness = LittleEndian> {
+    buf: Buf,
+
+    /// Byte offset of the next word to be read with a `ReadWords<Word, Queue>` or written
+    /// with a `WriteWords<Word>`, and byte offset of the end of the next word to be read
+    /// with `ReadWords<Word, Stack>`.
+    ///
+    /// Satisfies the invariants `pos <= buf.as_ref().len()` and `pos` is either a multiple
+    /// of `Word::BITS / 8` or equal to `buf.as_ref().len()`.
+    pos: usize,
+
+    phantom: PhantomData<(Word, E)>,
+}
+
+impl<Word: BitArray, Buf, E: Endianness> ByteCursor<Word, Buf, E> {
+    /// Creates a `ByteCursor` for the buffer `buf` and initializes the cursor position to
+    /// point at the beginning of the buffer.
+    ///
+    /// This is the typical starting point for decoding with `Queue` semantics. See
+    /// [`Cursor::new_at_write_beginning`] for a discussion of the method name.
+    #[inline(always)]
+    pub fn new_at_write_beginning(buf: Buf) -> Self {
+        Self {
+            buf,
+            pos: 0,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Creates a `ByteCursor` for the buffer `buf` and initializes the cursor position to
+    /// point at the end of the buffer.
+    ///
+    /// This is the typical starting point for decoding with `Stack` semantics. See
+    /// [`Cursor::new_at_write_end`] for a discussion of the method name.
+    #[inline(always)]
+    pub fn new_at_write_end(buf: Buf) -> Self
+    where
+        Buf: AsRef<[u8]>,
+    {
+        let pos = buf.as_ref().len();
+        Self {
+            buf,
+            pos,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Creates a `ByteCursor` for the buffer `buf` and initializes the cursor position to
+    /// point at the beginning of the `Word` with index `pos`.
+    ///
+    /// Returns an error if `pos` is larger than the number of (possibly incomplete) `Word`s
+    /// in the buffer.
+    #[allow(clippy::result_unit_err)]
+    pub fn new_at_pos(buf: Buf, pos: usize) -> Result<Self, ()>
+    where
+        Buf: AsRef<[u8]>,
+    {
+        let mut cursor = Self::new_at_write_beginning(buf);
+        cursor.seek(pos)?;
+        Ok(cursor)
+    }
+
+    /// Returns a new (read-only) `ByteCursor` that shares its buffer with the current
+    /// `ByteCursor` and points to the same position.
+    ///
+    /// See [`Cursor::as_view`].
+    pub fn as_view(&self) -> ByteCursor<Word, &[u8], E>
+    where
+        Buf: AsRef<[u8]>,
+    {
+        ByteCursor {
+            buf: self.buf.as_ref(),
+            pos: self.pos,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the generic buffer that the `ByteCursor` reads from or
+    /// writes to.
+    pub fn buf(&self) -> &Buf {
+        &self.buf
+    }
+
+    /// Returns a mutable reference to the generic buffer that the `ByteCursor` reads from
+    /// or writes to.
+    pub fn buf_mut(&mut self) -> &mut Buf {
+        &mut self.buf
+    }
+
+    /// Consumes the `ByteCursor` and returns the buffer and the current position (in units
+    /// of `Word`s, see [`Pos`]).
+    pub fn into_buf_and_pos(self) -> (Buf, usize) {
+        let pos = self.pos.div_ceil(Word::BITS / 8);
+        (self.buf, pos)
+    }
+}
+
+impl<Word, Buf, E> WriteWords<Word> for ByteCursor<Word, Buf, E>
+where
+    Word: BitArray + AsPrimitive<u8>,
+    Buf: AsMut<[u8]>,
+    E: Endianness,
+{
+    type WriteError = BoundedWriteError;
+
+    #[inline(always)]
+    fn write(&mut self, word: Word) -> Result<(), Self::WriteError> {
+        let end = self.pos + Word::BITS / 8;
+        if let Some(target) = self.buf.as_mut().get_mut(self.pos..end) {
+            E::word_to_bytes(word, target);
+            self.pos = end;
+            Ok(())
+        } else {
+            Err(BoundedWriteError::OutOfSpace)
+        }
+    }
+}
+
+impl<Word, Buf, E> BoundedWriteWords<Word> for ByteCursor<Word, Buf, E>
+where
+    Word: BitArray + AsPrimitive<u8>,
+    Buf: AsMut<[u8]> + AsRef<[u8]>,
+    E: Endianness,
+{
+    #[inline(always)]
+    fn space_left(&self) -> usize {
+        (self.buf.as_ref().len() - self.pos) / (Word::BITS / 8)
+    }
+}
+
+impl<Word, Buf, E> ReadWords<Word, Stack> for ByteCursor<Word, Buf, E>
+where
+    Word: BitArray,
+    u8: AsPrimitive<Word>,
+    Buf: AsRef<[u8]>,
+    E: Endianness,
+{
+    type ReadError = Infallible;
+
+    #[inline(always)]
+    fn read(&mut self) -> Result<Option<Word>, Self::ReadError> {
+        if self.pos == 0 {
+            Ok(None)
+        } else {
+            let word_size = Word::BITS / 8;
+            let start = (self.pos - 1) / word_size * word_size;
+            let word = word_from_padded_bytes::<Word, E>(&self.buf.as_ref()[start..self.pos]);
+            self.pos = start;
+            Ok(Some(word))
+        }
+    }
+
+    #[inline(always)]
+    fn maybe_exhausted(&self) -> bool {
+        BoundedReadWords::<Word, Stack>::is_exhausted(self)
+    }
+}
+
+impl<Word, Buf, E> ReadWords<Word, Queue> for ByteCursor<Word, Buf, E>
+where
+    Word: BitArray,
+    u8: AsPrimitive<Word>,
+    Buf: AsRef<[u8]>,
+    E: Endianness,
+{
+    type ReadError = Infallible;
+
+    #[inline(always)]
+    fn read(&mut self) -> Result<Option<Word>, Self::ReadError> {
+        let bytes = self.buf.as_ref();
+        if self.pos >= bytes.len() {
+            Ok(None)
+        } else {
+            let end = bytes.len().min(self.pos + Word::BITS / 8);
+            let word = word_from_padded_bytes::<Word, E>(&bytes[self.pos..end]);
+            self.pos = end;
+            Ok(Some(word))
+        }
+    }
+
+    #[inline(always)]
+    fn maybe_exhausted(&self) -> bool {
+        BoundedReadWords::<Word, Queue>::is_exhausted(self)
+    }
+}
+
+impl<Word, Buf, E> BoundedReadWords<Word, Stack> for ByteCursor<Word, Buf, E>
+where
+    Word: BitArray,
+    u8: AsPrimitive<Word>,
+    Buf: AsRef<[u8]>,
+    E: Endianness,
+{
+    #[inline(always)]
+    fn remaining(&self) -> usize {
+        self.pos.div_ceil(Word::BITS / 8)
+    }
+}
+
+impl<Word, Buf, E> BoundedReadWords<Word, Queue> for ByteCursor<Word, Buf, E>
+where
+    Word: BitArray,
+    u8: AsPrimitive<Word>,
+    Buf: AsRef<[u8]>,
+    E: Endianness,
+{
+    #[inline(always)]
+    fn remaining(&self) -> usize {
+        (self.buf.as_ref().len() - self.pos).div_ceil(Word::BITS / 8)
+    }
+}
+
+impl<Word, Buf, E: Endianness> PosSeek for ByteCursor<Word, Buf, E> {
+    type Position = usize;
+}
+
+impl<Word: BitArray, Buf: AsRef<[u8]>, E: Endianness> Pos for ByteCursor<Word, Buf, E> {
+    /// Returns the index of the `Word` at which the cursor currently points.
+    #[inline(always)]
+    fn pos(&self) -> usize {
+        self.pos.div_ceil(Word::BITS / 8)
+    }
+}
+
+impl<Word: BitArray, Buf: AsRef<[u8]>, E: Endianness> Seek for ByteCursor<Word, Buf, E> {
+    /// Moves the cursor to the beginning of the `Word` with index `pos`.
+    ///
+    /// Fails if `pos` is larger than the number of (possibly incomplete) `Word`s in the
+    /// buffer. Note that `pos` may be equal to the number of `Word`s in the buffer (EOF for
+    /// queues and beginning for stacks).
+    #[inline(always)]
+    fn seek(&mut self, pos: usize) -> Result<(), ()> {
+        let len = self.buf.as_ref().len();
+        if pos > len.div_ceil(Word::BITS / 8) {
+            Err(())
+        } else {
+            self.pos = len.min(pos * (Word::BITS / 8));
+            Ok(())
+        }
+    }
+}
+
+// MEMORY-MAPPED FILES ========================================================
+
+/// A read-only buffer of `Word`s that is backed by a memory-mapped file.
+///
+/// An `MmapWords<Word>` wraps a [`memmap2::Mmap`] and implements `AsRef<[Word]>` and
+/// [`SafeBuf<Word>`], so it can be used in the same places where you'd otherwise use a
+/// `Vec<Word>` that holds the entire contents of the file. In particular, you can wrap it
+/// in a [`Cursor`] to decode with both [`Stack`] and [`Queue`] semantics and to jump to
+/// arbitrary positions with [`Seek::seek`], or you can pass the slice returned by
+/// `.as_ref()` to constructors like [`AnsCoder::from_compressed_slice`]. The operating
+/// system only loads those pages of the file into memory that are actually accessed, which
+/// makes this backend well suited for decoding a few symbols from a huge file.
+///
+/// The file has to contain the compressed `Word`s in the native byte order of the machine
+/// (e.g., as written by `word.to_ne_bytes()` for all words returned by
+/// [`AnsCoder::into_compressed`]), and its length has to be a multiple of `Word::BITS /
+/// 8`. If you need to decode data with a fixed byte order, then you can wrap the
+/// [`memmap2::Mmap`] in a [`ByteCursor`] instead, which accesses bytes rather than
+/// `Word`s and therefore has no requirements on byte order, length, or alignment.
+///
+/// This type is only available if the crate feature `mmap` is enabled.
+///
+/// # Example
+///
+/// ```
+/// use constriction::{
+///     backends::{Cursor, MmapWords},
+///     stream::{model::DefaultLeakyQuantizer, stack::DefaultAnsCoder, Decode},
+///     Pos, Seek, UnwrapInfallible,
+/// };
+/// use std::io::Write;
+///
+/// let quantizer = DefaultLeakyQuantizer::new(-100..=100);
+/// let model = quantizer.quantize(probability::distribution::Gaussian::new(0.0, 10.0));
+///
+/// // Encode some data, remember a position in the middle, and write it to a file.
+/// let mut encoder = DefaultAnsCoder::new();
+/// encoder.encode_iid_symbols_reverse(-50..50, &model).unwrap();
+/// let snapshot = encoder.pos();
+/// encoder.encode_iid_symbols_reverse(50..100, &model).unwrap();
+/// let compressed = encoder.into_compressed().unwrap_infallible();
+/// # let dir = tempfile::tempdir().unwrap();
+/// # let path = dir.path().join("compressed.bin");
+/// let mut file = std::fs::File::create(&path).unwrap();
+/// for word in &compressed {
+///     file.write_all(&word.to_ne_bytes()).unwrap();
+/// }
+/// drop(file);
+///
+/// // Map the file into memory and decode from it without reading it in its entirety.
+/// let file = std::fs::File::open(&path).unwrap();
+/// // SAFETY: we don't modify the file while it's mapped.
+/// let words = unsafe { MmapWords::<u32>::map(&file) }.unwrap();
+/// let mut decoder = DefaultAnsCoder::from_compressed(Cursor::new_at_write_end(words)).unwrap();
+/// decoder.seek(snapshot).unwrap();
+/// let decoded = decoder.decode_iid_symbols(100, &model).map(UnwrapInfallible::unwrap_infallible);
+/// assert!(decoded.eq(-50..50));
+/// assert!(decoder.is_empty());
+/// # drop(decoder);
+/// # drop(file);
+/// # dir.close().unwrap();
+/// ```
+///
+/// [`AnsCoder::from_compressed_slice`]: crate::stream::stack::AnsCoder::from_compressed_slice
+/// [`AnsCoder::into_compressed`]: crate::stream::stack::AnsCoder::into_compressed
+#[cfg(feature = "mmap")]
+#[derive(Debug)]
+pub struct MmapWords<Word> {
+    mmap: memmap2::Mmap,
+    phantom: PhantomData<Word>,
+}
+
+#[cfg(feature = "mmap")]
+impl<Word: BitArray> MmapWords<Word> {
+    /// Maps the entire `file` into memory.
+    ///
+    /// Fails with an error of kind [`std::io::ErrorKind::InvalidData`] if the length of the
+    /// file is not a multiple of `Word::BITS / 8`.
+    ///
+    /// # Safety
+    ///
+    /// Same as for [`memmap2::Mmap::map`]: the file must not be modified (neither by this
+    /// nor by any other process) while it is mapped into memory, since this would be
+    /// undefined behavior.
+    pub unsafe fn map(file: &std::fs::File) -> std::io::Result<Self> {
+        Self::from_mmap(memmap2::Mmap::map(file)?).map_err(|_| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "length of memory-mapped file is not a multiple of the word size",
+            )
+        })
+    }
+
+    /// Wraps an existing memory map.
+    ///
+    /// Fails and returns the unmodified `mmap` if its length is not a multiple of
+    /// `Word::BITS / 8` or if it is not properly aligned for `Word`s (the latter can only
+    /// happen if `mmap` was created with a nonzero offset).
+    pub fn from_mmap(mmap: memmap2::Mmap) -> Result<Self, memmap2::Mmap> {
+        // SAFETY: `Word: BitArray` guarantees that `Word` behaves like a builtin unsigned
+        // integer type, so every bit pattern is a valid `Word`.
+        let (prefix, _, suffix) = unsafe { mmap.align_to::<Word>() };
+        if prefix.is_empty() && suffix.is_empty() {
+            Ok(Self {
+                mmap,
+                phantom: PhantomData,
+            })
+        } else {
+            Err(mmap)
+        }
+    }
+
+    /// Returns a reference to the underlying memory map.
+    pub fn mmap(&self) -> &memmap2::Mmap {
+        &self.mmap
+    }
+
+    /// Consumes the `MmapWords` and returns the underlying memory map.
+    pub fn into_mmap(self) -> memmap2::Mmap {
+        self.mmap
+    }
+}
+
+#[cfg(feature = "mmap")]
+impl<Word: BitArray> AsRef<[Word]> for MmapWords<Word> {
+    #[inline(always)]
+    fn as_ref(&self) -> &[Word] {
+        // SAFETY: every bit pattern is a valid `Word` (see `from_mmap`), and `from_mmap`
+        // verified that the memory map is aligned and has no trailing bytes (and neither
+        // can change since the memory map is immutable), so we return the entire map.
+        unsafe { self.mmap.align_to::<Word>().1 }
+    }
+}
+
+/// The memory map can't change its length, so `as_ref` always returns a slice of the
+/// same length.
+#[cfg(feature = "mmap")]
+unsafe impl<Word: BitArray> SafeBuf<Word> for MmapWords<Word> {}
+
+// SEGMENTED BUFFERS ==========================================================
+
+/// A growable buffer of `Word`s that is stored in fixed-size blocks.
+///
+/// A `SegmentedVec<Word>` can be used as a drop-in replacement for a `Vec<Word>` backend,
+/// e.g., as the `Backend` type parameter of an [`AnsCoder`] or a [`RangeEncoder`]. Unlike
+/// a `Vec<Word>`, which has to reallocate and copy its entire contents whenever it runs
+/// out of capacity, a `SegmentedVec` never moves any `Word`s once they've been written.
+/// Instead, it allocates a new block of `block_size` `Word`s whenever the last block is
+/// full. This avoids latency spikes and the temporary doubling of memory consumption when
+/// encoding very long sequences of symbols.
+///
+/// A `SegmentedVec` implements [`WriteWords<Word>`] (appends to the end), [`ReadWords<Word,
+/// Stack>`] (pops off the end), [`BoundedReadWords<Word, Stack>`], [`Pos`], and [`Seek`]
+/// with the same semantics as a `Vec<Word>`. For reading with `Queue` semantics, or for
+/// reading without consuming the data, use a [`SegmentedCursor`] (which entropy coders
+/// create automatically, e.g., in [`RangeEncoder::into_decoder`] or
+/// [`AnsCoder::as_decoder`]).
+///
+/// You can access the compressed data block by block via [`blocks`] (e.g., to write it to
+/// a file without copying it into a contiguous buffer first), or you can flatten it into
+/// a `Vec<Word>` by calling [`into_vec`].
+///
+/// # Example
+///
+/// ```
+/// use constriction::{
+///     backends::SegmentedVec,
+///     stream::{model::DefaultLeakyQuantizer, queue::RangeEncoder, Decode, Encode},
+///     UnwrapInfallible,
+/// };
+///
+/// let quantizer = DefaultLeakyQuantizer::new(-100..=100);
+/// let model = quantizer.quantize(probability::distribution::Gaussian::new(0.0, 10.0));
+///
+/// let backend = SegmentedVec::with_block_size(8);
+/// let mut encoder = RangeEncoder::<u32, u64, _>::with_backend(backend);
+/// encoder.encode_iid_symbols(-100..=100, &model).unwrap();
+/// assert_eq!(encoder.num_words(), 109);
+///
+/// // Decoding works as usual.
+/// let mut decoder = encoder.clone().into_decoder().unwrap();
+/// assert!(decoder.decode_iid_symbols(201, &model).map(Result::unwrap).eq(-100..=100));
+///
+/// // Access the compressed data block by block ...
+/// let compressed = encoder.into_compressed().unwrap_infallible();
+/// let block_lengths = compressed.blocks().map(|block| block.len()).collect::<Vec<_>>();
+/// assert_eq!(block_lengths, [8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 5]);
+///
+/// // ... or flatten it into a single `Vec<u32>`.
+/// let flattened: Vec<u32> = compressed.into_vec();
+/// assert_eq!(flattened.len(), 109);
+/// ```
+///
+/// [`AnsCoder`]: crate::stream::stack::AnsCoder
+/// [`AnsCoder::as_decoder`]: crate::stream::stack::AnsCoder::as_decoder
+/// [`RangeEncoder`]: crate::stream::queue::RangeEncoder
+/// [`RangeEncoder::into_decoder`]: crate::stream::queue::RangeEncoder::into_decoder
+/// [`blocks`]: Self::blocks
+/// [`into_vec`]: Self::into_vec
+#[derive(Debug, PartialEq, Eq)]
+pub struct SegmentedVec<Word> {
+    /// Invariants: `block_size > 0`, all blocks except the last one contain exactly
+    /// `block_size` words, and the last block (if any) is not empty. Each block is
+    /// allocated with capacity `block_size`, so it never reallocates.
+    blocks: Vec<Vec<Word>>,
+
+    block_size: usize,
+}
+
+impl<Word> SegmentedVec<Word> {
+    /// The block size used by [`new`](Self::new) and by the implementation of `Default`.
+    pub const DEFAULT_BLOCK_SIZE: usize = 1 << 14;
+
+    /// Creates an empty `SegmentedVec` with a block size of [`DEFAULT_BLOCK_SIZE`] words.
+    ///
+    /// [`DEFAULT_BLOCK_SIZE`]: Self::DEFAULT_BLOCK_SIZE
+    pub fn new() -> Self {
+        Self::with_block_size(Self::DEFAULT_BLOCK_SIZE)
+    }
+
+    /// Creates an empty `SegmentedVec` that allocates blocks of `block_size` words.
+    ///
+    /// # Panics
+    ///
+    /// If `block_size` is zero.
+    pub fn with_block_size(block_size: usize) -> Self {
+        assert!(block_size != 0);
+        Self {
+            blocks: Vec::new(),
+            block_size,
+        }
+    }
+
+    /// Returns the number of `Word`s per block.
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    /// Returns the total number of `Word`s in the buffer.
+    pub fn len(&self) -> usize {
+        match self.blocks.last() {
+            Some(last) => (self.blocks.len() - 1) * self.block_size + last.len(),
+            None => 0,
+        }
+    }
+
+    /// Returns `true` if the buffer contains no `Word`s.
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    /// Returns a reference to the `Word` at index `index`, or `None` if out of bounds.
+    pub fn get(&self, index: usize) -> Option<&Word> {
+        self.blocks
+            .get(index / self.block_size)?
+            .get(index % self.block_size)
+    }
+
+    /// Iterates over the blocks in order, yielding a slice of `Word`s for each block.
+    ///
+    /// All yielded slices have length [`block_size`](Self::block_size) except for the
+    /// last one, which may be shorter (but never empty).
+    pub fn blocks(&self) -> impl ExactSizeIterator<Item = &[Word]> + '_ {
+        self.blocks.iter().map(|block| &block[..])
+    }
+
+    /// Iterates over all `Word`s in order.
+    pub fn iter(&self) -> impl Iterator<Item = &Word> + '_ {
+        self.blocks.iter().flatten()
+    }
+
+    /// Consumes the `SegmentedVec` and returns an iterator over its blocks.
+    ///
+    /// This allows you to write out (and deallocate) the compressed data block by block.
+    pub fn into_blocks(self) -> impl ExactSizeIterator<Item = Vec<Word>> {
+        self.blocks.into_iter()
+    }
+
+    /// Copies all `Word`s into a single contiguous `Vec<Word>`.
+    pub fn into_vec(self) -> Vec<Word> {
+        let mut flattened = Vec::with_capacity(self.len());
+        for block in self.blocks {
+            flattened.extend(block);
+        }
+        flattened
+    }
+
+    /// Removes all `Word`s from the buffer and deallocates all blocks.
+    pub fn clear(&mut self) {
+        self.blocks.clear()
+    }
+
+    /// Shortens the buffer to `len` `Word`s (no-op if `len >= self.len()`).
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len() {
+            let num_blocks = len.div_ceil(self.block_size);
+            self.blocks.truncate(num_blocks);
+            if let Some(last) = self.blocks.last_mut() {
+                last.truncate(len - (num_blocks - 1) * self.block_size);
+            }
+        }
+    }
+}
+
+impl<Word> Default for SegmentedVec<Word> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Word: Clone> Clone for SegmentedVec<Word> {
+    fn clone(&self) -> Self {
+        // A derived implementation would allocate each block with capacity `block.len()`,
+        // which would violate the invariant that blocks never reallocate.
+        let blocks = self
+            .blocks
+            .iter()
+            .map(|block| {
+                let mut cloned = Vec::with_capacity(self.block_size);
+                cloned.extend_from_slice(block);
+                cloned
+            })
+            .collect();
+
+        Self {
+            blocks,
+            block_size: self.block_size,
+        }
+    }
+}
+
+impl<Word> From<SegmentedVec<Word>> for Vec<Word> {
+    fn from(segmented: SegmentedVec<Word>) -> Self {
+        segmented.into_vec()
+    }
+}
+
+impl<Word> AsRef<SegmentedVec<Word>> for SegmentedVec<Word> {
+    fn as_ref(&self) -> &SegmentedVec<Word> {
+        self
+    }
+}
+
+impl<Word> WriteWords<Word> for SegmentedVec<Word> {
+    /// The only way how writing to a `SegmentedVec` can fail is if a memory allocation
+    /// fails, which is typically treated as a fatal error (i.e., aborts) in Rust.
+    type WriteError = Infallible;
+
+    /// Appends the word to the end of the buffer (= top of the stack).
+    #[inline(always)]
+    fn write(&mut self, word: Word) -> Result<(), Self::WriteError> {
+        match self.blocks.last_mut() {
+            Some(last) if last.len() != self.block_size => last.push(word),
+            _ => {
+                let mut block = Vec::with_capacity(self.block_size);
+                block.push(word);
+                self.blocks.push(block);
+            }
+        }
+        Ok(())
+    }
+
+    fn maybe_full(&self) -> bool {
+        false
+    }
+}
+
+impl<Word> ReadWords<Word, Stack> for SegmentedVec<Word> {
+    /// The only way how reading from a `SegmentedVec` can fail is if the buffer is empty,
+    /// but that's not considered an error (it returns `Ok(None)` instead).
+    type ReadError = Infallible;
+
+    /// Pops the word off the end of the buffer (= top of the stack), and deallocates the
+    /// last block if it becomes empty.
+    #[inline(always)]
+    fn read(&mut self) -> Result<Option<Word>, Self::ReadError> {
+        if let Some(last) = self.blocks.last_mut() {
+            let word = last.pop();
+            if last.is_empty() {
+                self.blocks.pop();
+            }
+            Ok(word)
+        } else {
+            Ok(None)
+        }
+    }
+
+    #[inline(always)]
+    fn maybe_exhausted(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+impl<Word> BoundedReadWords<Word, Stack> for SegmentedVec<Word> {
+    #[inline(always)]
+    fn remaining(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<Word> PosSeek for SegmentedVec<Word> {
+    type Position = usize;
+}
+
+impl<Word> Pos for SegmentedVec<Word> {
+    /// Returns the length of the buffer since that's the current read and write position
+    /// (`SegmentedVec`s have [`Stack`] semantics, just like `Vec`s).
+    fn pos(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<Word> Seek for SegmentedVec<Word> {
+    /// Seeking in a `SegmentedVec` only succeeds if the provided position `pos` is smaller
+    /// than or equal to its current length, in which case the buffer gets truncated to
+    /// length `pos` (analogous to [seeking in a `Vec`](Vec#impl-Seek)).
+    fn seek(&mut self, pos: usize) -> Result<(), ()> {
+        if pos <= self.len() {
+            self.truncate(pos);
+            Ok(())
+        } else {
+            Err(())
+        }
+    }
+}
+
+impl<Word: Clone> IntoReadWords<Word, Stack> for SegmentedVec<Word> {
+    type IntoReadWords = SegmentedCursor<Word, SegmentedVec<Word>>;
+
+    fn into_read_words(self) -> Self::IntoReadWords {
+        SegmentedCursor::new_at_write_end(self)
+    }
+}
+
+impl<Word: Clone> IntoReadWords<Word, Queue> for SegmentedVec<Word> {
+    type IntoReadWords = SegmentedCursor<Word, SegmentedVec<Word>>;
+
+    fn into_read_words(self) -> Self::IntoReadWords {
+        SegmentedCursor::new_at_write_beginning(self)
+    }
+}
+
+impl<'a, Word: Clone + 'a> AsReadWords<'a, Word, Stack> for SegmentedVec<Word> {
+    type AsReadWords = SegmentedCursor<Word, &'a SegmentedVec<Word>>;
+
+    fn as_read_words(&'a self) -> Self::AsReadWords {
+        SegmentedCursor::new_at_write_end(self)
+    }
+}
+
+impl<'a, Word: Clone + 'a> AsReadWords<'a, Word, Queue> for SegmentedVec<Word> {
+    type AsReadWords = SegmentedCursor<Word, &'a SegmentedVec<Word>>;
+
+    fn as_read_words(&'a self) -> Self::AsReadWords {
+        SegmentedCursor::new_at_write_beginning(self)
+    }
+}
+
+impl<Word, S: Semantics> IntoSeekReadWords<Word, S> for SegmentedVec<Word>
+where
+    Self: IntoReadWords<Word, S, IntoReadWords = SegmentedCursor<Word, SegmentedVec<Word>>>,
+    SegmentedCursor<Word, SegmentedVec<Word>>: ReadWords<Word, S> + Seek,
+{
+    type IntoSeekReadWords = SegmentedCursor<Word, SegmentedVec<Word>>;
+
+    fn into_seek_read_words(self) -> Self::IntoSeekReadWords {
+        self.into_read_words()
+    }
+}
+
+/// Read-only cursor into a [`SegmentedVec`].
+///
+/// This is the counterpart of a [`Cursor`] for a `SegmentedVec`: it reads `Word`s without
+/// consuming them, and it implements [`ReadWords`] for both [`Queue`] semantics (advances
+/// towards the end) and [`Stack`] semantics (moves towards the beginning), as well as
+/// [`Pos`] and [`Seek`]. The type parameter `Buf` is either an owned `SegmentedVec<Word>`
+/// or a reference `&SegmentedVec<Word>`.
+///
+/// You usually don't have to create a `SegmentedCursor` manually since entropy coders do
+/// this automatically when you turn an encoder with a `SegmentedVec` backend into a
+/// decoder. See [`SegmentedVec`] for an example.
+#[derive(Clone, Debug)]
+pub struct SegmentedCursor<Word, Buf> {
+    buf: Buf,
+
+    /// Invariant: `pos <= buf.as_ref().len()`.
+    pos: usize,
+
+    phantom: PhantomData<Word>,
+}
+
+impl<Word, Buf: AsRef<SegmentedVec<Word>>> SegmentedCursor<Word, Buf> {
+    /// Creates a `SegmentedCursor` that points to the beginning of the buffer, i.e., that
+    /// is ready for reading with [`Queue`] semantics.
+    pub fn new_at_write_beginning(buf: Buf) -> Self {
+        Self {
+            buf,
+            pos: 0,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Creates a `SegmentedCursor` that points to the end of the buffer, i.e., that is
+    /// ready for reading with [`Stack`] semantics.
+    pub fn new_at_write_end(buf: Buf) -> Self {
+        let pos = buf.as_ref().len();
+        Self {
+            buf,
+            pos,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the generic buffer that the `SegmentedCursor` reads from.
+    pub fn buf(&self) -> &Buf {
+        &self.buf
+    }
+
+    /// Consumes the `SegmentedCursor` and returns the buffer and the current position.
+    pub fn into_buf_and_pos(self) -> (Buf, usize) {
+        (self.buf, self.pos)
+    }
+}
+
+impl<Word: Clone, Buf: AsRef<SegmentedVec<Word>>> ReadWords<Word, Stack>
+    for SegmentedCursor<Word, Buf>
+{
+    type ReadError = Infallible;
+
+    #[inline(always)]
+    fn read(&mut self) -> Result<Option<Word>, Self::ReadError> {
+        if self.pos == 0 {
+            Ok(None)
+        } else {
+            self.pos -= 1;
+            Ok(self.buf.as_ref().get(self.pos).cloned())
+        }
+    }
+
+    #[inline(always)]
+    fn maybe_exhausted(&self) -> bool {
+        BoundedReadWords::<Word, Stack>::is_exhausted(self)
+    }
+}
+
+impl<Word: Clone, Buf: AsRef<SegmentedVec<Word>>> ReadWords<Word, Queue>
+    for SegmentedCursor<Word, Buf>
+{
+    type ReadError = Infallible;
+
+    #[inline(always)]
+    fn read(&mut self) -> Result<Option<Word>, Self::ReadError> {
+        let maybe_word = self.buf.as_ref().get(self.pos).cloned();
+        if maybe_word.is_some() {
+            self.pos += 1;
+        }
+        Ok(maybe_word)
+    }
+
+    #[inline(always)]
+    fn maybe_exhausted(&self) -> bool {
+        BoundedReadWords::<Word, Queue>::is_exhausted(self)
+    }
+}
+
+impl<Word: Clone, Buf: AsRef<SegmentedVec<Word>>> BoundedReadWords<Word, Stack>
+    for SegmentedCursor<Word, Buf>
+{
+    #[inline(always)]
+    fn remaining(&self) -> usize {
+        self.pos
+    }
+}
+
+impl<Word: Clone, Buf: AsRef<SegmentedVec<Word>>> BoundedReadWords<Word, Queue>
+    for SegmentedCursor<Word, Buf>
+{
+    #[inline(always)]
+    fn remaining(&self) -> usize {
+        self.buf.as_ref().len() - self.pos
+    }
+}
+
+impl<Word, Buf> PosSeek for SegmentedCursor<Word, Buf> {
+    type Position = usize;
+}
+
+impl<Word, Buf: AsRef<SegmentedVec<Word>>> Pos for SegmentedCursor<Word, Buf> {
+    #[inline(always)]
+    fn pos(&self) -> usize {
+        self.pos
+    }
+}
+
+impl<Word, Buf: AsRef<SegmentedVec<Word>>> Seek for SegmentedCursor<Word, Buf> {
+    #[inline(always)]
+    fn seek(&mut self, pos: usize) -> Result<(), ()> {
+        if pos > self.buf.as_ref().len() {
+            Err(())
+        } else {
+            self.pos = pos;
+            Ok(())
+        }
+    }
+}
+
+// FRAMING OF MANY SMALL STREAMS ==============================================
+
+/// A buffer that concatenates many independent streams of compressed data ("frames").
+///
+/// If you compress many small records independently (e.g., with one [`RangeEncoder`] per
+/// record), then storing each record in its own `Vec` is wasteful, and simply
+/// concatenating the compressed data loses the information where one record ends and the
+/// next one starts. A `FrameWriter` stores all frames in a single `Vec<Word>`, and it
+/// prefixes each frame with its length (in units of `Word`s). The length prefix is a
+/// variable length integer that occupies a single `Word` unless the frame is long (more
+/// precisely, each `Word` of the length prefix holds `Word::BITS - 1` bits of the length,
+/// and its most significant bit indicates whether another `Word` follows). Thus, for
+/// `Word = u8`, the length prefix is a LEB128 encoded integer.
+///
+/// There are two ways to add a frame:
+/// - call [`frame_sink`](Self::frame_sink) to obtain a [`FrameSink`], which is a data sink
+///   that you can use as the backend of an entropy coder; the frame gets completed when
+///   the `FrameSink` is dropped (or when you call [`FrameSink::finish`]); or
+/// - call [`push_frame`](Self::push_frame) to copy an existing sequence of `Word`s into a
+///   new frame.
+///
+/// Read the frames back with a [`FrameReader`] (see [`reader`](Self::reader)), which hands
+/// out decoders that borrow from the buffer without copying, and which can skip over frames
+/// without decoding them.
+///
+/// # Example
+///
+/// ```
+/// use constriction::{
+///     backends::{FrameReader, FrameWriter},
+///     stream::{model::DefaultLeakyQuantizer, queue::DefaultRangeEncoder, Decode, Encode},
+/// };
+///
+/// let quantizer = DefaultLeakyQuantizer::new(-100..=100);
+/// let model = quantizer.quantize(probability::distribution::Gaussian::new(0.0, 10.0));
+/// let records = (0..100).map(|i| vec![i % 7, -3, i % 5]).collect::<Vec<_>>();
+///
+/// // Compress each record with its own `RangeEncoder` into a shared buffer.
+/// let mut frames = FrameWriter::new();
+/// for record in &records {
+///     let mut encoder = DefaultRangeEncoder::with_backend(frames.frame_sink());
+///     encoder.encode_iid_symbols(record, &model).unwrap();
+///     encoder.into_compressed().unwrap().finish();
+/// }
+/// assert_eq!(frames.num_frames(), 100);
+/// let compressed: Vec<u32> = frames.into_vec();
+///
+/// // Skip the first 42 frames without decoding them, then decode the next one.
+/// let mut reader = FrameReader::new(&compressed[..]);
+/// reader.skip_frames(42).unwrap();
+/// let mut decoder = reader.next_range_decoder::<u64>().unwrap().unwrap();
+/// let decoded = decoder.decode_iid_symbols(3, &model).collect::<Result<Vec<_>, _>>();
+/// assert_eq!(decoded.unwrap(), records[42]);
+/// ```
+///
+/// [`RangeEncoder`]: crate::stream::queue::RangeEncoder
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FrameWriter<Word> {
+    buf: Vec<Word>,
+    num_frames: usize,
+}
+
+impl<Word: BitArray> FrameWriter<Word> {
+    /// Creates an empty `FrameWriter`.
+    pub fn new() -> Self {
+        Self {
+            buf: Vec::new(),
+            num_frames: 0,
+        }
+    }
+
+    /// Returns a data sink that appends a new frame.
+    ///
+    /// The frame is completed (i.e., its length prefix is written) when the returned
+    /// `FrameSink` is dropped. The `FrameSink` implements [`WriteWords`] and also
+    /// [`ReadWords<Word, Stack>`](ReadWords) (restricted to the words of the new frame), so
+    /// you can use it as the backend of both a [`RangeEncoder`] and an [`AnsCoder`].
+    ///
+    /// [`RangeEncoder`]: crate::stream::queue::RangeEncoder
+    /// [`AnsCoder`]: crate::stream::stack::AnsCoder
+    pub fn frame_sink(&mut self) -> FrameSink<'_, Word> {
+        let start = self.buf.len();
+        // Placeholder for the length prefix, which we'll overwrite once the frame is done.
+        self.buf.push(Word::zero());
+        FrameSink {
+            frames: self,
+            start,
+        }
+    }
+
+    /// Appends a new frame with a copy of the provided `Word`s.
+    pub fn push_frame(&mut self, frame: &[Word]) {
+        self.push_length(frame.len());
+        self.buf.extend_from_slice(frame);
+        self.num_frames += 1;
+    }
+
+    /// Returns the number of frames in the buffer.
+    pub fn num_frames(&self) -> usize {
+        self.num_frames
+    }
+
+    /// Returns the concatenation of all frames, including their length prefixes.
+    pub fn as_slice(&self) -> &[Word] {
+        &self.buf
+    }
+
+    /// Consumes the `FrameWriter` and returns the concatenation of all frames, including
+    /// their length prefixes.
+    pub fn into_vec(self) -> Vec<Word> {
+        self.buf
+    }
+
+    /// Returns a [`FrameReader`] that reads the frames written so far.
+    pub fn reader(&self) -> FrameReader<'_, Word> {
+        FrameReader::new(&self.buf)
+    }
+
+    fn push_length(&mut self, len: usize) {
+        let mut words = SmallVec::<[Word; 4]>::new();
+        encode_frame_length(len, |word| words.push(word));
+        self.buf.extend_from_slice(&words);
+    }
+}
+
+/// Data sink that writes a new frame to a [`FrameWriter`].
+///
+/// See [`FrameWriter::frame_sink`].
+#[derive(Debug)]
+pub struct FrameSink<'a, Word: BitArray> {
+    frames: &'a mut FrameWriter<Word>,
+
+    /// Index of the placeholder for the length prefix in `frames.buf`.
+    start: usize,
+}
+
+impl<Word: BitArray> FrameSink<'_, Word> {
+    /// Returns the number of `Word`s in the frame so far.
+    pub fn len(&self) -> usize {
+        self.frames.buf.len() - self.start - 1
+    }
+
+    /// Returns `true` if no `Word`s have been written to the frame yet.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Completes the frame. This is equivalent to dropping the `FrameSink`.
+    pub fn finish(self) {}
+}
+
+impl<Word: BitArray> Drop for FrameSink<'_, Word> {
+    fn drop(&mut self) {
+        let len = self.len();
+        let mut words = SmallVec::<[Word; 4]>::new();
+        encode_frame_length(len, |word| words.push(word));
+        self.frames.buf[self.start] = words[0];
+        if words.len() > 1 {
+            // Only happens for long frames (or small `Word` types): shift the frame data.
+            let index = self.start + 1;
+            self.frames
+                .buf
+                .splice(index..index, words[1..].iter().cloned());
+        }
+        self.frames.num_frames += 1;
+    }
+}
+
+impl<Word: BitArray> WriteWords<Word> for FrameSink<'_, Word> {
+    type WriteError = Infallible;
+
+    #[inline(always)]
+    fn write(&mut self, word: Word) -> Result<(), Self::WriteError> {
+        self.frames.buf.push(word);
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn extend_from_iter(
+        &mut self,
+        iter: impl Iterator<Item = Word>,
+    ) -> Result<(), Self::WriteError> {
+        self.frames.buf.extend(iter);
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn maybe_full(&self) -> bool {
+        false
+    }
+}
+
+impl<Word: BitArray> ReadWords<Word, Stack> for FrameSink<'_, Word> {
+    type ReadError = Infallible;
+
+    #[inline]
+    fn read(&mut self) -> Result<Option<Word>, Self::ReadError> {
+        if self.is_empty() {
+            Ok(None)
+        } else {
+            Ok(self.frames.buf.pop())
+        }
+    }
+
+    #[inline(always)]
+    fn maybe_exhausted(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+impl<Word: BitArray> BoundedReadWords<Word, Stack> for FrameSink<'_, Word> {
+    #[inline(always)]
+    fn remaining(&self) -> usize {
+        self.len()
+    }
+}
+
+/// Reads the frames written by a [`FrameWriter`].
+///
+/// A `FrameReader` borrows the buffer of frames and hands out each frame as a slice into
+/// this buffer, without copying. You can either obtain the raw slices (`FrameReader`
+/// implements [`Iterator`]), or you can directly obtain a decoder that reads from the next
+/// frame (see [`next_range_decoder`] and [`next_ans_decoder`]). Frames can be skipped
+/// cheaply with [`skip_frames`] since skipping only has to read the length prefixes.
+///
+/// See [`FrameWriter`] for an example.
+///
+/// [`next_range_decoder`]: Self::next_range_decoder
+/// [`next_ans_decoder`]: Self::next_ans_decoder
+/// [`skip_frames`]: Self::skip_frames
+#[derive(Clone, Debug)]
+pub struct FrameReader<'a, Word> {
+    buf: &'a [Word],
+    pos: usize,
+}
+
+impl<'a, Word: BitArray> FrameReader<'a, Word> {
+    /// Creates a `FrameReader` that starts reading at the beginning of `buf`.
+    pub fn new(buf: &'a [Word]) -> Self {
+        Self { buf, pos: 0 }
+    }
+
+    /// Returns the current read position (in units of `Word`s), which is always at the
+    /// beginning of a length prefix or at the end of the buffer.
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    /// Returns `true` if there are no frames left to read.
+    pub fn is_exhausted(&self) -> bool {
+        self.pos == self.buf.len()
+    }
+
+    /// Returns the next frame as a slice (without its length prefix), or `Ok(None)` if
+    /// there are no frames left.
+    pub fn next_frame(&mut self) -> Result<Option<&'a [Word]>, FrameReadError> {
+        if self.is_exhausted() {
+            return Ok(None);
+        }
+
+        let mut len = 0usize;
+        let mut shift = 0;
+        let payload_bits = Word::BITS - 1;
+        let payload_mask = Word::max_value() >> 1;
+        loop {
+            let word = *self.buf.get(self.pos).ok_or(FrameReadError::Truncated)?;
+            self.pos += 1;
+            let payload = (word & payload_mask)
+                .to_usize()
+                .filter(|&payload| {
+                    shift < usize::BITS as usize && (payload << shift) >> shift == payload
+                })
+                .ok_or(FrameReadError::LengthOverflow)?;
+            len |= payload << shift;
+            if word >> payload_bits == Word::zero() {
+                break;
+            }
+            shift += payload_bits;
+        }
+
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|&end| end <= self.buf.len())
+            .ok_or(FrameReadError::Truncated)?;
+        let frame = &self.buf[self.pos..end];
+        self.pos = end;
+        Ok(Some(frame))
+    }
+
+    /// Skips over the next `amt` frames without decoding them.
+    ///
+    /// Returns `Err(FrameReadError::Truncated)` if there are fewer than `amt` frames left.
+    pub fn skip_frames(&mut self, amt: usize) -> Result<(), FrameReadError> {
+        for _ in 0..amt {
+            self.next_frame()?.ok_or(FrameReadError::Truncated)?;
+        }
+        Ok(())
+    }
+
+    /// Returns a [`RangeDecoder`] that reads from the next frame without copying it, or
+    /// `Ok(None)` if there are no frames left.
+    ///
+    /// [`RangeDecoder`]: crate::stream::queue::RangeDecoder
+    #[allow(clippy::type_complexity)]
+    pub fn next_range_decoder<State>(
+        &mut self,
+    ) -> Result<
+        Option<crate::stream::queue::RangeDecoder<Word, State, Cursor<Word, &'a [Word]>>>,
+        FrameReadError,
+    >
+    where
+        Word: Into<State>,
+        State: BitArray + AsPrimitive<Word>,
+    {
+        Ok(self.next_frame()?.map(|frame| {
+            crate::stream::queue::RangeDecoder::from_compressed(frame)
+                .unwrap_or_else(|never| match never {})
+        }))
+    }
+
+    /// Returns an [`AnsCoder`] that decodes from the next frame without copying it, or
+    /// `Ok(None)` if there are no frames left.
+    ///
+    /// Returns `Err(FrameReadError::InvalidFrame)` if the frame ends in a zero word, which
+    /// is never the case for data written by an `AnsCoder` (see
+    /// [`AnsCoder::from_compressed`]).
+    ///
+    /// [`AnsCoder`]: crate::stream::stack::AnsCoder
+    /// [`AnsCoder::from_compressed`]: crate::stream::stack::AnsCoder::from_compressed
+    #[allow(clippy::type_complexity)]
+    pub fn next_ans_decoder<State>(
+        &mut self,
+    ) -> Result<
+        Option<crate::stream::stack::AnsCoder<Word, State, Cursor<Word, &'a [Word]>>>,
+        FrameReadError,
+    >
+    where
+        Word: Into<State>,
+        State: BitArray + AsPrimitive<Word>,
+    {
+        self.next_frame()?
+            .map(|frame| {
+                crate::stream::stack::AnsCoder::from_compressed_slice(frame)
+                    .map_err(|()| FrameReadError::InvalidFrame)
+            })
+            .transpose()
+    }
+}
+
+impl<'a, Word: BitArray> Iterator for FrameReader<'a, Word> {
+    type Item = Result<&'a [Word], FrameReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_frame().transpose()
+    }
+}
+
+/// Error type for [`FrameReader`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameReadError {
+    /// The buffer ends in the middle of a length prefix or of a frame.
+    Truncated,
+
+    /// A length prefix doesn't fit into a `usize`.
+    LengthOverflow,
+
+    /// The frame is not valid compressed data for the requested entropy coder.
+    InvalidFrame,
+}
+
+impl Display for FrameReadError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Truncated => write!(f, "Buffer of frames is truncated."),
+            Self::LengthOverflow => write!(f, "Length of frame is too large."),
+            Self::InvalidFrame => write!(f, "Frame contains invalid compressed data."),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FrameReadError {}
+
+fn encode_frame_length<Word: BitArray>(mut len: usize, mut write: impl FnMut(Word)) {
+    let payload_bits = Word::BITS - 1;
+    let payload_mask = Word::max_value() >> 1;
+    let usize_mask = payload_mask.to_usize().unwrap_or(usize::MAX);
+    loop {
+        let payload = Word::from(len & usize_mask).expect("fits");
+        len = len.checked_shr(payload_bits as u32).unwrap_or(0);
+        if len == 0 {
+            write(payload);
+            return;
+        }
+        write(payload | (Word::one() << payload_bits));
+    }
+}
+
+// INTEGRITY CHECKS ===========================================================
+
+/// Adapter that interleaves checksums into a data sink of compressed data.
+///
+/// Wraps an arbitrary [`WriteWords`] backend and writes out an additional checksum `Word`
+/// after every `block_size` `Word`s written to it. Use a [`ChecksumReadWords`] with the
+/// same `block_size` to read the data back in, either with [`Queue`] or with [`Stack`]
+/// semantics. The `ChecksumReadWords` verifies each block before it hands out any of the
+/// block's `Word`s, so damaged or truncated data is reported as a
+/// [`ChecksumReadError`] at the first affected block instead of being silently decoded
+/// into garbage.
+///
+/// You must call [`finish`] once you're done writing. This writes out a checksum for the
+/// last (possibly empty) partial block, followed by a single `Word` that holds the length
+/// of this partial block. Thus, the length of the written data is always
+/// `num_words + num_words / block_size + 2`, where `num_words` is the number of `Word`s
+/// that the client wrote. The trailer allows readers to parse the data from either end.
+///
+/// Checksums are a 64-bit hash of the block, truncated to the size of a `Word`, so they
+/// get weaker for smaller `Word` types. They detect accidental corruption but they
+/// are not a cryptographic integrity check.
+///
+/// # Example
+///
+/// ```
+/// use constriction::{
+///     backends::{ChecksumReadError, ChecksumReadWords, ChecksumWriteWords, Cursor},
+///     stream::{model::DefaultLeakyQuantizer, queue::RangeEncoder, queue::RangeDecoder},
+///     stream::{Decode, Encode},
+///     CoderError,
+/// };
+///
+/// let quantizer = DefaultLeakyQuantizer::new(-100..=100);
+/// let model = quantizer.quantize(probability::distribution::Gaussian::new(0.0, 30.0));
+/// let symbols = (-50..50).collect::<Vec<i32>>();
+///
+/// // Encode through a `ChecksumWriteWords` that adds a checksum after every 4 words.
+/// let mut encoder = RangeEncoder::<u32, u64, _>::with_backend(
+///     ChecksumWriteWords::new(Vec::new(), 4),
+/// );
+/// encoder.encode_iid_symbols(&symbols, &model).unwrap();
+/// let mut compressed = encoder.into_compressed().unwrap().finish().unwrap();
+///
+/// // Decoding through a `ChecksumReadWords` verifies the checksums on the fly.
+/// let backend = ChecksumReadWords::new(Cursor::new_at_write_beginning(&compressed[..]), 4);
+/// let mut decoder = RangeDecoder::<u32, u64, _>::with_backend(backend).unwrap();
+/// let decoded = decoder.decode_iid_symbols(100, &model).collect::<Result<Vec<_>, _>>();
+/// assert_eq!(decoded.unwrap(), symbols);
+///
+/// // Flip a bit in the third block (i.e., in block number 2 if we count from zero).
+/// compressed[12] ^= 1 << 7;
+/// let backend = ChecksumReadWords::new(Cursor::new_at_write_beginning(&compressed[..]), 4);
+/// let mut decoder = RangeDecoder::<u32, u64, _>::with_backend(backend).unwrap();
+/// let err = decoder.decode_iid_symbols(100, &model).find_map(Result::err).unwrap();
+/// assert!(matches!(err, CoderError::Backend(ChecksumReadError::ChecksumMismatch { block: 2 })));
+/// ```
+///
+/// [`finish`]: Self::finish
+#[derive(Clone, Debug)]
+pub struct ChecksumWriteWords<Word, Backend> {
+    inner: Backend,
+    block_size: usize,
+    num_pending: usize,
+    checksum: BlockChecksum,
+    phantom: PhantomData<Word>,
+}
+
+impl<Word, Backend> ChecksumWriteWords<Word, Backend>
+where
+    Word: BitArray + AsPrimitive<u64>,
+    u64: AsPrimitive<Word>,
+    Backend: WriteWords<Word>,
+{
+    /// Wraps `inner` in an adapter that writes a checksum after every `block_size` `Word`s.
+    ///
+    /// # Panics
+    ///
+    /// If `block_size` is zero, or if `block_size - 1` cannot be represented by a `Word`
+    /// (e.g., if `block_size > 256` for `Word = u8`).
+    pub fn new(inner: Backend, block_size: usize) -> Self {
+        assert_valid_block_size::<Word>(block_size);
+        Self {
+            inner,
+            block_size,
+            num_pending: 0,
+            checksum: BlockChecksum::new(),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns the number of `Word`s that are covered by each checksum.
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    /// Returns a reference to the wrapped backend.
+    ///
+    /// The wrapped backend is missing the trailer until you call [`finish`].
+    ///
+    /// [`finish`]: Self::finish
+    pub fn inner(&self) -> &Backend {
+        &self.inner
+    }
+
+    /// Writes the checksum and the length of the final partial block and returns the
+    /// wrapped backend.
+    ///
+    /// Data that was written without calling `finish` cannot be read back by a
+    /// [`ChecksumReadWords`].
+    pub fn finish(mut self) -> Result<Backend, Backend::WriteError> {
+        let checksum = self.checksum.finish(Some(self.num_pending));
+        self.inner.write(checksum)?;
+        self.inner.write((self.num_pending as u64).as_())?;
+        Ok(self.inner)
+    }
+}
+
+impl<Word, Backend> WriteWords<Word> for ChecksumWriteWords<Word, Backend>
+where
+    Word: BitArray + AsPrimitive<u64>,
+    u64: AsPrimitive<Word>,
+    Backend: WriteWords<Word>,
+{
+    type WriteError = Backend::WriteError;
+
+    fn write(&mut self, word: Word) -> Result<(), Self::WriteError> {
+        self.inner.write(word)?;
+        self.checksum.update(word.as_());
+        self.num_pending += 1;
+        if self.num_pending == self.block_size {
+            let checksum = core::mem::replace(&mut self.checksum, BlockChecksum::new());
+            self.num_pending = 0;
+            self.inner.write(checksum.finish(None))?;
+        }
+        Ok(())
+    }
+}
+
+/// Adapter that verifies the checksums written by a [`ChecksumWriteWords`].
+///
+/// Wraps a [`ReadWords`] backend that holds data which was written by a
+/// [`ChecksumWriteWords`] with the same `block_size`. Implements `ReadWords<Word, Queue>`
+/// if the wrapped backend does, in which case the data is parsed from its beginning, and
+/// `ReadWords<Word, Stack>` if the wrapped backend does, in which case the data is parsed
+/// from its end. Thus, you can wrap, e.g., a [`Cursor`], a [`Reverse`], a `Vec`, or one of
+/// the iterator adapters [`FallibleIteratorReadWords`] and [`InfallibleIteratorReadWords`].
+///
+/// The adapter reads an entire block from the wrapped backend and verifies its checksum
+/// before it returns the first `Word` of the block. It then serves the remaining `Word`s
+/// of the block from an internal buffer. If verification fails then `read` returns an
+/// error that identifies the damaged block (counting blocks in reading order, starting at
+/// zero). The adapter should not be used any further after it returned an error.
+///
+/// See [`ChecksumWriteWords`] for an example.
+#[derive(Clone, Debug)]
+pub struct ChecksumReadWords<Word, Backend> {
+    inner: Backend,
+    block_size: usize,
+
+    /// The verified but not yet read `Word`s of the current block, in reverse reading
+    /// order (so that we can `pop` from it).
+    buf: Vec<Word>,
+
+    /// Only used for `Queue` semantics, where we have to look ahead by one `Word` to
+    /// detect the final block.
+    lookahead: Option<Word>,
+
+    num_blocks: usize,
+    finished: bool,
+}
+
+impl<Word, Backend> ChecksumReadWords<Word, Backend>
+where
+    Word: BitArray + AsPrimitive<u64>,
+    u64: AsPrimitive<Word>,
+{
+    /// Wraps `inner` in an adapter that verifies checksums after every `block_size`
+    /// `Word`s.
+    ///
+    /// # Panics
+    ///
+    /// Under the same conditions as [`ChecksumWriteWords::new`].
+    pub fn new(inner: Backend, block_size: usize) -> Self {
+        assert_valid_block_size::<Word>(block_size);
+        Self {
+            inner,
+            block_size,
+            buf: Vec::with_capacity(block_size + 2),
+            lookahead: None,
+            num_blocks: 0,
+            finished: false,
+        }
+    }
+
+    /// Returns the number of `Word`s that are covered by each checksum.
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    /// Returns the number of blocks whose checksums have been verified so far.
+    pub fn num_verified_blocks(&self) -> usize {
+        self.num_blocks
+    }
+
+    /// Consumes the adapter and returns the wrapped backend.
+    ///
+    /// Any `Word`s that were already read from the wrapped backend but not yet from the
+    /// adapter are lost.
+    pub fn into_inner(self) -> Backend {
+        self.inner
+    }
+
+    /// Verifies the `Word`s in `self.buf`, which must be in writing order, against
+    /// `checksum`.
+    fn verify_block(
+        &mut self,
+        checksum: Word,
+        final_len: Option<usize>,
+    ) -> Result<(), ChecksumReadError<()>> {
+        let mut expected = BlockChecksum::new();
+        for &word in &self.buf {
+            expected.update(word.as_());
+        }
+        if expected.finish::<Word>(final_len) != checksum {
+            self.buf.clear();
+            return Err(ChecksumReadError::ChecksumMismatch {
+                block: self.num_blocks,
+            });
+        }
+
+        self.num_blocks += 1;
+        Ok(())
+    }
+
+    fn truncated<E>(&mut self) -> ChecksumReadError<E> {
+        self.buf.clear();
+        ChecksumReadError::Truncated {
+            block: self.num_blocks,
+        }
+    }
+}
+
+impl<Word, Backend> ReadWords<Word, Queue> for ChecksumReadWords<Word, Backend>
+where
+    Word: BitArray + AsPrimitive<u64>,
+    u64: AsPrimitive<Word>,
+    Backend: ReadWords<Word, Queue>,
+{
+    type ReadError = ChecksumReadError<Backend::ReadError>;
+
+    fn read(&mut self) -> Result<Option<Word>, Self::ReadError> {
+        if let Some(word) = self.buf.pop() {
+            return Ok(Some(word));
+        } else if self.finished {
+            return Ok(None);
+        }
+
+        // Read the next block's data, its checksum, and one more `Word`. If the latter
+        // exists then we're not in the final block, which would end with a length `Word`.
+        self.buf.extend(self.lookahead.take());
+        while self.buf.len() < self.block_size + 2 {
+            match self.inner.read().map_err(ChecksumReadError::Backend)? {
+                Some(word) => self.buf.push(word),
+                None => break,
+            }
+        }
+
+        if self.buf.len() == self.block_size + 2 {
+            self.lookahead = self.buf.pop();
+            let checksum = self.buf.pop().expect("block_size + 1 > 0");
+            self.verify_block(checksum, None)
+                .map_err(ChecksumReadError::cast)?;
+        } else {
+            self.finished = true;
+            let (len_word, checksum) = match (self.buf.pop(), self.buf.pop()) {
+                (Some(len_word), Some(checksum)) => (len_word, checksum),
+                _ => return Err(self.truncated()),
+            };
+            if len_word.as_() != self.buf.len() as u64 {
+                self.buf.clear();
+                return Err(ChecksumReadError::ChecksumMismatch {
+                    block: self.num_blocks,
+                });
+            }
+            self.verify_block(checksum, Some(self.buf.len()))
+                .map_err(ChecksumReadError::cast)?;
+        }
+
+        self.buf.reverse();
+        Ok(self.buf.pop())
+    }
+
+    #[inline(always)]
+    fn maybe_exhausted(&self) -> bool {
+        self.buf.is_empty()
+    }
+}
+
+impl<Word, Backend> ReadWords<Word, Stack> for ChecksumReadWords<Word, Backend>
+where
+    Word: BitArray + AsPrimitive<u64>,
+    u64: AsPrimitive<Word>,
+    Backend: ReadWords<Word, Stack>,
+{
+    type ReadError = ChecksumReadError<Backend::ReadError>;
+
+    fn read(&mut self) -> Result<Option<Word>, Self::ReadError> {
+        if let Some(word) = self.buf.pop() {
+            return Ok(Some(word));
+        } else if self.finished {
+            return Ok(None);
+        }
+
+        // The first block we encounter is the final block, which ends in its length.
+        let final_len = if self.num_blocks == 0 {
+            let len_word = self
+                .inner
+                .read()
+                .map_err(ChecksumReadError::Backend)?
+                .ok_or_else(|| self.truncated())?;
+            let len = len_word.as_();
+            if len >= self.block_size as u64 {
+                return Err(ChecksumReadError::ChecksumMismatch { block: 0 });
+            }
+            Some(len as usize)
+        } else {
+            None
+        };
+
+        let checksum = match self.inner.read().map_err(ChecksumReadError::Backend)? {
+            Some(checksum) => checksum,
+            None if final_len.is_none() => {
+                self.finished = true;
+                return Ok(None);
+            }
+            None => return Err(self.truncated()),
+        };
+
+        for _ in 0..final_len.unwrap_or(self.block_size) {
+            match self.inner.read().map_err(ChecksumReadError::Backend)? {
+                Some(word) => self.buf.push(word),
+                None => return Err(self.truncated()),
+            }
+        }
+
+        // We read backwards, so reversing `self.buf` brings it into writing order, which
+        // is what `verify_block` expects and also what we need for reading with `pop`.
+        self.buf.reverse();
+        self.verify_block(checksum, final_len)
+            .map_err(ChecksumReadError::cast)?;
+
+        match self.buf.pop() {
+            Some(word) => Ok(Some(word)),
+            // An empty final block; continue with the preceding block (if any).
+            None => self.read(),
+        }
+    }
+
+    #[inline(always)]
+    fn maybe_exhausted(&self) -> bool {
+        self.buf.is_empty()
+    }
+}
+
+/// Error type for [`ChecksumReadWords`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChecksumReadError<ReadError> {
+    /// The checksum of the block with the provided index (counted in reading order,
+    /// starting at zero) doesn't match its content.
+    ChecksumMismatch { block: usize },
+
+    /// The data ended in the middle of the block with the provided index (counted in
+    /// reading order, starting at zero).
+    Truncated { block: usize },
+
+    /// The wrapped backend returned an error.
+    Backend(ReadError),
+}
+
+impl ChecksumReadError<()> {
+    fn cast<E>(self) -> ChecksumReadError<E> {
+        match self {
+            Self::ChecksumMismatch { block } => ChecksumReadError::ChecksumMismatch { block },
+            Self::Truncated { block } => ChecksumReadError::Truncated { block },
+            Self::Backend(()) => unreachable!(),
+        }
+    }
+}
+
+impl<ReadError: Display> Display for ChecksumReadError<ReadError> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::ChecksumMismatch { block } => {
+                write!(f, "Checksum mismatch in block {block} of compressed data.")
+            }
+            Self::Truncated { block } => {
+                write!(f, "Compressed data is truncated in block {block}.")
+            }
+            Self::Backend(err) => write!(f, "Error while reading compressed data: {err}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<ReadError: std::error::Error + 'static> std::error::Error for ChecksumReadError<ReadError> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Backend(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+fn assert_valid_block_size<Word: BitArray>(block_size: usize) {
+    assert!(block_size != 0);
+    assert!(Word::BITS >= 64 || ((block_size - 1) as u64) >> Word::BITS == 0);
+}
+
+/// Running 64-bit hash over the `Word`s of a block (only used for error detection).
+#[derive(Clone, Copy, Debug)]
+struct BlockChecksum(u64);
+
+impl BlockChecksum {
+    #[inline(always)]
+    fn new() -> Self {
+        Self(0x243f_6a88_85a3_08d3)
+    }
+
+    #[inline(always)]
+    fn update(&mut self, word: u64) {
+        self.0 = (self.0.rotate_left(5) ^ word).wrapping_mul(0x517c_c1b7_2722_0a95);
+    }
+
+    /// Mixes in the length of the block if it's the final block, then applies the
+    /// finalizer of MurmurHash3 so that truncation to a `Word` keeps all bits relevant.
+    fn finish<Word>(self, final_len: Option<usize>) -> Word
+    where
+        Word: BitArray,
+        u64: AsPrimitive<Word>,
+    {
+        let mut hash = match final_len {
+            None => self.0,
+            Some(len) => self.0 ^ (len as u64 + 1).wrapping_mul(0x9e37_79b9_7f4a_7c15),
+        };
+        hash ^= hash >> 33;
+        hash = hash.wrapping_mul(0xff51_afd7_ed55_8ccd);
+        hash ^= hash >> 33;
+        hash = hash.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+        hash ^= hash >> 33;
+        hash.as_()
+    }
+}
+
+// RING BUFFERS ===============================================================
+
+/// A fixed-capacity circular queue of `Word`s for streaming compressed data from an
+/// encoder to a decoder in constant memory.
+///
+/// A `RingBuffer` stores up to `CAPACITY` `Word`s inline (it never allocates on the heap),
+/// so it can be used on embedded targets without an allocator. It uses interior mutability
+/// so that an encoder and a decoder can *share* the buffer: the backend traits are
+/// implemented for *shared references* `&RingBuffer`, where [`WriteWords`] appends to the
+/// end of the queue and [`ReadWords<Word, Queue>`](ReadWords) consumes from its front.
+/// Thus, you can construct, e.g., a [`RangeEncoder`] and a [`RangeDecoder`] that both
+/// operate on the same `RingBuffer` and interleave encoding and decoding.
+///
+/// Both "buffer full" and "buffer empty" are recoverable conditions: writing to a full
+/// buffer returns [`BoundedWriteError::OutOfSpace`] and reading from an empty buffer
+/// returns a [`RingBufferEmptyError`], and neither modifies the buffer. A `RangeDecoder`
+/// leaves its internal state unchanged when its backend reports an error, so the consumer
+/// can simply retry decoding a symbol once more data is available. However, an encoder may
+/// have already updated its internal state when its backend reports an error, so the
+/// producer should pause *before* it encodes a symbol that might not fit (see
+/// [`RangeEncoder::max_num_words_next_symbol`]).
+///
+/// Once the producer is done, it calls [`close`](Self::close). Reading from a closed and
+/// empty buffer indicates "end of file" by returning `Ok(None)`, which allows the consumer
+/// to decode the last few symbols (whose decoding may look past the end of the data).
+///
+/// # Capacity
+///
+/// A `RangeEncoder` occasionally has to hold back a run of words whose values aren't
+/// determined yet (see [`EncoderSituation::Inverted`]), and it then writes all of them at
+/// once. Thus, the producer can only make progress as long as
+/// [`max_num_words_next_symbol`] (and, before sealing, [`num_seal_words`]) doesn't exceed
+/// `CAPACITY`. If it does, then not even a completely drained buffer has enough space left,
+/// and the consumer can't decode any further either because it waits for the held-back
+/// words. The length of such a run isn't bounded, so no finite `CAPACITY` is sufficient
+/// for all inputs, but long runs are exponentially unlikely. Further, a `RangeDecoder`
+/// reads `State::BITS / Word::BITS` words when it is constructed. We therefore recommend a
+/// `CAPACITY` of at least `State::BITS / Word::BITS + 2` words, and a producer that must
+/// not stall should treat `max_num_words_next_symbol() > CAPACITY` as an error.
+///
+/// # Example
+///
+/// ```
+/// use constriction::{
+///     backends::RingBuffer,
+///     stream::{
+///         model::DefaultLeakyQuantizer,
+///         queue::{DefaultRangeDecoder, DefaultRangeEncoder},
+///         Decode, Encode,
+///     },
+/// };
+///
+/// let quantizer = DefaultLeakyQuantizer::new(-100..=100);
+/// let model = quantizer.quantize(probability::distribution::Gaussian::new(0.0, 10.0));
+/// let symbols = (0..1000).map(|i| (i * 37) % 41 - 20).collect::<Vec<i32>>();
+///
+/// // Stream the compressed data through a buffer that holds only 8 words at a time.
+/// let ring = RingBuffer::<u32, 8>::new();
+/// let mut encoder = DefaultRangeEncoder::with_backend(&ring);
+/// let mut decoder = None;
+/// let mut decoded = Vec::new();
+///
+/// let mut drain = |decoder: &mut Option<_>| {
+///     // Decode as many symbols as the data in `ring` allows.
+///     if decoder.is_none() && (ring.len() >= 2 || ring.is_closed()) {
+///         *decoder = Some(DefaultRangeDecoder::with_backend(&ring).unwrap());
+///     }
+///     if let Some(decoder) = decoder {
+///         while decoded.len() != symbols.len() && (!ring.is_empty() || ring.is_closed()) {
+///             decoded.push(decoder.decode_symbol(&model).unwrap());
+///         }
+///     }
+/// };
+///
+/// for symbol in &symbols {
+///     if ring.space_left() < encoder.max_num_words_next_symbol() {
+///         drain(&mut decoder); // Producer pauses until the consumer drains the buffer.
+///     }
+///     encoder.encode_symbol(symbol, &model).unwrap();
+/// }
+/// if ring.space_left() < encoder.num_seal_words() {
+///     drain(&mut decoder);
+/// }
+/// encoder.into_compressed().unwrap();
+/// ring.close();
+/// drain(&mut decoder);
+///
+/// assert_eq!(decoded, symbols);
+/// ```
+///
+/// [`RangeEncoder`]: crate::stream::queue::RangeEncoder
+/// [`RangeDecoder`]: crate::stream::queue::RangeDecoder
+/// [`RangeEncoder::max_num_words_next_symbol`]:
+///     crate::stream::queue::RangeEncoder::max_num_words_next_symbol
+/// [`max_num_words_next_symbol`]: crate::stream::queue::RangeEncoder::max_num_words_next_symbol
+/// [`num_seal_words`]: crate::stream::queue::RangeEncoder::num_seal_words
+/// [`EncoderSituation::Inverted`]: crate::stream::queue::EncoderSituation::Inverted
+pub struct RingBuffer<Word, const CAPACITY: usize> {
+    words: [core::cell::Cell<Word>; CAPACITY],
+    head: core::cell::Cell<usize>,
+    len: core::cell::Cell<usize>,
+    closed: core::cell::Cell<bool>,
+}
+
+impl<Word: BitArray, const CAPACITY: usize> RingBuffer<Word, CAPACITY> {
+    /// Creates an empty (and open) ring buffer.
+    pub fn new() -> Self {
+        Self {
+            words: core::array::from_fn(|_| core::cell::Cell::new(Word::zero())),
+            head: core::cell::Cell::new(0),
+            len: core::cell::Cell::new(0),
+            closed: core::cell::Cell::new(false),
+        }
+    }
+
+    /// Returns the maximum number of `Word`s that the buffer can hold at any time.
+    pub const fn capacity(&self) -> usize {
+        CAPACITY
+    }
+
+    /// Returns the number of `Word`s that are currently in the buffer.
+    pub fn len(&self) -> usize {
+        self.len.get()
+    }
+
+    /// Returns `true` if there are currently no `Word`s in the buffer.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the buffer is at capacity.
+    ///
+    /// Note that writing to a closed buffer fails even if the buffer isn't full (see
+    /// [`space_left`](Self::space_left)).
+    pub fn is_full(&self) -> bool {
+        self.len() == CAPACITY
+    }
+
+    /// Returns the number of `Word`s that can currently be written to the buffer.
+    ///
+    /// Returns zero if the buffer has been [closed](Self::close).
+    pub fn space_left(&self) -> usize {
+        if self.closed.get() {
+            0
+        } else {
+            CAPACITY - self.len()
+        }
+    }
+
+    /// Marks the end of the stream of compressed data.
+    ///
+    /// After calling `close`, reading from the buffer returns the remaining `Word`s and
+    /// then `Ok(None)`, and writing to the buffer fails.
+    pub fn close(&self) {
+        self.closed.set(true);
+    }
+
+    /// Returns `true` if [`close`](Self::close) has been called.
+    pub fn is_closed(&self) -> bool {
+        self.closed.get()
+    }
+
+    /// Removes all `Word`s and reopens the buffer.
+    ///
+    /// Takes `&mut self` to ensure that no entropy coder is currently using the buffer.
+    pub fn clear(&mut self) {
+        self.head.set(0);
+        self.len.set(0);
+        self.closed.set(false);
+    }
+}
+
+impl<Word, const CAPACITY: usize> Debug for RingBuffer<Word, CAPACITY> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("RingBuffer")
+            .field("capacity", &CAPACITY)
+            .field("len", &self.len.get())
+            .field("closed", &self.closed.get())
+            .finish()
+    }
+}
+
+impl<Word: BitArray, const CAPACITY: usize> Default for RingBuffer<Word, CAPACITY> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Word: BitArray, const CAPACITY: usize> WriteWords<Word> for &RingBuffer<Word, CAPACITY> {
+    type WriteError = BoundedWriteError;
+
+    #[inline]
+    fn write(&mut self, word: Word) -> Result<(), Self::WriteError> {
+        if self.space_left() == 0 {
+            return Err(BoundedWriteError::OutOfSpace);
+        }
+        let len = self.len.get();
+        self.words[(self.head.get() + len) % CAPACITY].set(word);
+        self.len.set(len + 1);
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn maybe_full(&self) -> bool {
+        self.space_left() == 0
+    }
+}
+
+impl<Word: BitArray, const CAPACITY: usize> BoundedWriteWords<Word>
+    for &RingBuffer<Word, CAPACITY>
+{
+    #[inline(always)]
+    fn space_left(&self) -> usize {
+        RingBuffer::space_left(self)
+    }
+}
+
+impl<Word: BitArray, const CAPACITY: usize> ReadWords<Word, Queue> for &RingBuffer<Word, CAPACITY> {
+    type ReadError = RingBufferEmptyError;
+
+    #[inline]
+    fn read(&mut self) -> Result<Option<Word>, Self::ReadError> {
+        let len = self.len.get();
+        if len != 0 {
+            let head = self.head.get();
+            self.head.set((head + 1) % CAPACITY);
+            self.len.set(len - 1);
+            Ok(Some(self.words[head].get()))
+        } else if self.closed.get() {
+            Ok(None)
+        } else {
+            Err(RingBufferEmptyError)
+        }
+    }
+
+    #[inline(always)]
+    fn maybe_exhausted(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+impl<Word: BitArray, const CAPACITY: usize> BoundedReadWords<Word, Queue>
+    for &RingBuffer<Word, CAPACITY>
+{
+    #[inline(always)]
+    fn remaining(&self) -> usize {
+        self.len()
+    }
+}
+
+/// Error type for reading from an empty [`RingBuffer`] that hasn't been closed yet.
+///
+/// This error is recoverable: the read didn't modify the `RingBuffer`, so you can retry
+/// after the producer wrote more data to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RingBufferEmptyError;
+
+impl Display for RingBufferEmptyError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Tried to read from an empty ring buffer that hasn't been closed."
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RingBufferEmptyError {}
+
+// GROWABLE QUEUES FOR INCREMENTALLY ARRIVING DATA ===========================
+
+/// A growable queue of `Word`s for decoding compressed data that arrives incrementally.
+///
+/// A `PushReadWords` is a data source for decoders with [`Queue`] semantics, such as a
+/// [`RangeDecoder`] or a [`QueueDecoder`]. You push words into it as they arrive (e.g.,
+/// from network packets) with [`push`](Self::push) or [`extend`](Extend::extend), and you
+/// [`close`](Self::close) it once no more data will arrive. Unlike the iterator adapters
+/// ([`FallibleIteratorReadWords`] and [`InfallibleIteratorReadWords`]), running out of data
+/// is not the end: reading from an empty `PushReadWords` that hasn't been closed yet
+/// returns a [`NeedMoreInput`] error, and the next read after pushing more words succeeds.
+/// Reading from a closed and empty `PushReadWords` returns `Ok(None)`, i.e., it indicates
+/// "end of file" just like, e.g., an exhausted [`Cursor`].
+///
+/// Both a `RangeDecoder` and a `QueueDecoder` with this backend leave their internal state
+/// unchanged when decoding a symbol fails with `NeedMoreInput`, so you can simply retry
+/// decoding the same symbol after pushing more words. To make this possible, a
+/// `PushReadWords` keeps the `Word`s that have already been read until the next call to
+/// [`push`](Self::push) or [`extend`](Extend::extend), so that a decoder can
+/// [`unread`](ReadWords::unread) them. The decoders provide the convenience methods `push_words` and `close_input` for this
+/// backend so that you don't need to disassemble them to get at the backend.
+///
+/// # Example
+///
+/// ```
+/// use constriction::{
+///     backends::{NeedMoreInput, PushReadWords},
+///     stream::{
+///         model::DefaultLeakyQuantizer,
+///         queue::{DefaultRangeEncoder, RangeDecoder},
+///         Decode, Encode,
+///     },
+///     CoderError,
+/// };
+///
+/// let quantizer = DefaultLeakyQuantizer::new(-100..=100);
+/// let model = quantizer.quantize(probability::distribution::Gaussian::new(0.0, 10.0));
+/// let symbols = (0..100).map(|i| (i * 37) % 41 - 20).collect::<Vec<i32>>();
+///
+/// let mut encoder = DefaultRangeEncoder::new();
+/// encoder.encode_iid_symbols(&symbols, &model).unwrap();
+/// let compressed = encoder.into_compressed().unwrap();
+///
+/// // Simulate data that arrives in packets of 3 words each.
+/// let mut packets = compressed.chunks(3);
+/// let mut backend = PushReadWords::new();
+/// let mut decoder = loop {
+///     match RangeDecoder::<u32, u64, _>::from_push_backend(backend) {
+///         Ok(decoder) => break decoder,
+///         Err(returned_backend) => {
+///             // Not enough data to initialize the decoder yet.
+///             backend = returned_backend;
+///             backend.extend(packets.next().unwrap().iter().copied());
+///         }
+///     }
+/// };
+///
+/// let mut decoded = Vec::new();
+/// while decoded.len() != symbols.len() {
+///     match decoder.decode_symbol(&model) {
+///         Ok(symbol) => decoded.push(symbol),
+///         Err(CoderError::Backend(NeedMoreInput)) => match packets.next() {
+///             Some(packet) => decoder.push_words(packet.iter().copied()),
+///             None => decoder.close_input(),
+///         },
+///         Err(err) => panic!("{}", err),
+///     }
+/// }
+/// assert_eq!(decoded, symbols);
+/// ```
+///
+/// [`RangeDecoder`]: crate::stream::queue::RangeDecoder
+/// [`QueueDecoder`]: crate::symbol::QueueDecoder
+#[derive(Clone, Debug)]
+pub struct PushReadWords<Word> {
+    /// Starts with `num_read` `Word`s that have already been read but may still be unread.
+    words: alloc::collections::VecDeque<Word>,
+    num_read: usize,
+    closed: bool,
+}
+
+impl<Word> PushReadWords<Word> {
+    /// Creates an empty (and open) `PushReadWords`.
+    pub fn new() -> Self {
+        Self {
+            words: alloc::collections::VecDeque::new(),
+            num_read: 0,
+            closed: false,
+        }
+    }
+
+    /// Appends a `Word` to the end of the queue.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `PushReadWords` has already been [closed](Self::close).
+    pub fn push(&mut self, word: Word) {
+        assert!(!self.closed, "Tried to push to a closed `PushReadWords`.");
+        self.discard_read();
+        self.words.push_back(word);
+    }
+
+    /// Drops all `Word`s that have already been read, so that they can no longer be unread.
+    fn discard_read(&mut self) {
+        self.words.drain(..self.num_read);
+        self.num_read = 0;
+    }
+
+    /// Marks the end of the compressed data.
+    ///
+    /// After calling `close`, reading returns the remaining `Word`s and then `Ok(None)`.
+    pub fn close(&mut self) {
+        self.closed = true;
+    }
+
+    /// Returns `true` if [`close`](Self::close) has been called.
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Returns the number of `Word`s that have been pushed but not yet read.
+    pub fn len(&self) -> usize {
+        self.words.len() - self.num_read
+    }
+
+    /// Returns `true` if all pushed `Word`s have been read.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<Word: PartialEq> PartialEq for PushReadWords<Word> {
+    /// Compares the closed status and the `Word`s that haven't been read yet.
+    fn eq(&self, other: &Self) -> bool {
+        self.closed == other.closed
+            && self
+                .words
+                .iter()
+                .skip(self.num_read)
+                .eq(other.words.iter().skip(other.num_read))
+    }
+}
+
+impl<Word: Eq> Eq for PushReadWords<Word> {}
+
+impl<Word> Default for PushReadWords<Word> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Word> Extend<Word> for PushReadWords<Word> {
+    /// Appends all `Word`s from `iter` to the end of the queue.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `PushReadWords` has already been [closed](Self::close).
+    fn extend<I: IntoIterator<Item = Word>>(&mut self, iter: I) {
+        assert!(!self.closed, "Tried to push to a closed `PushReadWords`.");
+        self.discard_read();
+        self.words.extend(iter);
+    }
+}
+
+impl<Word: Clone> ReadWords<Word, Queue> for PushReadWords<Word> {
+    type ReadError = NeedMoreInput;
+
+    #[inline]
+    fn read(&mut self) -> Result<Option<Word>, Self::ReadError> {
+        match self.words.get(self.num_read) {
+            Some(word) => {
+                self.num_read += 1;
+                Ok(Some(word.clone()))
+            }
+            None if self.closed => Ok(None),
+            None => Err(NeedMoreInput),
+        }
+    }
+
+    #[inline(always)]
+    fn maybe_exhausted(&self) -> bool {
+        self.is_empty()
+    }
+
+    /// Succeeds as long as the `Word`s haven't been discarded by a subsequent call to
+    /// [`push`](PushReadWords::push) or [`extend`](Extend::extend).
+    #[inline]
+    fn unread(&mut self, num_words: usize) -> bool {
+        if num_words <= self.num_read {
+            self.num_read -= num_words;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl<Word: Clone> BoundedReadWords<Word, Queue> for PushReadWords<Word> {
+    #[inline(always)]
+    fn remaining(&self) -> usize {
+        self.len()
+    }
+}
+
+/// Error type for reading from an empty [`PushReadWords`] that hasn't been closed yet.
+///
+/// This error is recoverable: push more data to the `PushReadWords` and then retry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NeedMoreInput;
+
+impl Display for NeedMoreInput {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Need more compressed data to continue decoding; push more data and try again."
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for NeedMoreInput {}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        BigEndian, BoundedReadWords, BoundedWriteError, BoundedWriteWords, ByteCursor, ByteVec,
+        ChecksumReadError, ChecksumReadWords, ChecksumWriteWords, CountingWriteWords, Cursor,
+        DiscardedWordsError, Endianness, FallibleCallbackWriteWords, FallibleIteratorReadWords,
+        FrameReadError, FrameReader, FrameWriter, IoReadWords, IoWriteWords, NeedMoreInput,
+        PushReadWords, ReadWords, Reverse, RingBuffer, RingBufferEmptyError, SegmentedCursor,
+        SegmentedVec, Tee, TeeWriteError, WriteWords,
+    };
+    use crate::{
+        stream::{
+            model::{DefaultLeakyQuantizer, SmallLeakyQuantizer},
+            queue::{
+                DefaultRangeDecoder, DefaultRangeEncoder, SmallRangeDecoder, SmallRangeEncoder,
+            },
+            stack::{AnsCoder, DefaultAnsCoder},
+            Decode, Encode,
+        },
+        symbol::{
+            huffman::{DecoderHuffmanTree, EncoderHuffmanTree},
+            DefaultQueueEncoder, QueueDecoder, QueueEncoder, ReadBitStream, WriteBitStream,
+        },
+        Pos, Queue, Seek, Semantics, Stack,
+    };
+    use alloc::vec::Vec;
+    use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+    use core::convert::Infallible;
+    use probability::distribution::Gaussian;
+    use std::{
+        fs::File,
+        io::{BufReader, BufWriter},
+    };
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn decode_on_the_fly_stack() {
+        fn encode_to_file(amt: u32) {
+            let quantizer = DefaultLeakyQuantizer::new(-256..=255);
+            let model = quantizer.quantize(Gaussian::new(0.0, 100.0));
+
+            let symbols = (0..amt).map(|i| {
+                let cheap_hash = i.wrapping_mul(0x6979_E2F3).wrapping_add(0x0059_0E91);
+                (cheap_hash >> (32 - 9)) as i32 - 256
+            });
+
+            let mut encoder = DefaultAnsCoder::new();
+            encoder.encode_iid_symbols_reverse(symbols, model).unwrap();
+            let compressed = encoder.into_compressed().unwrap();
+
+            let mut file = BufWriter::new(File::create("backend_stack_example.tmp").unwrap());
+            for &word in compressed.iter().rev() {
+                file.write_u32::<LittleEndian>(word).unwrap();
+            }
+        }
+
+        fn decode_from_file_on_the_fly(amt: u32) {
+            let quantizer = DefaultLeakyQuantizer::new(-256..=255);
+            let model = quantizer.quantize(Gaussian::new(0.0, 100.0));
+
+            let mut file = BufReader::new(File::open("backend_stack_example.tmp").unwrap());
+            let word_iterator = std::iter::from_fn(move || match file.read_u32::<LittleEndian>() {
+                Ok(word) => Some(Ok(word)),
+                Err(err) => {
+                    if err.kind() == std::io::ErrorKind::UnexpectedEof {
+                        None
+                    } else {
+                        Some(Err(err))
+                    }
+                }
+            });
+
+            let mut decoder =
+                DefaultAnsCoder::from_reversed_compressed_iter(word_iterator).unwrap();
+
+            for (i, symbol) in decoder.decode_iid_symbols(amt as usize, &model).enumerate() {
+                let cheap_hash = (i as u32)
+                    .wrapping_mul(0x6979_E2F3)
+                    .wrapping_add(0x0059_0E91);
+                let expected = (cheap_hash >> (32 - 9)) as i32 - 256;
+                assert_eq!(symbol.unwrap(), expected);
+            }
+            assert!(decoder.is_empty());
+
+            let mut word_iterator = decoder.into_raw_parts().0.into_iter();
+            assert!(word_iterator.next().is_none());
+
+            std::mem::drop(word_iterator);
+            std::fs::remove_file("backend_stack_example.tmp").unwrap();
+        }
+
+        encode_to_file(1000);
+        decode_from_file_on_the_fly(1000);
+    }
+
+    #[test]
+    fn io_read_write_words_queue() {
+        let quantizer = DefaultLeakyQuantizer::new(-256..=255);
+        let model = quantizer.quantize(Gaussian::new(0.0, 100.0));
+        let symbols = (0..1000u32)
+            .map(|i| {
+                let cheap_hash = i.wrapping_mul(0x6979_E2F3).wrapping_add(0x0059_0E91);
+                (cheap_hash >> (32 - 9)) as i32 - 256
+            })
+            .collect::<Vec<_>>();
+
+        let mut reference_encoder = DefaultRangeEncoder::new();
+        reference_encoder
+            .encode_iid_symbols(&symbols, &model)
+            .unwrap();
+        let reference = reference_encoder.into_compressed().unwrap();
+
+        let mut little_encoder = DefaultRangeEncoder::with_backend(IoWriteWords::<
+            _,
+            super::LittleEndian,
+        >::new(Vec::new()));
+        little_encoder.encode_iid_symbols(&symbols, &model).unwrap();
+        let little = little_encoder.into_compressed().unwrap().into_inner();
+        let expected_little = reference
+            .iter()
+            .flat_map(|word| word.to_le_bytes())
+            .collect::<Vec<_>>();
+        assert_eq!(little, expected_little);
+
+        let mut big_encoder =
+            DefaultRangeEncoder::with_backend(IoWriteWords::<_, BigEndian>::new(Vec::new()));
+        big_encoder.encode_iid_symbols(&symbols, &model).unwrap();
+        let big = big_encoder.into_compressed().unwrap().into_inner();
+        let expected_big = reference
+            .iter()
+            .flat_map(|word| word.to_be_bytes())
+            .collect::<Vec<_>>();
+        assert_eq!(big, expected_big);
+
+        let backend = IoReadWords::<_, BigEndian>::new(&big[..]);
+        let mut decoder = DefaultRangeDecoder::with_backend(backend).unwrap();
+        let decoded = decoder
+            .decode_iid_symbols(symbols.len(), &model)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(decoded, symbols);
+        assert!(decoder.maybe_exhausted());
+
+        // A stream that ends in the middle of a word is reported as an error.
+        let truncated = IoReadWords::<_, BigEndian>::new(&big[..10]);
+        let mut decoder = DefaultRangeDecoder::with_backend(truncated).unwrap();
+        let err = decoder
+            .decode_iid_symbols(symbols.len(), &model)
+            .find_map(Result::err)
+            .unwrap();
+        assert!(matches!(
+            err,
+            crate::CoderError::Backend(err) if err.kind() == std::io::ErrorKind::UnexpectedEof
+        ));
+    }
+
+    #[test]
+    fn io_read_write_words_symbol_coder() {
+        let amt = 1000;
+        let symbols = (0..amt as u32)
+            .map(|i| (i.wrapping_mul(0x6979_E2F3).wrapping_add(0x0059_0E91) >> 29) as usize % 5)
+            .collect::<Vec<_>>();
+
+        let probabilities = [2, 2, 4, 1, 1];
+        let encoder_codebook = EncoderHuffmanTree::from_probabilities::<u32, _>(&probabilities);
+        let decoder_codebook = DecoderHuffmanTree::from_probabilities::<u32, _>(&probabilities);
+
+        let mut reference_encoder = DefaultQueueEncoder::new();
+        reference_encoder
+            .encode_iid_symbols(&symbols, &encoder_codebook)
+            .unwrap();
+        let reference = reference_encoder.into_compressed().unwrap();
+
+        let backend = IoWriteWords::<_, super::LittleEndian>::new(Vec::new());
+        let mut encoder = QueueEncoder::<u32, _>::from_compressed(backend);
+        encoder
+            .encode_iid_symbols(&symbols, &encoder_codebook)
+            .unwrap();
+        let bytes = encoder.into_compressed().unwrap().into_inner();
+        let expected = reference
+            .iter()
+            .flat_map(|word| word.to_le_bytes())
+            .collect::<Vec<_>>();
+        assert_eq!(bytes, expected);
+
+        let backend = IoReadWords::<_, super::LittleEndian>::new(&bytes[..]);
+        let mut decoder = QueueDecoder::<u32, _>::from_compressed(backend);
+        let reconstructed = decoder
+            .decode_iid_symbols(amt, &decoder_codebook)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(reconstructed, symbols);
+    }
+
+    fn cheap_symbols(amt: u32) -> Vec<i32> {
+        (0..amt)
+            .map(|i| {
+                let cheap_hash = i.wrapping_mul(0x6979_E2F3).wrapping_add(0x0059_0E91);
+                (cheap_hash >> (32 - 9)) as i32 - 256
+            })
+            .collect()
+    }
+
+    #[test]
+    fn byte_vec_stack() {
+        let quantizer = DefaultLeakyQuantizer::new(-256..=255);
+        let model = quantizer.quantize(Gaussian::new(0.0, 100.0));
+        let symbols = cheap_symbols(1000);
+
+        let mut reference_coder = DefaultAnsCoder::new();
+        reference_coder
+            .encode_iid_symbols_reverse(&symbols, &model)
+            .unwrap();
+        let reference = reference_coder.into_compressed().unwrap();
+
+        let mut little_coder = AnsCoder::<u32, u64, ByteVec<u32>>::default();
+        little_coder
+            .encode_iid_symbols_reverse(&symbols, &model)
+            .unwrap();
+        let little = little_coder.into_compressed().unwrap();
+        let expected_little = reference
+            .iter()
+            .flat_map(|word| word.to_le_bytes())
+            .collect::<Vec<_>>();
+        assert_eq!(little.as_bytes(), &expected_little[..]);
+        assert_eq!(little.num_words(), reference.len());
+        assert_eq!(little.pos(), reference.len());
+
+        let mut big_coder = AnsCoder::<u32, u64, ByteVec<u32, BigEndian>>::default();
+        big_coder
+            .encode_iid_symbols_reverse(&symbols, &model)
+            .unwrap();
+        assert!(big_coder
+            .as_decoder()
+            .decode_iid_symbols(symbols.len(), &model)
+            .map(Result::unwrap)
+            .eq(symbols.iter().cloned()));
+        let big = big_coder.into_compressed().unwrap();
+        let expected_big = reference
+            .iter()
+            .flat_map(|word| word.to_be_bytes())
+            .collect::<Vec<_>>();
+        assert_eq!(big.as_bytes(), &expected_big[..]);
+
+        let mut decoder = AnsCoder::<u32, u64, _>::from_compressed(big).unwrap();
+        let decoded = decoder
+            .decode_iid_symbols(symbols.len(), &model)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(decoded, symbols);
+        assert!(decoder.is_empty());
+    }
+
+    #[test]
+    fn byte_vec_pos_seek() {
+        let mut backend = ByteVec::<u16, BigEndian>::new();
+        backend.write(0x0102u16).unwrap();
+        backend.write(0x0304u16).unwrap();
+        assert_eq!(backend.pos(), 2);
+        assert_eq!(backend.as_bytes(), &[1, 2, 3, 4]);
+
+        assert_eq!(backend.seek(3), Err(()));
+        backend.seek(1).unwrap();
+        assert_eq!(backend.as_bytes(), &[1, 2]);
+
+        // An incomplete word at the end gets padded with zeros before writing.
+        let mut backend = ByteVec::<u16, BigEndian>::from_bytes(alloc::vec![1, 2, 3]);
+        assert_eq!(backend.num_words(), 2);
+        assert_eq!(backend.pos(), 2);
+        backend.write(0x0506u16).unwrap();
+        assert_eq!(backend.as_bytes(), &[1, 2, 3, 0, 5, 6]);
+        assert_eq!(
+            ReadWords::<u16, Stack>::read(&mut backend),
+            Ok(Some(0x0506))
+        );
+        backend.seek(1).unwrap();
+        assert_eq!(
+            ReadWords::<u16, Stack>::read(&mut backend),
+            Ok(Some(0x0102))
+        );
+        assert_eq!(ReadWords::<u16, Stack>::read(&mut backend), Ok(None));
+
+        let mut backend = ByteVec::<u16, super::LittleEndian>::from_bytes(alloc::vec![1, 2, 3]);
+        assert_eq!(
+            ReadWords::<u16, Stack>::read(&mut backend),
+            Ok(Some(0x0003))
+        );
+        assert_eq!(
+            ReadWords::<u16, Stack>::read(&mut backend),
+            Ok(Some(0x0201))
+        );
+        assert!(ReadWords::<u16, Stack>::maybe_exhausted(&backend));
+    }
+
+    #[test]
+    fn byte_cursor_queue() {
+        let quantizer = SmallLeakyQuantizer::new(-256..=255);
+        let model = quantizer.quantize(Gaussian::new(0.0, 100.0));
+        let symbols = cheap_symbols(1000);
+
+        let mut reference_encoder = SmallRangeEncoder::new();
+        reference_encoder
+            .encode_iid_symbols(&symbols, &model)
+            .unwrap();
+        let reference = reference_encoder.into_compressed().unwrap();
+
+        let mut encoder = SmallRangeEncoder::with_backend(ByteVec::<u16, BigEndian>::new());
+        encoder.encode_iid_symbols(&symbols[..500], &model).unwrap();
+        let checkpoint = encoder.pos();
+        encoder.encode_iid_symbols(&symbols[500..], &model).unwrap();
+        assert_eq!(encoder.num_words(), reference.len());
+        let compressed = encoder.into_compressed().unwrap();
+        let expected = reference
+            .iter()
+            .flat_map(|word| word.to_be_bytes())
+            .collect::<Vec<_>>();
+        assert_eq!(compressed.as_bytes(), &expected[..]);
+
+        let cursor = ByteCursor::<u16, _, BigEndian>::new_at_write_beginning(compressed.as_bytes());
+        let mut decoder = SmallRangeDecoder::with_backend(cursor).unwrap();
+        let decoded = decoder
+            .decode_iid_symbols(symbols.len(), &model)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(decoded, symbols);
+        assert!(decoder.maybe_exhausted());
+
+        decoder.seek(checkpoint).unwrap();
+        let decoded = decoder
+            .decode_iid_symbols(500, &model)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(decoded, &symbols[500..]);
+        assert!(decoder.maybe_exhausted());
+    }
+
+    #[test]
+    fn byte_cursor_u64() {
+        let words = [
+            0x0102_0304_0506_0708u64,
+            0x1112_1314_1516_1718,
+            0x2122_2324_2526_2728,
+        ];
+
+        let mut cursor =
+            ByteCursor::<u64, _, BigEndian>::new_at_write_beginning(alloc::vec![0u8; 20]);
+        assert_eq!(BoundedWriteWords::<u64>::space_left(&cursor), 2);
+        cursor.write(words[0]).unwrap();
+        cursor.write(words[1]).unwrap();
+        assert_eq!(cursor.write(words[2]), Err(BoundedWriteError::OutOfSpace));
+        assert_eq!(cursor.pos(), 2);
+        let (mut buf, _) = cursor.into_buf_and_pos();
+        buf[16..].copy_from_slice(&[0x21, 0x22, 0x23, 0x24]);
+
+        // Reading with `Queue` semantics pads the incomplete last word.
+        let mut cursor = ByteCursor::<u64, _, BigEndian>::new_at_write_beginning(&buf[..]);
+        assert_eq!(BoundedReadWords::<u64, Queue>::remaining(&cursor), 3);
+        assert_eq!(
+            ReadWords::<u64, Queue>::read(&mut cursor),
+            Ok(Some(words[0]))
+        );
+        assert_eq!(
+            ReadWords::<u64, Queue>::read(&mut cursor),
+            Ok(Some(words[1]))
+        );
+        assert_eq!(
+            ReadWords::<u64, Queue>::read(&mut cursor),
+            Ok(Some(0x2122_2324_0000_0000))
+        );
+        assert_eq!(cursor.pos(), 3);
+        assert_eq!(ReadWords::<u64, Queue>::read(&mut cursor), Ok(None));
+
+        // Reading with `Stack` semantics from the same position goes back again.
+        assert_eq!(BoundedReadWords::<u64, Stack>::remaining(&cursor), 3);
+        assert_eq!(
+            ReadWords::<u64, Stack>::read(&mut cursor),
+            Ok(Some(0x2122_2324_0000_0000))
+        );
+        assert_eq!(
+            ReadWords::<u64, Stack>::read(&mut cursor),
+            Ok(Some(words[1]))
+        );
+        assert_eq!(cursor.pos(), 1);
+
+        assert_eq!(cursor.seek(4), Err(()));
+        cursor.seek(3).unwrap();
+        assert!(ReadWords::<u64, Queue>::maybe_exhausted(&cursor));
+        let mut cursor =
+            ByteCursor::<u64, _, super::LittleEndian>::new_at_pos(&buf[..], 1).unwrap();
+        assert_eq!(
+            ReadWords::<u64, Queue>::read(&mut cursor),
+            Ok(Some(words[1].swap_bytes()))
+        );
+        assert_eq!(
+            ReadWords::<u64, Queue>::read(&mut cursor),
+            Ok(Some(0x2423_2221))
+        );
+    }
+
+    #[test]
+    fn trimmed_range_encoder() {
+        fn test<E: Endianness>(amt: u32) -> (usize, usize) {
+            let quantizer = DefaultLeakyQuantizer::new(-256..=255);
+            let model = quantizer.quantize(Gaussian::new(0.0, 100.0));
+            let symbols = cheap_symbols(amt);
+
+            let mut encoder = DefaultRangeEncoder::with_backend(ByteVec::<u32, E>::new());
+            encoder.encode_iid_symbols(&symbols, &model).unwrap();
+            let untrimmed = encoder.clone().into_compressed().unwrap();
+            let trimmed = encoder.into_trimmed_compressed();
+            assert!(trimmed.num_bytes() <= untrimmed.num_bytes());
+
+            let mut decoder = DefaultRangeDecoder::from_compressed(trimmed.clone()).unwrap();
+            let decoded = decoder
+                .decode_iid_symbols(symbols.len(), &model)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            assert_eq!(decoded, symbols);
+
+            (trimmed.num_bytes(), untrimmed.num_bytes())
+        }
+
+        let mut total_trimmed = 0;
+        let mut total_untrimmed = 0;
+        for amt in (0..40).chain([100, 1000]) {
+            test::<super::LittleEndian>(amt);
+            let (trimmed, untrimmed) = test::<BigEndian>(amt);
+            total_trimmed += trimmed;
+            total_untrimmed += untrimmed;
+        }
+        assert!(total_trimmed < total_untrimmed);
+        assert_eq!(test::<BigEndian>(0), (0, 0));
+    }
+
+    #[test]
+    #[cfg(feature = "mmap")]
+    #[cfg_attr(miri, ignore)]
+    fn mmap_words() {
+        use super::{Cursor, MmapWords};
+        use std::io::Write;
+
+        let quantizer = DefaultLeakyQuantizer::new(-256..=255);
+        let model = quantizer.quantize(Gaussian::new(0.0, 100.0));
+        let symbols = cheap_symbols(1000);
+
+        let mut encoder = DefaultRangeEncoder::new();
+        encoder.encode_iid_symbols(&symbols, &model).unwrap();
+        let compressed = encoder.into_compressed().unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("compressed.bin");
+        let mut file = File::create(&path).unwrap();
+        for word in &compressed {
+            file.write_all(&word.to_ne_bytes()).unwrap();
+        }
+        file.write_all(&[1, 2, 3]).unwrap();
+        drop(file);
+
+        let file = File::open(&path).unwrap();
+        let err = unsafe { MmapWords::<u32>::map(&file) }.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+        // Decode with `Queue` semantics through a `ByteCursor`, which doesn't care about
+        // the trailing bytes as long as we don't read that far.
+        let mmap = unsafe { memmap2::Mmap::map(&file) }.unwrap();
+        {
+            let cursor = ByteCursor::<u32, _, super::LittleEndian>::new_at_write_beginning(&mmap);
+            let mut decoder = DefaultRangeDecoder::with_backend(cursor).unwrap();
+            let decoded = decoder
+                .decode_iid_symbols(symbols.len(), &model)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            if cfg!(target_endian = "little") {
+                assert_eq!(decoded, symbols);
+            }
+        }
+
+        // The file must not be modified while it's mapped (see `memmap2::Mmap::map`).
+        drop(mmap);
+        drop(file);
+
+        // Remove the trailing bytes and decode through a `Cursor` with `Queue` semantics.
+        let file = std::fs::OpenOptions::new().write(true).open(&path).unwrap();
+        file.set_len(4 * compressed.len() as u64).unwrap();
+        drop(file);
+        let file = File::open(&path).unwrap();
+        let words = unsafe { MmapWords::<u32>::map(&file) }.unwrap();
+        assert_eq!(words.as_ref(), &compressed[..]);
+        let mut decoder = DefaultRangeDecoder::from_compressed(words).unwrap();
+        let decoded = decoder
+            .decode_iid_symbols(symbols.len(), &model)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(decoded, symbols);
+        assert!(decoder.maybe_exhausted());
+
+        // Reading with `Stack` semantics through a `Cursor` visits the words in reverse.
+        let words = unsafe { MmapWords::<u32>::map(&file) }.unwrap();
+        let mut cursor = Cursor::new_at_write_end(words);
+        assert_eq!(cursor.seek(compressed.len() + 1), Err(()));
+        assert!(
+            core::iter::from_fn(|| ReadWords::<u32, Stack>::read(&mut cursor).unwrap())
+                .eq(compressed.iter().rev().cloned())
+        );
+
+        drop(cursor);
+        drop(file);
+        dir.close().unwrap();
+    }
+
+    #[test]
+    fn segmented_vec_stack() {
+        let quantizer = DefaultLeakyQuantizer::new(-256..=255);
+        let model = quantizer.quantize(Gaussian::new(0.0, 100.0));
+        let symbols = cheap_symbols(1000);
+
+        let mut reference_coder = DefaultAnsCoder::new();
+        reference_coder
+            .encode_iid_symbols_reverse(&symbols, &model)
+            .unwrap();
+        let reference = reference_coder.into_compressed().unwrap();
+
+        let mut coder = DefaultAnsCoder::from_compressed(SegmentedVec::with_block_size(7)).unwrap();
+        coder
+            .encode_iid_symbols_reverse(&symbols[500..], &model)
+            .unwrap();
+        let (checkpoint_pos, checkpoint_state) = coder.pos();
+        coder
+            .encode_iid_symbols_reverse(&symbols[..500], &model)
+            .unwrap();
+        assert_eq!(coder.num_words(), reference.len());
+        assert!(coder
+            .as_decoder()
+            .decode_iid_symbols(symbols.len(), &model)
+            .map(Result::unwrap)
+            .eq(symbols.iter().cloned()));
+
+        let compressed = coder.into_compressed().unwrap();
+        assert!(compressed.blocks().all(|block| block.len() <= 7));
+        assert!(compressed.iter().eq(reference.iter()));
+
+        // Decoding from the `SegmentedVec` consumes it (and deallocates emptied blocks).
+        let mut decoder = DefaultAnsCoder::from_compressed(compressed.clone()).unwrap();
+        let decoded = decoder
+            .decode_iid_symbols(500, &model)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(decoded, &symbols[..500]);
+        assert_eq!(decoder.pos(), (checkpoint_pos, checkpoint_state));
+        assert_eq!(decoder.bulk().blocks().len(), checkpoint_pos.div_ceil(7));
+
+        // Seeking truncates, just like for a `Vec`.
+        let mut decoder = DefaultAnsCoder::from_compressed(compressed).unwrap();
+        decoder.seek((checkpoint_pos, checkpoint_state)).unwrap();
+        let decoded = decoder
+            .decode_iid_symbols(500, &model)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(decoded, &symbols[500..]);
+        assert!(decoder.is_empty());
+        assert_eq!(decoder.into_compressed().unwrap().blocks().len(), 0);
+    }
+
+    #[test]
+    fn segmented_vec_queue() {
+        let quantizer = DefaultLeakyQuantizer::new(-256..=255);
+        let model = quantizer.quantize(Gaussian::new(0.0, 100.0));
+        let symbols = cheap_symbols(1000);
+
+        let mut reference_encoder = DefaultRangeEncoder::new();
+        reference_encoder
+            .encode_iid_symbols(&symbols, &model)
+            .unwrap();
+        let reference = reference_encoder.into_compressed().unwrap();
+
+        let mut encoder = DefaultRangeEncoder::with_backend(SegmentedVec::with_block_size(16));
+        encoder.encode_iid_symbols(&symbols[..500], &model).unwrap();
+        let checkpoint = encoder.pos();
+        encoder.encode_iid_symbols(&symbols[500..], &model).unwrap();
+        assert_eq!(encoder.num_words(), reference.len());
+
+        let compressed = encoder.into_compressed().unwrap();
+        assert_eq!(compressed.len(), reference.len());
+        assert_eq!(compressed.clone().into_vec(), reference);
+        let mut concatenated = Vec::new();
+        for block in compressed.blocks() {
+            concatenated.extend_from_slice(block);
+        }
+        assert_eq!(concatenated, reference);
+
+        let mut decoder = DefaultRangeDecoder::from_compressed(compressed).unwrap();
+        let decoded = decoder
+            .decode_iid_symbols(symbols.len(), &model)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(decoded, symbols);
+        assert!(decoder.maybe_exhausted());
+
+        decoder.seek(checkpoint).unwrap();
+        let decoded = decoder
+            .decode_iid_symbols(500, &model)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(decoded, &symbols[500..]);
+        assert!(decoder.maybe_exhausted());
+    }
+
+    #[test]
+    fn segmented_vec_truncate() {
+        let mut buf = SegmentedVec::with_block_size(3);
+        buf.extend_from_iter(0..10u32).unwrap();
+        assert_eq!(buf.len(), 10);
+        assert_eq!(buf.blocks().len(), 4);
+        assert_eq!(buf.get(7), Some(&7));
+        assert_eq!(buf.get(10), None);
+
+        assert_eq!(buf.seek(11), Err(()));
+        buf.seek(6).unwrap();
+        assert_eq!(
+            buf.blocks().map(|block| block.len()).collect::<Vec<_>>(),
+            [3, 3]
+        );
+        buf.truncate(4);
+        assert_eq!(
+            buf.blocks().map(|block| block.len()).collect::<Vec<_>>(),
+            [3, 1]
+        );
+        assert_eq!(ReadWords::<u32, Stack>::read(&mut buf), Ok(Some(3)));
+        assert_eq!(buf.blocks().len(), 1);
+        buf.write(10).unwrap();
+        assert!(buf.iter().cloned().eq([0, 1, 2, 10]));
+
+        let mut cursor = SegmentedCursor::new_at_write_end(&buf);
+        assert_eq!(ReadWords::<u32, Stack>::read(&mut cursor), Ok(Some(10)));
+        assert_eq!(ReadWords::<u32, Stack>::read(&mut cursor), Ok(Some(2)));
+        assert_eq!(ReadWords::<u32, Queue>::read(&mut cursor), Ok(Some(2)));
+        assert_eq!(BoundedReadWords::<u32, Queue>::remaining(&cursor), 1);
+        buf.truncate(0);
+        assert!(buf.is_empty());
+        assert_eq!(ReadWords::<u32, Stack>::read(&mut buf), Ok(None));
+    }
+
+    #[test]
+    fn segmented_vec_clone() {
+        let mut buf = SegmentedVec::with_block_size(4);
+        buf.extend_from_iter(0..6u32).unwrap();
+
+        // Pushing onto a clone must not reallocate the (partially filled) last block.
+        let mut cloned = buf.clone();
+        assert_eq!(cloned, buf);
+        assert!(cloned.blocks.iter().all(|block| block.capacity() == 4));
+        let last_block = cloned.blocks.last().unwrap().as_ptr();
+        cloned.extend_from_iter(6..8).unwrap();
+        assert_eq!(cloned.blocks().len(), 2);
+        assert_eq!(cloned.blocks.last().unwrap().as_ptr(), last_block);
+        assert!(cloned.iter().cloned().eq(0..8));
+        assert!(buf.iter().cloned().eq(0..6));
+    }
+
+    #[test]
+    fn counting_write_words() {
+        let quantizer = DefaultLeakyQuantizer::new(-256..=255);
+        let model = quantizer.quantize(Gaussian::new(0.0, 100.0));
+        let symbols = cheap_symbols(1000);
+
+        for amt in [0, 1, 2, 10, 100, 1000] {
+            let mut reference_encoder = DefaultRangeEncoder::new();
+            let mut counting_encoder = DefaultRangeEncoder::with_backend(CountingWriteWords::new());
+            let mut reference_coder = DefaultAnsCoder::new();
+            let mut counting_coder =
+                DefaultAnsCoder::from_compressed(CountingWriteWords::new()).unwrap();
+            for symbol in &symbols[..amt] {
+                reference_encoder.encode_symbol(symbol, &model).unwrap();
+                counting_encoder.encode_symbol(symbol, &model).unwrap();
+                reference_coder.encode_symbol(symbol, &model).unwrap();
+                counting_coder.encode_symbol(symbol, &model).unwrap();
+                assert_eq!(counting_encoder.num_bits(), reference_encoder.num_bits());
+                assert_eq!(counting_encoder.pos(), reference_encoder.pos());
+                assert_eq!(counting_coder.num_bits(), reference_coder.num_bits());
+                assert_eq!(
+                    counting_coder.num_valid_bits(),
+                    reference_coder.num_valid_bits()
+                );
+                assert_eq!(counting_coder.pos(), reference_coder.pos());
+            }
+            assert_eq!(counting_encoder.is_empty(), amt == 0);
+
+            let reference = reference_encoder.into_compressed().unwrap();
+            let counter = counting_encoder.into_compressed().unwrap();
+            assert_eq!(counter.num_words(), reference.len());
+
+            let reference = reference_coder.into_compressed().unwrap();
+            let mut counter = counting_coder.into_compressed().unwrap();
+            assert_eq!(counter.num_words(), reference.len());
+            assert_eq!(counter.num_bits(), 32 * reference.len());
+
+            let expected = if reference.is_empty() {
+                Ok(None)
+            } else {
+                Err(DiscardedWordsError)
+            };
+            assert_eq!(ReadWords::<u32, Stack>::read(&mut counter), expected);
+            assert_eq!(counter.num_words(), reference.len());
+        }
+    }
+
+    #[test]
+    fn checksum_round_trip_stack() {
+        let quantizer = DefaultLeakyQuantizer::new(-256..=255);
+        let model = quantizer.quantize(Gaussian::new(0.0, 100.0));
+        let symbols = cheap_symbols(1000);
+
+        for block_size in [1, 2, 7, 64] {
+            for amt in [0, 1, 2, 10, 100, 1000] {
+                let mut reference = DefaultAnsCoder::new();
+                reference
+                    .encode_iid_symbols_reverse(&symbols[..amt], &model)
+                    .unwrap();
+                let reference = reference.into_compressed().unwrap();
+
+                let backend = ChecksumWriteWords::new(Vec::new(), block_size);
+                let mut coder = AnsCoder::<u32, u64, _>::from_raw_parts(backend, 0);
+                coder
+                    .encode_iid_symbols_reverse(&symbols[..amt], &model)
+                    .unwrap();
+                let compressed = coder.into_compressed().unwrap().finish().unwrap();
+                let n = reference.len();
+                assert_eq!(compressed.len(), n + n / block_size + 2);
+
+                let backend =
+                    ChecksumReadWords::new(Cursor::new_at_write_end(&compressed[..]), block_size);
+                let mut coder = AnsCoder::<u32, u64, _>::from_compressed(backend).unwrap();
+                let decoded = coder
+                    .decode_iid_symbols(amt, &model)
+                    .collect::<Result<Vec<_>, _>>()
+                    .unwrap();
+                assert_eq!(decoded, &symbols[..amt]);
+                let (mut bulk, _) = coder.into_raw_parts();
+                assert_eq!(ReadWords::<u32, Stack>::read(&mut bulk), Ok(None));
+
+                fn read_all<B: ReadWords<u32, Stack, ReadError = Infallible>>(
+                    backend: B,
+                    block_size: usize,
+                ) -> Vec<u32> {
+                    let mut backend = ChecksumReadWords::new(backend, block_size);
+                    let mut words = Vec::new();
+                    while let Some(word) = backend.read().unwrap() {
+                        words.push(word);
+                    }
+                    assert_eq!(backend.read(), Ok(None));
+                    words.reverse();
+                    words
+                }
+
+                assert_eq!(read_all(compressed.clone(), block_size), reference);
+                let reversed = Cursor::new_at_write_end(compressed.clone()).into_reversed();
+                assert_eq!(read_all(reversed, block_size), reference);
+                let iter = compressed.iter().rev().map(|&w| Ok::<_, Infallible>(w));
+                let iter = FallibleIteratorReadWords::new(iter);
+                assert_eq!(read_all(iter, block_size), reference);
+            }
+        }
+    }
+
+    #[test]
+    fn checksum_round_trip_queue() {
+        let quantizer = DefaultLeakyQuantizer::new(-256..=255);
+        let model = quantizer.quantize(Gaussian::new(0.0, 100.0));
+        let symbols = cheap_symbols(1000);
+
+        for block_size in [1, 2, 7, 64] {
+            for amt in [0, 1, 2, 10, 100, 1000] {
+                let mut compressed = Vec::new();
+                let backend = ChecksumWriteWords::new(
+                    FallibleCallbackWriteWords::new(|word| {
+                        compressed.push(word);
+                        Ok::<(), Infallible>(())
+                    }),
+                    block_size,
+                );
+                let mut encoder = DefaultRangeEncoder::with_backend(backend);
+                encoder.encode_iid_symbols(&symbols[..amt], &model).unwrap();
+                encoder.into_compressed().unwrap().finish().unwrap();
+
+                let backend = Cursor::new_at_write_beginning(&compressed[..]);
+                let backend = ChecksumReadWords::new(backend, block_size);
+                let mut decoder = DefaultRangeDecoder::with_backend(backend).unwrap();
+                let decoded = decoder
+                    .decode_iid_symbols(amt, &model)
+                    .collect::<Result<Vec<_>, _>>()
+                    .unwrap();
+                assert_eq!(decoded, &symbols[..amt]);
+
+                let mut reversed = compressed.clone();
+                reversed.reverse();
+                let backend = Reverse(Cursor::new_at_write_end(&reversed[..]));
+                let mut backend = ChecksumReadWords::new(backend, block_size);
+                let backend2 = FallibleIteratorReadWords::new(
+                    compressed.iter().map(|&w| Ok::<_, Infallible>(w)),
+                );
+                let mut backend2 = ChecksumReadWords::new(backend2, block_size);
+                let mut reference = DefaultRangeEncoder::new();
+                reference
+                    .encode_iid_symbols(&symbols[..amt], &model)
+                    .unwrap();
+                for expected in reference.into_compressed().unwrap() {
+                    assert_eq!(
+                        ReadWords::<u32, Queue>::read(&mut backend),
+                        Ok(Some(expected))
+                    );
+                    assert_eq!(
+                        ReadWords::<u32, Queue>::read(&mut backend2),
+                        Ok(Some(expected))
+                    );
+                }
+                assert_eq!(ReadWords::<u32, Queue>::read(&mut backend), Ok(None));
+                assert_eq!(ReadWords::<u32, Queue>::read(&mut backend2), Ok(None));
+            }
+        }
+    }
+
+    #[test]
+    fn checksum_detects_corruption() {
+        let data = (1..=100u32).collect::<Vec<_>>();
+        let mut backend = ChecksumWriteWords::new(Vec::new(), 8);
+        backend.extend_from_iter(data.iter().cloned()).unwrap();
+        let compressed = backend.finish().unwrap();
+        // 12 full blocks of 9 words, then a final block of 4 data words plus 2 words.
+        assert_eq!(compressed.len(), 12 * 9 + 6);
+
+        fn read_all<S: Semantics>(
+            mut backend: impl ReadWords<u32, S, ReadError = ChecksumReadError<Infallible>>,
+        ) -> Result<Vec<u32>, ChecksumReadError<Infallible>> {
+            let mut words = Vec::new();
+            while let Some(word) = backend.read()? {
+                words.push(word);
+            }
+            Ok(words)
+        }
+
+        let forward = |data: &Vec<u32>| {
+            let backend = Cursor::new_at_write_beginning(data.clone());
+            read_all::<Queue>(ChecksumReadWords::new(backend, 8))
+        };
+        let backward = |data: &Vec<u32>| {
+            let backend = Cursor::new_at_write_end(data.clone());
+            read_all::<Stack>(ChecksumReadWords::new(backend, 8))
+        };
+
+        assert_eq!(forward(&compressed), Ok(data.clone()));
+        let mut reversed_data = data.clone();
+        reversed_data.reverse();
+        assert_eq!(backward(&compressed), Ok(reversed_data));
+
+        for (index, forward_block, backward_block) in [
+            (0, 0, 12),
+            (8, 0, 12),
+            (9, 1, 11),
+            (50, 5, 7),
+            (108, 12, 0),
+            (113, 12, 0),
+        ] {
+            let mut corrupted = compressed.clone();
+            corrupted[index] ^= 1 << 20;
+            assert_eq!(
+                forward(&corrupted),
+                Err(ChecksumReadError::ChecksumMismatch {
+                    block: forward_block
+                })
+            );
+            assert_eq!(
+                backward(&corrupted),
+                Err(ChecksumReadError::ChecksumMismatch {
+                    block: backward_block
+                })
+            );
+        }
+
+        // Truncation at a block boundary loses the trailer.
+        let truncated = compressed[..9 * 5].to_vec();
+        assert!(forward(&truncated).is_err());
+        assert!(backward(&truncated).is_err());
+        assert_eq!(
+            forward(&compressed[..1].to_vec()),
+            Err(ChecksumReadError::Truncated { block: 0 })
+        );
+        assert_eq!(
+            backward(&Vec::new()),
+            Err(ChecksumReadError::Truncated { block: 0 })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "async")]
+    fn async_read_words() {
+        use super::{AsyncReadWords, AsyncWriteWords};
+
+        let words = (0..100u32)
+            .map(|i| i.wrapping_mul(0x6979_E2F3))
+            .collect::<Vec<_>>();
+        futures::executor::block_on(async {
+            let mut backend =
+                AsyncWriteWords::<_, BigEndian>::new(futures::io::Cursor::new(Vec::new()));
+            backend.extend_from_iter(words.iter().cloned()).unwrap();
+            assert_eq!(backend.num_buffered_bytes(), 400);
+            backend.flush().await.unwrap();
+            assert_eq!(backend.num_buffered_bytes(), 0);
+            let bytes = backend.into_inner().into_inner();
+
+            let mut backend = AsyncReadWords::<u32, _, BigEndian>::new(&bytes[..]);
+            let err = ReadWords::<u32, Queue>::read(&mut backend).unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::WouldBlock);
+
+            backend.fill(3).await.unwrap();
+            assert!(backend.num_buffered_words() >= 3);
+            for &expected in &words {
+                if backend.num_buffered_words() == 0 {
+                    backend.fill(1).await.unwrap();
+                }
+                assert_eq!(
+                    ReadWords::<u32, Queue>::read(&mut backend).unwrap(),
+                    Some(expected)
+                );
+            }
+            backend.fill(1).await.unwrap();
+            assert!(backend.reached_end());
+            assert_eq!(ReadWords::<u32, Queue>::read(&mut backend).unwrap(), None);
+
+            let mut backend = AsyncReadWords::<u32, _, BigEndian>::new(&bytes[..7]);
+            backend.fill(2).await.unwrap_err();
+        });
+    }
+
+    #[test]
+    fn ring_buffer() {
+        let mut ring = RingBuffer::<u16, 5>::new();
+        assert_eq!(ring.capacity(), 5);
+        let mut writer = &ring;
+        let mut reader = &ring;
+
+        for round in 0..4u16 {
+            for i in 0..5 {
+                assert_eq!(writer.write(10 * round + i), Ok(()));
+            }
+            assert!(ring.is_full());
+            assert!(writer.maybe_full());
+            assert_eq!(writer.write(99), Err(BoundedWriteError::OutOfSpace));
+            assert_eq!(ring.len(), 5);
+
+            for i in 0..3 {
+                assert_eq!(reader.read(), Ok(Some(10 * round + i)));
+            }
+            assert_eq!(BoundedReadWords::<u16, Queue>::remaining(&reader), 2);
+            assert_eq!(BoundedWriteWords::space_left(&writer), 3);
+            for i in 3..5 {
+                assert_eq!(reader.read(), Ok(Some(10 * round + i)));
+            }
+            assert!(ring.is_empty());
+            assert_eq!(reader.read(), Err(RingBufferEmptyError));
+            assert_eq!(reader.read(), Err(RingBufferEmptyError));
+
+            // Shift the head so that the next round wraps around.
+            writer.write(1000).unwrap();
+            assert_eq!(reader.read(), Ok(Some(1000)));
+        }
+
+        writer.write(7).unwrap();
+        ring.close();
+        assert_eq!(ring.space_left(), 0);
+        let mut writer = &ring;
+        let mut reader = &ring;
+        assert_eq!(writer.write(8), Err(BoundedWriteError::OutOfSpace));
+        assert_eq!(reader.read(), Ok(Some(7)));
+        assert_eq!(reader.read(), Ok(None));
+        assert_eq!(reader.read(), Ok(None));
+
+        ring.clear();
+        assert!(!ring.is_closed());
+        assert_eq!(ring.space_left(), 5);
+    }
+
+    #[test]
+    fn ring_buffer_pipeline() {
+        fn pipeline<const CAPACITY: usize>(amt: usize) {
+            let quantizer = SmallLeakyQuantizer::new(-256..=255);
+            let model = quantizer.quantize(Gaussian::new(0.0, 3.0));
+            let symbols = cheap_symbols(amt as u32)
+                .into_iter()
+                .map(|s| s / 64)
+                .collect::<Vec<_>>();
+
+            let ring = RingBuffer::<u16, CAPACITY>::new();
+            let mut encoder = SmallRangeEncoder::with_backend(&ring);
+            let mut decoder = None;
+            let mut decoded = Vec::new();
+            let mut max_len = 0;
+
+            let mut drain = |decoder: &mut Option<_>| {
+                if decoder.is_none() && (ring.len() >= 2 || ring.is_closed()) {
+                    *decoder = Some(SmallRangeDecoder::with_backend(&ring).unwrap());
+                }
+                if let Some(decoder) = decoder {
+                    while decoded.len() != symbols.len() && (!ring.is_empty() || ring.is_closed()) {
+                        decoded.push(decoder.decode_symbol(&model).unwrap());
+                    }
+                }
+            };
+
+            for symbol in &symbols {
+                if ring.space_left() < encoder.max_num_words_next_symbol() {
+                    drain(&mut decoder);
+                }
+                encoder.encode_symbol(symbol, &model).unwrap();
+                max_len = max_len.max(ring.len());
+            }
+            if ring.space_left() < encoder.num_seal_words() {
+                drain(&mut decoder);
+            }
+            encoder.into_compressed().unwrap();
+            ring.close();
+            drain(&mut decoder);
+
+            assert_eq!(decoded, symbols);
+            assert!(ring.is_empty());
+            assert!(max_len <= CAPACITY);
+        }
+
+        for amt in [0, 1, 2, 10, 100, 10000] {
+            pipeline::<4>(amt);
+            pipeline::<5>(amt);
+            pipeline::<64>(amt);
+        }
+    }
+
+    #[test]
+    fn frames() {
+        let quantizer = DefaultLeakyQuantizer::new(-256..=255);
+        let model = quantizer.quantize(Gaussian::new(0.0, 100.0));
+        let records = (0..50).map(|i| cheap_symbols(i * 7)).collect::<Vec<_>>();
+
+        let mut frames = FrameWriter::<u32>::new();
+        for (i, record) in records.iter().enumerate() {
+            if i % 2 == 0 {
+                let mut encoder = DefaultRangeEncoder::with_backend(frames.frame_sink());
+                encoder.encode_iid_symbols(record, &model).unwrap();
+                let sink = encoder.into_compressed().unwrap();
+                assert!(sink.len() <= 1 + record.len() / 2);
+            } else {
+                let mut encoder = DefaultAnsCoder::from_compressed(frames.frame_sink()).unwrap();
+                encoder.encode_iid_symbols_reverse(record, &model).unwrap();
+                encoder.into_compressed().unwrap().finish();
+            }
+        }
+        frames.push_frame(&[1, 2, 3]);
+        assert_eq!(frames.num_frames(), records.len() + 1);
+
+        let mut reader = frames.reader();
+        for (i, record) in records.iter().enumerate() {
+            if i % 2 == 0 {
+                let mut decoder = reader.next_range_decoder::<u64>().unwrap().unwrap();
+                let decoded = decoder
+                    .decode_iid_symbols(record.len(), &model)
+                    .collect::<Result<Vec<_>, _>>()
+                    .unwrap();
+                assert_eq!(&decoded, record);
+                assert!(decoder.maybe_exhausted());
+            } else {
+                let mut decoder = reader.next_ans_decoder::<u64>().unwrap().unwrap();
+                let decoded = decoder
+                    .decode_iid_symbols(record.len(), &model)
+                    .collect::<Result<Vec<_>, _>>()
+                    .unwrap();
+                assert_eq!(&decoded, record);
+                assert!(decoder.is_empty());
+            }
+        }
+        assert_eq!(reader.next_frame(), Ok(Some(&[1, 2, 3][..])));
+        assert_eq!(reader.next_frame(), Ok(None));
+        assert!(reader.is_exhausted());
+
+        // Skipping frames.
+        let mut reader = frames.reader();
+        reader.skip_frames(31).unwrap();
+        let mut decoder = reader.next_ans_decoder::<u64>().unwrap().unwrap();
+        let decoded = decoder
+            .decode_iid_symbols(records[31].len(), &model)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(decoded, records[31]);
+        assert_eq!(reader.count(), records.len() - 31);
+
+        // Truncated buffer.
+        let compressed = frames.as_slice();
+        let mut reader = FrameReader::new(&compressed[..compressed.len() - 1]);
+        assert_eq!(
+            reader.skip_frames(records.len() + 1),
+            Err(FrameReadError::Truncated)
+        );
+    }
+
+    #[test]
+    fn frames_with_multi_word_length_prefix() {
+        let lengths = [0, 1, 127, 128, 129, 16383, 16384, 20000];
+        let data = (0..20000u32).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+
+        let mut frames = FrameWriter::<u8>::new();
+        for (i, &len) in lengths.iter().enumerate() {
+            if i % 2 == 0 {
+                frames.push_frame(&data[..len]);
+            } else {
+                let mut sink = frames.frame_sink();
+                sink.extend_from_iter(data[..len].iter().cloned()).unwrap();
+                assert_eq!(sink.len(), len);
+            }
+        }
+        assert_eq!(frames.as_slice()[..2], [0, 1]);
+        assert_eq!(frames.as_slice()[3], 127);
+        assert_eq!(frames.as_slice()[131..133], [0x80, 1]);
+
+        let frames = frames.into_vec();
+        let reader = FrameReader::new(&frames[..]);
+        let decoded = reader.collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(decoded.len(), lengths.len());
+        for (frame, &len) in decoded.iter().zip(&lengths) {
+            assert_eq!(*frame, &data[..len]);
+        }
+
+        let mut reader = FrameReader::new(&frames[..]);
+        reader.skip_frames(6).unwrap();
+        assert_eq!(reader.next_frame(), Ok(Some(&data[..16384])));
+
+        // Truncated length prefix.
+        let mut reader = FrameReader::new(&[0x80u8, 0x80][..]);
+        assert_eq!(reader.next_frame(), Err(FrameReadError::Truncated));
+
+        // Length prefix that doesn't fit into a `usize`.
+        let mut overflow = [0xffu8; 12];
+        overflow[11] = 1;
+        let mut reader = FrameReader::new(&overflow[..]);
+        assert_eq!(reader.next_frame(), Err(FrameReadError::LengthOverflow));
+    }
+
+    #[test]
+    fn tee() {
+        let quantizer = DefaultLeakyQuantizer::new(-256..=255);
+        let model = quantizer.quantize(Gaussian::new(0.0, 100.0));
+        let symbols = cheap_symbols(100);
+
+        let mut reference = DefaultRangeEncoder::new();
+        reference.encode_iid_symbols(&symbols, &model).unwrap();
+        let reference = reference.into_compressed().unwrap();
+
+        let mut via_callback = Vec::new();
+        let backend = Tee(
+            CountingWriteWords::new(),
+            Tee(
+                Vec::new(),
+                FallibleCallbackWriteWords::new(|word| {
+                    via_callback.push(word);
+                    Ok::<(), Infallible>(())
+                }),
+            ),
+        );
+        let mut encoder = DefaultRangeEncoder::with_backend(backend);
+        encoder.encode_iid_symbols(&symbols, &model).unwrap();
+        let Tee(counter, Tee(compressed, _)) = encoder.into_compressed().unwrap();
+        assert_eq!(counter.num_words(), reference.len());
+        assert_eq!(compressed, reference);
+        assert_eq!(via_callback, reference);
+
+        // Both children are bounded.
+        let mut small = [0u32; 3];
+        let mut large = [0u32; 5];
+        let mut backend = Tee(
+            Cursor::new_at_write_beginning(&mut small[..]),
+            Cursor::new_at_write_beginning(&mut large[..]),
+        );
+        assert_eq!(backend.space_left(), 3);
+        for word in 1..=3 {
+            assert!(!backend.is_full());
+            backend.write(word).unwrap();
+        }
+        assert!(backend.is_full());
+        assert_eq!(
+            backend.write(4),
+            Err(TeeWriteError::First(BoundedWriteError::OutOfSpace))
+        );
+        assert_eq!(backend.1.space_left(), 2);
+
+        let mut backend = Tee(
+            Cursor::new_at_write_beginning(&mut large[..]),
+            Cursor::new_at_write_beginning(&mut small[..]),
+        );
+        backend.extend_from_iter(5..8).unwrap();
+        assert_eq!(
+            backend.write(8),
+            Err(TeeWriteError::Second(BoundedWriteError::OutOfSpace))
+        );
+        assert_eq!(backend.0.space_left(), 1);
+        assert_eq!(small, [5, 6, 7]);
+        assert_eq!(large, [5, 6, 7, 8, 0]);
+    }
+
+    #[test]
+    fn push_read_words() {
+        let mut backend = PushReadWords::<u16>::new();
+        assert!(ReadWords::<u16, Queue>::maybe_exhausted(&backend));
+        assert_eq!(backend.read(), Err(NeedMoreInput));
+
+        backend.push(3);
+        backend.extend([4, 5]);
+        assert_eq!(BoundedReadWords::<u16, Queue>::remaining(&backend), 3);
+        assert_eq!(backend.read(), Ok(Some(3)));
+        assert_eq!(backend.read(), Ok(Some(4)));
+        assert_eq!(backend.read(), Ok(Some(5)));
+        assert_eq!(backend.read(), Err(NeedMoreInput));
+        assert_eq!(backend.read(), Err(NeedMoreInput));
+
+        // Reads can be undone until the next push.
+        assert!(backend.unread(2));
+        assert_eq!(backend.len(), 2);
+        assert_eq!(backend.read(), Ok(Some(4)));
+        assert!(!backend.unread(3));
+        assert_eq!(backend.read(), Ok(Some(5)));
+
+        backend.push(6);
+        assert!(!backend.unread(1));
+        backend.close();
+        assert!(backend.is_closed());
+        assert_eq!(backend.read(), Ok(Some(6)));
+        assert_eq!(backend.read(), Ok(None));
+        assert_eq!(backend.read(), Ok(None));
+    }
+
+    #[test]
+    #[should_panic]
+    fn push_read_words_closed() {
+        let mut backend = PushReadWords::<u32>::new();
+        backend.close();
+        backend.push(1);
     }
 }
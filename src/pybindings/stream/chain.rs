@@ -249,6 +249,9 @@ impl From<EncoderFrontendError> for PyErr {
             EncoderFrontendError::OutOfRemainders => {
                 pyo3::exceptions::PyAssertionError::new_err(err.to_string())
             }
+            EncoderFrontendError::InvalidRemainders => {
+                pyo3::exceptions::PyValueError::new_err(err.to_string())
+            }
         }
     }
 }
@@ -0,0 +1,760 @@
+//! Adaptive binary arithmetic coding with context modeling (as in CABAC)
+//!
+//! This module provides a [`BinaryEncoder`] and a [`BinaryDecoder`] for sequences of binary
+//! decisions (bits) whose statistics change slowly over time, such as significance flags
+//! or sign bits in image and video codecs. Each bit is coded with respect to a *context*
+//! (see [`BitContext`]), i.e., a small object that holds an estimate of the probability
+//! that the next bit is `true` and that updates this estimate after each coded bit. You
+//! typically keep one context for each kind of decision (e.g., one context per position
+//! in a block) so that each context learns the statistics of its own kind of decision.
+//! This is the approach taken by Context-Adaptive Binary Arithmetic Coding (CABAC) in the
+//! H.264 and H.265 video coding standards.
+//!
+//! Two kinds of contexts are provided:
+//! - a [`ShiftContext`] updates its probability estimate with an exponential moving
+//!   average whose rate is `2^-shift` for a configurable `shift`; and
+//! - a [`DualRateContext`] averages two such estimates with a fast and a slow rate, which
+//!   combines fast adaption at the beginning of a sequence with a precise estimate at the
+//!   end (as in H.266/VVC).
+//!
+//! You can also implement the trait [`BitContext`] for your own context types.
+//!
+//! Both coders have *queue* semantics ("first in first out"). They are thin wrappers
+//! around a [`RangeEncoder`] and a [`RangeDecoder`], respectively, and therefore support
+//! all backends from the [`backends`] module. They don't construct any entropy models
+//! on the heap; each bit is coded directly with the current probability estimate of its
+//! context. If you need to code a mix of binary decisions and symbols from larger
+//! alphabets, use [`inner_mut`](BinaryEncoder::inner_mut) to access the wrapped range
+//! coder.
+//!
+//! # Example
+//!
+//! ```
+//! use constriction::stream::binary::{
+//!     BitContext, DefaultBinaryDecoder, DefaultBinaryEncoder, DualRateContext, ShiftContext,
+//! };
+//!
+//! // Some highly skewed bits, and some less skewed ones.
+//! let flags = (0..1000).map(|i| i % 20 == 0).collect::<Vec<_>>();
+//! let signs = (0..1000).map(|i| i % 3 == 0).collect::<Vec<_>>();
+//!
+//! let mut encoder = DefaultBinaryEncoder::new();
+//! let mut flag_context = ShiftContext::new(5);
+//! let mut sign_context = DualRateContext::new(4, 7);
+//! for (&flag, &sign) in flags.iter().zip(&signs) {
+//!     encoder.encode_bit(&mut flag_context, flag).unwrap();
+//!     encoder.encode_bit(&mut sign_context, sign).unwrap();
+//! }
+//! let compressed = encoder.into_compressed().unwrap();
+//! assert!(compressed.len() * 32 < 1500); // Much less than one bit per bit.
+//!
+//! // The decoder has to start with the same initial contexts.
+//! let mut decoder = DefaultBinaryDecoder::from_compressed(compressed).unwrap();
+//! let mut flag_context = ShiftContext::new(5);
+//! let mut sign_context = DualRateContext::new(4, 7);
+//! for (&flag, &sign) in flags.iter().zip(&signs) {
+//!     assert_eq!(decoder.decode_bit(&mut flag_context).unwrap(), flag);
+//!     assert_eq!(decoder.decode_bit(&mut sign_context).unwrap(), sign);
+//! }
+//! assert!(decoder.maybe_exhausted());
+//! ```
+//!
+//! [`backends`]: crate::backends
+
+use alloc::vec::Vec;
+use core::{borrow::Borrow, num::NonZeroU16};
+
+use num_traits::AsPrimitive;
+
+use super::{
+    model::{DecoderModel, EncoderModel, EntropyModel},
+    queue::{DecoderFrontendError, RangeDecoder, RangeEncoder},
+    Decode, Encode,
+};
+use crate::{
+    backends::{AsReadWords, BoundedReadWords, Cursor, IntoReadWords, ReadWords, WriteWords},
+    BitArray, CoderError, Queue,
+};
+
+/// Number of bits of the fixed point representation of probabilities of bits.
+pub const BIT_PRECISION: usize = 16;
+
+const HALF: u16 = 1 << (BIT_PRECISION - 1);
+
+/// An adaptive estimate of the probability distribution of a binary decision.
+///
+/// See [module level documentation](self).
+pub trait BitContext {
+    /// Returns the current estimate of the probability that the next bit is `true`, in
+    /// units of `2^-16`.
+    ///
+    /// The coders treat a return value of zero like a return value of one since bits must
+    /// not have zero probability.
+    fn probability_of_one(&self) -> u16;
+
+    /// Updates the estimate after a bit has been encoded or decoded.
+    fn update(&mut self, bit: bool);
+}
+
+impl<C: BitContext> BitContext for &mut C {
+    #[inline(always)]
+    fn probability_of_one(&self) -> u16 {
+        (**self).probability_of_one()
+    }
+
+    #[inline(always)]
+    fn update(&mut self, bit: bool) {
+        (**self).update(bit)
+    }
+}
+
+/// A [`BitContext`] that adapts with a single rate.
+///
+/// After each bit, moves its probability estimate `p` a fraction `2^-shift` towards the
+/// observed bit, i.e., `p <- p + (bit - p) / 2^shift`. Smaller values of `shift` adapt
+/// faster but lead to noisier estimates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ShiftContext {
+    /// Invariant: `probability_of_one != 0`
+    probability_of_one: u16,
+    shift: u8,
+}
+
+impl ShiftContext {
+    /// Creates a context with an initial probability estimate of one half.
+    ///
+    /// # Panics
+    ///
+    /// If `shift` is not in `1..=15`.
+    pub fn new(shift: u32) -> Self {
+        Self::with_probability_of_one(HALF, shift)
+    }
+
+    /// Creates a context with a given initial estimate of the probability that a bit is
+    /// `true`, in units of `2^-16`.
+    ///
+    /// # Panics
+    ///
+    /// If `shift` is not in `1..=15` or if `probability_of_one == 0`.
+    pub fn with_probability_of_one(probability_of_one: u16, shift: u32) -> Self {
+        assert!((1..BIT_PRECISION as u32).contains(&shift));
+        assert!(probability_of_one != 0);
+        Self {
+            probability_of_one,
+            shift: shift as u8,
+        }
+    }
+
+    /// Returns the adaption rate, see [`new`](Self::new).
+    pub fn shift(&self) -> u32 {
+        self.shift as u32
+    }
+}
+
+impl BitContext for ShiftContext {
+    #[inline(always)]
+    fn probability_of_one(&self) -> u16 {
+        self.probability_of_one
+    }
+
+    #[inline(always)]
+    fn update(&mut self, bit: bool) {
+        self.probability_of_one = update_estimate(self.probability_of_one, self.shift, bit);
+    }
+}
+
+/// A [`BitContext`] that averages two estimates with different adaption rates.
+///
+/// Keeps two estimates, which are updated in the same way as in a [`ShiftContext`] with
+/// shifts `fast_shift` and `slow_shift`, respectively, and uses the mean of both as the
+/// probability estimate. The fast estimate adapts quickly to the statistics at the
+/// beginning of a sequence or after a sudden change, while the slow estimate is more
+/// precise for stationary statistics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DualRateContext {
+    /// Invariant: `fast != 0`
+    fast: u16,
+
+    /// Invariant: `slow != 0`
+    slow: u16,
+
+    fast_shift: u8,
+    slow_shift: u8,
+}
+
+impl DualRateContext {
+    /// Creates a context with an initial probability estimate of one half.
+    ///
+    /// # Panics
+    ///
+    /// If `fast_shift` or `slow_shift` is not in `1..=15`.
+    pub fn new(fast_shift: u32, slow_shift: u32) -> Self {
+        Self::with_probability_of_one(HALF, fast_shift, slow_shift)
+    }
+
+    /// Creates a context with a given initial estimate of the probability that a bit is
+    /// `true`, in units of `2^-16`.
+    ///
+    /// # Panics
+    ///
+    /// If `fast_shift` or `slow_shift` is not in `1..=15` or if `probability_of_one == 0`.
+    pub fn with_probability_of_one(
+        probability_of_one: u16,
+        fast_shift: u32,
+        slow_shift: u32,
+    ) -> Self {
+        assert!((1..BIT_PRECISION as u32).contains(&fast_shift));
+        assert!((1..BIT_PRECISION as u32).contains(&slow_shift));
+        assert!(probability_of_one != 0);
+        Self {
+            fast: probability_of_one,
+            slow: probability_of_one,
+            fast_shift: fast_shift as u8,
+            slow_shift: slow_shift as u8,
+        }
+    }
+
+    /// Returns the adaption rates `(fast_shift, slow_shift)`, see [`new`](Self::new).
+    pub fn shifts(&self) -> (u32, u32) {
+        (self.fast_shift as u32, self.slow_shift as u32)
+    }
+}
+
+impl BitContext for DualRateContext {
+    #[inline(always)]
+    fn probability_of_one(&self) -> u16 {
+        ((self.fast as u32 + self.slow as u32) / 2) as u16
+    }
+
+    #[inline(always)]
+    fn update(&mut self, bit: bool) {
+        self.fast = update_estimate(self.fast, self.fast_shift, bit);
+        self.slow = update_estimate(self.slow, self.slow_shift, bit);
+    }
+}
+
+/// Maps `1..=0xFFFF` to `1..=0xFFFF` as long as `1 <= shift <= 15`.
+#[inline(always)]
+fn update_estimate(probability_of_one: u16, shift: u8, bit: bool) -> u16 {
+    if bit {
+        probability_of_one + (((1u32 << BIT_PRECISION) - probability_of_one as u32) >> shift) as u16
+    } else {
+        probability_of_one - (probability_of_one >> shift)
+    }
+}
+
+/// Entropy model for a single bit, which we construct on the fly for each coded bit.
+#[derive(Debug, Clone, Copy)]
+struct BitModel {
+    probability_of_one: NonZeroU16,
+}
+
+impl BitModel {
+    #[inline(always)]
+    fn new(probability_of_one: u16) -> Self {
+        Self {
+            probability_of_one: NonZeroU16::new(probability_of_one)
+                .unwrap_or(NonZeroU16::new(1).expect("1 != 0")),
+        }
+    }
+
+    #[inline(always)]
+    fn probability_of_zero(self) -> NonZeroU16 {
+        // Can't be zero because `probability_of_one` can't be zero.
+        NonZeroU16::new(self.probability_of_one.get().wrapping_neg()).expect("nonzero")
+    }
+}
+
+impl EntropyModel<BIT_PRECISION> for BitModel {
+    type Symbol = bool;
+    type Probability = u16;
+}
+
+impl EncoderModel<BIT_PRECISION> for BitModel {
+    #[inline(always)]
+    fn left_cumulative_and_probability(
+        &self,
+        symbol: impl Borrow<bool>,
+    ) -> Option<(u16, NonZeroU16)> {
+        let probability_of_zero = self.probability_of_zero();
+        Some(if *symbol.borrow() {
+            (probability_of_zero.get(), self.probability_of_one)
+        } else {
+            (0, probability_of_zero)
+        })
+    }
+}
+
+impl DecoderModel<BIT_PRECISION> for BitModel {
+    #[inline(always)]
+    fn quantile_function(&self, quantile: u16) -> (bool, u16, NonZeroU16) {
+        let probability_of_zero = self.probability_of_zero();
+        if quantile < probability_of_zero.get() {
+            (false, 0, probability_of_zero)
+        } else {
+            (true, probability_of_zero.get(), self.probability_of_one)
+        }
+    }
+}
+
+/// Encoder for adaptive binary arithmetic coding.
+///
+/// See [module level documentation](self) for a discussion and an example.
+#[derive(Debug, Clone)]
+pub struct BinaryEncoder<Word, State, Backend = Vec<Word>>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+    Backend: WriteWords<Word>,
+{
+    inner: RangeEncoder<Word, State, Backend>,
+}
+
+/// Type alias for a [`BinaryEncoder`] with sane parameters for typical use cases.
+pub type DefaultBinaryEncoder<Backend = Vec<u32>> = BinaryEncoder<u32, u64, Backend>;
+
+/// Type alias for a [`BinaryEncoder`] with a smaller word size and internal state.
+pub type SmallBinaryEncoder<Backend = Vec<u16>> = BinaryEncoder<u16, u32, Backend>;
+
+impl<Word, State> BinaryEncoder<Word, State>
+where
+    Word: BitArray + Into<State> + AsPrimitive<u16>,
+    State: BitArray + AsPrimitive<Word>,
+    u16: Into<Word>,
+{
+    /// Creates an empty encoder for in-memory data.
+    pub fn new() -> Self {
+        Self::with_backend(Vec::new())
+    }
+}
+
+impl<Word, State, Backend> Default for BinaryEncoder<Word, State, Backend>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+    Backend: WriteWords<Word> + Default,
+    Word: AsPrimitive<u16>,
+    u16: Into<Word>,
+{
+    fn default() -> Self {
+        Self::with_backend(Default::default())
+    }
+}
+
+impl<Word, State, Backend> From<RangeEncoder<Word, State, Backend>>
+    for BinaryEncoder<Word, State, Backend>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+    Backend: WriteWords<Word>,
+{
+    fn from(inner: RangeEncoder<Word, State, Backend>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<Word, State, Backend> BinaryEncoder<Word, State, Backend>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+    Backend: WriteWords<Word>,
+    u16: Into<Word>,
+    Word: AsPrimitive<u16>,
+{
+    /// Creates an empty encoder that writes to the provided backend.
+    ///
+    /// See [`RangeEncoder::with_backend`].
+    pub fn with_backend(backend: Backend) -> Self {
+        Self {
+            inner: RangeEncoder::with_backend(backend),
+        }
+    }
+
+    /// Encodes a single bit with the current estimate of `context` and then updates
+    /// `context`.
+    #[inline(always)]
+    pub fn encode_bit(
+        &mut self,
+        mut context: impl BitContext,
+        bit: bool,
+    ) -> Result<(), Backend::WriteError> {
+        let model = BitModel::new(context.probability_of_one());
+        context.update(bit);
+        self.encode_with_model(model, bit)
+    }
+
+    /// Encodes a bit with probability one half, without any context (also called a
+    /// "bypass" bit).
+    #[inline(always)]
+    pub fn encode_bypass_bit(&mut self, bit: bool) -> Result<(), Backend::WriteError> {
+        self.encode_with_model(BitModel::new(HALF), bit)
+    }
+
+    /// Encodes the `num_bits` least significant bits of `bits` as bypass bits (starting
+    /// with the most significant one).
+    pub fn encode_bypass_bits(
+        &mut self,
+        bits: u32,
+        num_bits: usize,
+    ) -> Result<(), Backend::WriteError> {
+        assert!(num_bits <= 32);
+        for i in (0..num_bits).rev() {
+            self.encode_bypass_bit(bits & (1 << i) != 0)?;
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn encode_with_model(&mut self, model: BitModel, bit: bool) -> Result<(), Backend::WriteError> {
+        self.inner
+            .encode_symbol(bit, model)
+            .map_err(|err| match err {
+                CoderError::Frontend(_) => unreachable!("Bits always have nonzero probability."),
+                CoderError::Backend(err) => err,
+            })
+    }
+
+    /// Terminates the compressed data and returns the backend.
+    ///
+    /// See [`RangeEncoder::into_compressed`].
+    pub fn into_compressed(self) -> Result<Backend, Backend::WriteError> {
+        self.inner.into_compressed()
+    }
+
+    /// See [`RangeEncoder::num_words`].
+    pub fn num_words<'a>(&'a self) -> usize
+    where
+        Backend: AsReadWords<'a, Word, Queue>,
+        Backend::AsReadWords: BoundedReadWords<Word, Queue>,
+    {
+        self.inner.num_words()
+    }
+
+    /// Returns the wrapped [`RangeEncoder`].
+    pub fn inner(&self) -> &RangeEncoder<Word, State, Backend> {
+        &self.inner
+    }
+
+    /// Returns the wrapped [`RangeEncoder`] mutably, e.g., to interleave the encoding of
+    /// bits with the encoding of symbols from larger alphabets.
+    pub fn inner_mut(&mut self) -> &mut RangeEncoder<Word, State, Backend> {
+        &mut self.inner
+    }
+
+    /// Consumes the `BinaryEncoder` and returns the wrapped [`RangeEncoder`].
+    pub fn into_inner(self) -> RangeEncoder<Word, State, Backend> {
+        self.inner
+    }
+}
+
+/// Decoder for adaptive binary arithmetic coding.
+///
+/// See [module level documentation](self) for a discussion and an example.
+#[derive(Debug, Clone)]
+pub struct BinaryDecoder<Word, State, Backend>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+    Backend: ReadWords<Word, Queue>,
+{
+    inner: RangeDecoder<Word, State, Backend>,
+}
+
+/// Type alias for a [`BinaryDecoder`] with sane parameters for typical use cases.
+pub type DefaultBinaryDecoder<Backend = Cursor<u32, Vec<u32>>> = BinaryDecoder<u32, u64, Backend>;
+
+/// Type alias for a [`BinaryDecoder`] with a smaller word size and internal state.
+pub type SmallBinaryDecoder<Backend> = BinaryDecoder<u16, u32, Backend>;
+
+impl<Word, State, Backend> From<RangeDecoder<Word, State, Backend>>
+    for BinaryDecoder<Word, State, Backend>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+    Backend: ReadWords<Word, Queue>,
+{
+    fn from(inner: RangeDecoder<Word, State, Backend>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<Word, State, Backend> BinaryDecoder<Word, State, Backend>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+    Backend: ReadWords<Word, Queue>,
+    u16: Into<Word>,
+    Word: AsPrimitive<u16>,
+{
+    /// Creates a decoder for the provided compressed data.
+    ///
+    /// See [`RangeDecoder::from_compressed`].
+    pub fn from_compressed<Buf>(compressed: Buf) -> Result<Self, Backend::ReadError>
+    where
+        Buf: IntoReadWords<Word, Queue, IntoReadWords = Backend>,
+    {
+        Ok(Self {
+            inner: RangeDecoder::from_compressed(compressed)?,
+        })
+    }
+
+    /// Creates a decoder that reads from the provided backend.
+    ///
+    /// See [`RangeDecoder::with_backend`].
+    pub fn with_backend(backend: Backend) -> Result<Self, Backend::ReadError> {
+        Ok(Self {
+            inner: RangeDecoder::with_backend(backend)?,
+        })
+    }
+
+    /// Decodes a single bit with the current estimate of `context` and then updates
+    /// `context`.
+    ///
+    /// The `context` must be in the same state as the context that was used to encode the
+    /// bit.
+    #[inline(always)]
+    pub fn decode_bit(
+        &mut self,
+        mut context: impl BitContext,
+    ) -> Result<bool, CoderError<DecoderFrontendError, Backend::ReadError>> {
+        let bit = self.decode_with_model(BitModel::new(context.probability_of_one()))?;
+        context.update(bit);
+        Ok(bit)
+    }
+
+    /// Decodes a bit that was encoded with
+    /// [`BinaryEncoder::encode_bypass_bit`].
+    #[inline(always)]
+    pub fn decode_bypass_bit(
+        &mut self,
+    ) -> Result<bool, CoderError<DecoderFrontendError, Backend::ReadError>> {
+        self.decode_with_model(BitModel::new(HALF))
+    }
+
+    /// Decodes bits that were encoded with [`BinaryEncoder::encode_bypass_bits`].
+    pub fn decode_bypass_bits(
+        &mut self,
+        num_bits: usize,
+    ) -> Result<u32, CoderError<DecoderFrontendError, Backend::ReadError>> {
+        assert!(num_bits <= 32);
+        let mut bits = 0u32;
+        for _ in 0..num_bits {
+            bits = (bits << 1) | self.decode_bypass_bit()? as u32;
+        }
+        Ok(bits)
+    }
+
+    #[inline(always)]
+    fn decode_with_model(
+        &mut self,
+        model: BitModel,
+    ) -> Result<bool, CoderError<DecoderFrontendError, Backend::ReadError>> {
+        self.inner.decode_symbol(model)
+    }
+
+    /// See [`RangeDecoder::maybe_exhausted`].
+    pub fn maybe_exhausted(&self) -> bool {
+        self.inner.maybe_exhausted()
+    }
+
+    /// Returns the wrapped [`RangeDecoder`].
+    pub fn inner(&self) -> &RangeDecoder<Word, State, Backend> {
+        &self.inner
+    }
+
+    /// Returns the wrapped [`RangeDecoder`] mutably, e.g., to interleave the decoding of
+    /// bits with the decoding of symbols from larger alphabets.
+    pub fn inner_mut(&mut self) -> &mut RangeDecoder<Word, State, Backend> {
+        &mut self.inner
+    }
+
+    /// Consumes the `BinaryDecoder` and returns the wrapped [`RangeDecoder`].
+    pub fn into_inner(self) -> RangeDecoder<Word, State, Backend> {
+        self.inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        model::DefaultContiguousCategoricalEntropyModel,
+        queue::{DefaultRangeDecoder, DefaultRangeEncoder},
+    };
+    use super::*;
+
+    use rand_xoshiro::{
+        rand_core::{RngCore, SeedableRng},
+        Xoshiro256StarStar,
+    };
+
+    #[test]
+    fn context_updates() {
+        let mut context = ShiftContext::new(4);
+        for _ in 0..1000 {
+            context.update(true);
+        }
+        assert!(context.probability_of_one() > 0xFFF0);
+        for _ in 0..1000 {
+            context.update(false);
+        }
+        assert!(context.probability_of_one() != 0);
+        assert!(context.probability_of_one() < 0x10);
+
+        let mut context = ShiftContext::with_probability_of_one(0xFFFF, 1);
+        context.update(true);
+        assert_eq!(context.probability_of_one(), 0xFFFF);
+        let mut context = ShiftContext::with_probability_of_one(1, 1);
+        context.update(false);
+        assert_eq!(context.probability_of_one(), 1);
+
+        // A dual-rate context follows sudden changes faster than a slow single-rate context
+        // but settles closer to the true probability than a fast one.
+        let mut fast = ShiftContext::new(3);
+        let mut slow = ShiftContext::new(8);
+        let mut dual = DualRateContext::new(3, 8);
+        for i in 0..2000 {
+            let bit = i % 10 == 0;
+            fast.update(bit);
+            slow.update(bit);
+            dual.update(bit);
+        }
+        let target = 0x1_0000 / 10;
+        let error = |p: u16| (p as i32 - target).abs();
+        assert!(error(dual.probability_of_one()) < error(fast.probability_of_one()));
+        for _ in 0..10 {
+            fast.update(true);
+            slow.update(true);
+            dual.update(true);
+        }
+        assert!(dual.probability_of_one() > slow.probability_of_one());
+    }
+
+    #[test]
+    fn round_trip() {
+        generic_round_trip::<u32, u64>();
+        generic_round_trip::<u16, u32>();
+        generic_round_trip::<u32, u128>();
+    }
+
+    fn generic_round_trip<Word, State>()
+    where
+        Word: BitArray + Into<State> + AsPrimitive<u16>,
+        State: BitArray + AsPrimitive<Word>,
+        u16: Into<Word>,
+    {
+        #[cfg(not(miri))]
+        const AMT: usize = 10000;
+
+        #[cfg(miri)]
+        const AMT: usize = 100;
+
+        let mut rng = Xoshiro256StarStar::seed_from_u64(Word::BITS as u64 ^ State::BITS as u64);
+
+        // Bits whose probability drifts slowly, assigned to one of 8 contexts.
+        let bits = (0..AMT)
+            .map(|i| {
+                let context_index = i % 8;
+                let threshold = ((i / 8) as u32 * 0x1_0000 / AMT as u32
+                    + context_index as u32 * 0x1000)
+                    & 0xFFFF;
+                (context_index, (rng.next_u32() & 0xFFFF) < threshold / 4)
+            })
+            .collect::<alloc::vec::Vec<_>>();
+        let bypass = (0..AMT / 8)
+            .map(|_| rng.next_u32())
+            .collect::<alloc::vec::Vec<_>>();
+
+        let mut encoder = BinaryEncoder::<Word, State>::new();
+        let mut shift_contexts = [ShiftContext::new(5); 4];
+        let mut dual_contexts = [DualRateContext::new(4, 7); 4];
+        for (i, &(context_index, bit)) in bits.iter().enumerate() {
+            if context_index < 4 {
+                encoder
+                    .encode_bit(&mut shift_contexts[context_index], bit)
+                    .unwrap();
+            } else {
+                encoder
+                    .encode_bit(&mut dual_contexts[context_index - 4], bit)
+                    .unwrap();
+            }
+            if i % 8 == 7 {
+                encoder.encode_bypass_bits(bypass[i / 8], 13).unwrap();
+            }
+        }
+        let num_words = encoder.num_words();
+        let compressed = encoder.into_compressed().unwrap();
+        assert_eq!(compressed.len(), num_words);
+
+        // Skewed bits should cost much less than one bit each.
+        let num_bypass_bits = 13 * (AMT / 8);
+        assert!(compressed.len() * Word::BITS < num_bypass_bits + AMT * 3 / 4);
+
+        let mut decoder = BinaryDecoder::<Word, State, _>::from_compressed(compressed).unwrap();
+        let mut shift_contexts = [ShiftContext::new(5); 4];
+        let mut dual_contexts = [DualRateContext::new(4, 7); 4];
+        for (i, &(context_index, bit)) in bits.iter().enumerate() {
+            let decoded = if context_index < 4 {
+                decoder.decode_bit(&mut shift_contexts[context_index])
+            } else {
+                decoder.decode_bit(&mut dual_contexts[context_index - 4])
+            };
+            assert_eq!(decoded.unwrap(), bit);
+            if i % 8 == 7 {
+                assert_eq!(
+                    decoder.decode_bypass_bits(13).unwrap(),
+                    bypass[i / 8] & ((1 << 13) - 1)
+                );
+            }
+        }
+        assert!(decoder.maybe_exhausted());
+    }
+
+    #[test]
+    fn extreme_probabilities() {
+        let mut encoder = DefaultBinaryEncoder::new();
+        let mut context = ShiftContext::with_probability_of_one(1, 1);
+        for i in 0..100 {
+            encoder.encode_bit(&mut context, i == 50).unwrap();
+        }
+        let mut context = ShiftContext::with_probability_of_one(0xFFFF, 1);
+        for i in 0..100 {
+            encoder.encode_bit(&mut context, i != 50).unwrap();
+        }
+        let compressed = encoder.into_compressed().unwrap();
+
+        let mut decoder = DefaultBinaryDecoder::from_compressed(compressed).unwrap();
+        let mut context = ShiftContext::with_probability_of_one(1, 1);
+        for i in 0..100 {
+            assert_eq!(decoder.decode_bit(&mut context).unwrap(), i == 50);
+        }
+        let mut context = ShiftContext::with_probability_of_one(0xFFFF, 1);
+        for i in 0..100 {
+            assert_eq!(decoder.decode_bit(&mut context).unwrap(), i != 50);
+        }
+    }
+
+    #[test]
+    fn mixed_with_other_symbols() {
+        let model = DefaultContiguousCategoricalEntropyModel::from_floating_point_probabilities(&[
+            0.1, 0.2, 0.3, 0.4,
+        ])
+        .unwrap();
+
+        let mut encoder = BinaryEncoder::from(DefaultRangeEncoder::new());
+        let mut context = DualRateContext::new(4, 7);
+        for i in 0..100 {
+            encoder.encode_bit(&mut context, i % 3 == 0).unwrap();
+            encoder.inner_mut().encode_symbol(i % 4, &model).unwrap();
+        }
+        let compressed = encoder.into_compressed().unwrap();
+
+        let mut decoder =
+            BinaryDecoder::from(DefaultRangeDecoder::from_compressed(compressed).unwrap());
+        let mut context = DualRateContext::new(4, 7);
+        for i in 0..100 {
+            assert_eq!(decoder.decode_bit(&mut context).unwrap(), i % 3 == 0);
+            assert_eq!(decoder.inner_mut().decode_symbol(&model).unwrap(), i % 4);
+        }
+        assert!(decoder.maybe_exhausted());
+    }
+}
@@ -12,6 +12,10 @@
 //! This property of treating symbols independently upon decoding can be useful for advanced
 //! compression methods that combine inference, quantization, and bits-back coding.
 //!
+//! The module also provides a [`QueueChainCoder`], which has the same property but operates
+//! as a queue (i.e., a first-in-first-out data structure), analogous to a [`RangeEncoder`]
+//! and [`RangeDecoder`].
+//!
 //! # Motivation
 //!
 //! The following example illustrates how decoding differs between an [`AnsCoder`] and a
@@ -88,20 +92,24 @@
 //! TODO
 //!
 //! [`AnsCoder`]: super::stack::AnsCoder
+//! [`RangeEncoder`]: super::queue::RangeEncoder
+//! [`RangeDecoder`]: super::queue::RangeDecoder
 
 use alloc::vec::Vec;
 
-use core::{borrow::Borrow, convert::Infallible, fmt::Display};
+use core::{borrow::Borrow, convert::Infallible, fmt::Display, num::NonZeroUsize};
 
 use num_traits::AsPrimitive;
 
 use super::{
     model::{DecoderModel, EncoderModel},
-    Code, Decode, Encode, TryCodingError,
+    queue::EncoderSituation,
+    Code, Decode, Encode, FinishError, TryCodingError,
 };
 use crate::{
-    backends::{ReadWords, WriteWords},
-    BitArray, CoderError, DefaultEncoderFrontendError, NonZeroBitArray, Pos, PosSeek, Seek, Stack,
+    backends::{BoundedReadWords, Cursor, ReadWords, WriteWords},
+    BitArray, CoderError, DefaultEncoderFrontendError, NonZeroBitArray, Pos, PosSeek, Queue, Seek,
+    Stack,
 };
 
 /// Experimental entropy coder for advanced variants of bitsback coding.
@@ -239,6 +247,10 @@ where
     remainders: RemaindersBackend,
 
     heads: ChainCoderHeads<Word, State, PRECISION>,
+
+    /// Words that encoding consumed from `remainders` since the most recent call to
+    /// [`ChainCoder::checkpoint`], or `None` if there is no valid checkpoint.
+    remainders_log: Option<Vec<Word>>,
 }
 
 /// Type of the internal state used by [`ChainCoder<Word, State>`]. Relevant for
@@ -300,6 +312,7 @@ impl<Word: BitArray, State: BitArray, const PRECISION: usize>
 
 pub type DefaultChainCoder = ChainCoder<u32, u64, Vec<u32>, Vec<u32>, 24>;
 pub type SmallChainCoder = ChainCoder<u16, u32, Vec<u16>, Vec<u16>, 12>;
+pub type LargeChainCoder = ChainCoder<u64, u128, Vec<u64>, Vec<u64>, 48>;
 
 impl<Word, State, CompressedBackend, RemaindersBackend, const PRECISION: usize>
     ChainCoder<Word, State, CompressedBackend, RemaindersBackend, PRECISION>
@@ -337,6 +350,7 @@ where
             compressed: data,
             remainders,
             heads,
+            remainders_log: None,
         })
     }
 
@@ -369,6 +383,7 @@ where
             compressed,
             remainders,
             heads,
+            remainders_log: None,
         })
     }
 
@@ -447,6 +462,7 @@ where
             compressed,
             remainders,
             heads,
+            remainders_log: None,
         })
     }
 
@@ -541,6 +557,77 @@ where
         self.heads.compressed.get() == Word::one()
     }
 
+    /// Checks that re-encoding consumed the remainders exactly.
+    ///
+    /// This method is for the decompression side of the usage cycle described in the
+    /// [struct level documentation](ChainCoder): call it after you've created a
+    /// `ChainCoder` with [`from_remainders`](Self::from_remainders) from the
+    /// `remainders_suffix` (without the `remainders_prefix`) and encoded all symbols back
+    /// onto it. It returns `Ok(())` if all remainders have been consumed and if there's an
+    /// integer amount of `Word`s in the compressed data (see [`is_whole`](Self::is_whole)),
+    /// which is the state in which the compressing `ChainCoder` started decoding.
+    /// Otherwise, it returns one of the following errors:
+    ///
+    /// - [`FinishError::UnreadWords`] if the remainders still contain some words that
+    ///   haven't been consumed (the variant holds their number); and
+    /// - [`FinishError::InvalidFinalState`] if all remainders have been consumed but the
+    ///   compressed data doesn't consist of an integer amount of `Word`s.
+    ///
+    /// Either error indicates that you encoded a different number of symbols than what was
+    /// decoded during compression, that the entropy models differ between compression and
+    /// decompression, or that the remainders were corrupted. Calling this method doesn't
+    /// modify the coder, so you can call [`into_binary`](Self::into_binary) or
+    /// [`into_compressed`](Self::into_compressed) afterwards.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use constriction::stream::{
+    ///     chain::DefaultChainCoder, model::DefaultLeakyQuantizer, Decode, FinishError,
+    /// };
+    /// use probability::distribution::Gaussian;
+    ///
+    /// let original_data = (0..100u32).map(
+    ///     |i| i.wrapping_mul(0xad5f_b2ed).wrapping_add(0xed55_4892)
+    /// ).collect::<Vec<_>>();
+    /// let quantizer = DefaultLeakyQuantizer::new(-100..=100);
+    /// let models = (0..50u32).map(|i| quantizer.quantize(Gaussian::new(i as f64, 10.0)));
+    ///
+    /// // Compression: decode symbols from `original_data`.
+    /// let mut coder = DefaultChainCoder::from_binary(original_data.clone()).unwrap();
+    /// let symbols = coder.decode_symbols(models.clone()).collect::<Result<Vec<_>, _>>().unwrap();
+    /// let (remainders_prefix, remainders_suffix) = coder.into_remainders().unwrap();
+    ///
+    /// // Decompression with too few symbols leaves some remainders unconsumed.
+    /// let mut coder = DefaultChainCoder::from_remainders(remainders_suffix.clone()).unwrap();
+    /// coder.encode_symbols_reverse(symbols.iter().zip(models.clone()).skip(10)).unwrap();
+    /// assert!(matches!(coder.finish(), Err(FinishError::UnreadWords(_))));
+    ///
+    /// // Decompression with all symbols passes the check.
+    /// let mut coder = DefaultChainCoder::from_remainders(remainders_suffix).unwrap();
+    /// coder.encode_symbols_reverse(symbols.iter().zip(models)).unwrap();
+    /// assert_eq!(coder.finish(), Ok(()));
+    ///
+    /// let (recovered_prefix, recovered_suffix) = coder.into_binary().unwrap();
+    /// assert!(recovered_prefix.is_empty());
+    /// let mut recovered = remainders_prefix;
+    /// recovered.extend_from_slice(&recovered_suffix);
+    /// assert_eq!(recovered, original_data);
+    /// ```
+    pub fn finish(&self) -> Result<(), FinishError>
+    where
+        RemaindersBackend: BoundedReadWords<Word, Stack>,
+    {
+        let num_unread = self.remainders.remaining();
+        if num_unread != 0 {
+            Err(FinishError::UnreadWords(num_unread))
+        } else if !self.is_whole() {
+            Err(FinishError::InvalidFinalState)
+        } else {
+            Ok(())
+        }
+    }
+
     pub fn encode_symbols_reverse<S, M, I>(
         &mut self,
         symbols_and_models: I,
@@ -619,6 +706,7 @@ where
                 compressed: self.heads.compressed,
                 remainders: self.heads.remainders,
             },
+            remainders_log: None,
         })
     }
 
@@ -648,6 +736,7 @@ where
                 compressed: self.heads.compressed,
                 remainders: self.heads.remainders,
             },
+            remainders_log: None,
         })
     }
 
@@ -749,11 +838,115 @@ where
             .read()
             .map_err(|err| CoderError::Backend(BackendError::Remainders(err)))?
             .ok_or(CoderError::Frontend(EncoderFrontendError::OutOfRemainders))?;
+        if let Some(log) = &mut self.remainders_log {
+            log.push(word);
+        }
         self.heads.remainders = (self.heads.remainders << Word::BITS) | word.into();
         Ok(())
     }
 }
 
+/// A snapshot of a [`ChainCoder`] that can be restored with [`ChainCoder::rollback`].
+///
+/// Obtained from [`ChainCoder::checkpoint`]. A checkpoint doesn't copy any compressed data
+/// or remainders, so it is cheap to create.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ChainCoderCheckpoint<Word: BitArray, State: BitArray, const PRECISION: usize> {
+    compressed_len: usize,
+    remainders_len: usize,
+    heads: ChainCoderHeads<Word, State, PRECISION>,
+}
+
+impl<Word, State, const PRECISION: usize> ChainCoder<Word, State, Vec<Word>, Vec<Word>, PRECISION>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+{
+    /// Records the current state of the coder so that you can undo subsequent encoding
+    /// operations with [`rollback`](Self::rollback).
+    ///
+    /// Encoding onto a `ChainCoder` consumes words from the remainders. In order to be able
+    /// to restore them upon [`rollback`](Self::rollback), the coder keeps a log of all
+    /// remainder words that it consumes after this method is called. Thus, the cost of a
+    /// checkpoint is proportional to the amount of data encoded since the checkpoint, not
+    /// to the total amount of data on the coder. Calling this method discards the log for
+    /// any previous checkpoint (see [`rollback`](Self::rollback) for implications).
+    ///
+    /// This method takes `&mut self` because it resets the log. Only encoding is supported
+    /// between a call to `checkpoint` and the corresponding call to `rollback`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use constriction::stream::{model::DefaultLeakyQuantizer, chain::DefaultChainCoder};
+    /// use probability::distribution::Gaussian;
+    ///
+    /// let quantizer = DefaultLeakyQuantizer::new(-100..=100);
+    /// let model = quantizer.quantize(Gaussian::new(0.0, 10.0));
+    /// let remainders = (0..100u32).map(|i| i.wrapping_mul(0xad5f_b2ed) | 1).collect::<Vec<_>>();
+    ///
+    /// let mut reference = DefaultChainCoder::from_remainders(remainders.clone()).unwrap();
+    /// reference.encode_iid_symbols_reverse(&[3, 1, 4], model).unwrap();
+    /// reference.encode_iid_symbols_reverse(&[1, 5], model).unwrap();
+    ///
+    /// let mut coder = DefaultChainCoder::from_remainders(remainders).unwrap();
+    /// coder.encode_iid_symbols_reverse(&[3, 1, 4], model).unwrap();
+    /// let checkpoint = coder.checkpoint();
+    ///
+    /// // Encode a candidate batch, then decide to undo it and encode a different batch.
+    /// coder.encode_iid_symbols_reverse(&[-50, 80, 95, -99], model).unwrap();
+    /// coder.rollback(checkpoint).unwrap();
+    /// coder.encode_iid_symbols_reverse(&[1, 5], model).unwrap();
+    ///
+    /// assert_eq!(coder.into_remainders().unwrap(), reference.into_remainders().unwrap());
+    /// ```
+    pub fn checkpoint(&mut self) -> ChainCoderCheckpoint<Word, State, PRECISION> {
+        match &mut self.remainders_log {
+            Some(log) => log.clear(),
+            None => self.remainders_log = Some(Vec::new()),
+        }
+
+        ChainCoderCheckpoint {
+            compressed_len: self.compressed.len(),
+            remainders_len: self.remainders.len(),
+            heads: self.heads,
+        }
+    }
+
+    /// Restores the state of the coder at the time when `checkpoint` was created.
+    ///
+    /// This discards all symbols that have been encoded since the call to
+    /// [`checkpoint`](Self::checkpoint) and returns all remainder words that were consumed
+    /// in the process. The compressed data is truncated in place, i.e., no compressed data
+    /// is copied. After a successful rollback, you can use the same checkpoint again.
+    ///
+    /// Returns `Err(())` and leaves the coder unchanged if the rollback isn't possible.
+    /// This happens in the following cases:
+    /// - if a symbol has been decoded or the coder has been [`seek`](Seek::seek)ed since
+    ///   the checkpoint was created (both invalidate all checkpoints);
+    /// - if a newer checkpoint has been created since `checkpoint` and some remainder
+    ///   words were consumed between the two checkpoints (i.e., only the most recent
+    ///   checkpoint is guaranteed to remain valid); or
+    /// - if `checkpoint` was created on a different coder and this can be detected.
+    #[allow(clippy::result_unit_err)]
+    pub fn rollback(
+        &mut self,
+        checkpoint: ChainCoderCheckpoint<Word, State, PRECISION>,
+    ) -> Result<(), ()> {
+        let log = self.remainders_log.as_mut().ok_or(())?;
+        if self.compressed.len() < checkpoint.compressed_len
+            || self.remainders.len() + log.len() != checkpoint.remainders_len
+        {
+            return Err(());
+        }
+
+        self.compressed.truncate(checkpoint.compressed_len);
+        self.remainders.extend(log.drain(..).rev());
+        self.heads = checkpoint.heads;
+        Ok(())
+    }
+}
+
 impl<Word, State, CompressedBackend, RemaindersBackend, const PRECISION: usize> Code
     for ChainCoder<Word, State, CompressedBackend, RemaindersBackend, PRECISION>
 where
@@ -809,9 +1002,14 @@ impl std::error::Error for DecoderFrontendError {}
 
 /// Frontend error type for misuse of a [`ChainCoder`] for encoding.
 #[derive(Debug, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum EncoderFrontendError {
     OutOfRemainders,
     ImpossibleSymbol,
+
+    /// Only returned by a [`QueueChainCoder`], if the remainders were not generated by
+    /// [`QueueChainCoder::into_remainders`].
+    InvalidRemainders,
 }
 
 impl core::fmt::Display for EncoderFrontendError {
@@ -821,6 +1019,9 @@ impl core::fmt::Display for EncoderFrontendError {
                 write!(f, "Out of remainders information from previous decoding.")
             }
             Self::ImpossibleSymbol => DefaultEncoderFrontendError::ImpossibleSymbol.fmt(f),
+            Self::InvalidRemainders => {
+                write!(f, "Invalid remainders information from previous decoding.")
+            }
         }
     }
 }
@@ -963,6 +1164,7 @@ where
         // `state` is valid since we don't provide a public API to modify fields of
         // `ChainCoderHeads` individually.
         self.heads = state;
+        self.remainders_log = None;
 
         Ok(())
     }
@@ -993,6 +1195,9 @@ where
         assert!(PRECISION != 0);
         assert!(State::BITS >= Word::BITS + PRECISION);
 
+        // Decoding invalidates any checkpoint (see `ChainCoder::rollback`).
+        self.remainders_log = None;
+
         let word = if PRECISION == Word::BITS
             || self.heads.compressed.get() < Word::one() << PRECISION
         {
@@ -1146,143 +1351,885 @@ where
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::super::model::LeakyQuantizer;
-    use super::*;
+/// Variant of a [`ChainCoder`] with queue semantics ("first in first out").
+///
+/// A `QueueChainCoder` has the same property as a [`ChainCoder`] that changing the entropy
+/// model for one symbol affects only that one symbol upon decoding. But while a
+/// `ChainCoder` operates as a stack (i.e., you have to encode symbols in reverse order
+/// when you recover the original data), a `QueueChainCoder` operates as a queue, i.e., you
+/// encode the symbols in the same order in which you decoded them. This is useful for
+/// bits-back coding in combination with a [`RangeEncoder`] or with autoregressive models.
+///
+/// A `QueueChainCoder` splits data into compressed data and remainders in the same way as
+/// a `ChainCoder` does: upon decoding, each symbol consumes the next `PRECISION` bits of
+/// the compressed data, and the part of these bits that is not needed to identify the
+/// symbol gets appended to the remainders. The difference is that the remainders are
+/// represented with range coder arithmetic rather than with ANS arithmetic, so that they
+/// come out in the same order in which they went in.
+///
+/// # Intended Usage
+///
+/// The usage cycle is the same as for a `ChainCoder` (see [`ChainCoder`]'s documentation),
+/// except that
+/// - you create the `QueueChainCoder` with [`from_binary`] from a backend that reads its
+///   data as a *queue* (e.g., a [`Cursor`]), and similarly for [`from_remainders`];
+/// - you encode the symbols *in the same order* in which you decoded them; and
+/// - the tuples returned by [`into_remainders`] and [`into_binary`] are the other way round
+///   than for a `ChainCoder`: their first entry (the "prefix") contains the newly
+///   generated data, and their second entry (the "suffix") contains the unaltered remaining
+///   data that the `QueueChainCoder` didn't need.
+///
+/// # Example
+///
+/// ```
+/// use constriction::{
+///     backends::Cursor,
+///     stream::{model::DefaultLeakyQuantizer, Decode, Encode, chain::DefaultQueueChainCoder},
+/// };
+/// use probability::distribution::Gaussian;
+///
+/// // Generate some sample binary data for demonstration purpose.
+/// let original_data = (0..100u32).map(
+///     |i| i.wrapping_mul(0xad5f_b2ed).wrapping_add(0xed55_4892)
+/// ).collect::<Vec<_>>();
+///
+/// // Decode some symbols from the original data.
+/// let mut coder: DefaultQueueChainCoder = DefaultQueueChainCoder::from_binary(Cursor::new_at_write_beginning(
+///     original_data.clone()
+/// ));
+/// let quantizer = DefaultLeakyQuantizer::new(-100..=100);
+/// let models = (0..50u32).map(|i| quantizer.quantize(Gaussian::new(i as f64, 10.0)));
+/// let symbols = coder.decode_symbols(models.clone()).collect::<Result<Vec<_>, _>>().unwrap();
+///
+/// // Export the remainders (prefix) and the part of `original_data` that wasn't used (suffix).
+/// let (remainders, unused_data) = coder.into_remainders().unwrap();
+/// let (unused_data, pos) = unused_data.into_buf_and_pos();
+/// let unused_data = &unused_data[pos..];
+/// // (verify that we've indeed reduced the amount of data:)
+/// assert!(remainders.len() + unused_data.len() < original_data.len());
+///
+/// // ... do something with the `symbols`, then recover the original data later:
+/// let mut coder = DefaultQueueChainCoder::<Vec<u32>, _>::from_remainders(
+///     Cursor::new_at_write_beginning(remainders)
+/// ).unwrap();
+/// coder.encode_symbols(symbols.into_iter().zip(models)).unwrap(); // (Same order as above.)
+/// let (mut recovered, _) = coder.into_binary().unwrap(); // (We used up all `remainders`.)
+/// recovered.extend_from_slice(unused_data);
+///
+/// assert_eq!(recovered, original_data);
+/// ```
+///
+/// [`RangeEncoder`]: super::queue::RangeEncoder
+/// [`Cursor`]: crate::backends::Cursor
+/// [`from_binary`]: Self::from_binary
+/// [`from_remainders`]: Self::from_remainders
+/// [`into_remainders`]: Self::into_remainders
+/// [`into_binary`]: Self::into_binary
+#[derive(Debug, Clone)]
+pub struct QueueChainCoder<
+    Word,
+    State,
+    CompressedBackend,
+    RemaindersBackend,
+    const PRECISION: usize,
+> where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+{
+    /// The compressed bit string. Read from by decoder, written to by encoder.
+    compressed: CompressedBackend,
 
-    use probability::distribution::Gaussian;
-    use rand_xoshiro::{
-        rand_core::{RngCore, SeedableRng},
-        Xoshiro256StarStar,
-    };
+    /// Leftover information from decoding. Written to by decoder, read from by encoder.
+    remainders: RemaindersBackend,
 
-    use alloc::vec;
+    heads: QueueChainCoderHeads<Word, State, PRECISION>,
+}
 
-    #[test]
-    fn restore_none() {
-        generic_restore_many::<u32, u64, u32, 24>(4, 0);
-    }
+/// Type of the internal state used by [`QueueChainCoder<Word, State>`]. Relevant for
+/// [`Seek`]ing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct QueueChainCoderHeads<Word: BitArray, State: BitArray, const PRECISION: usize> {
+    /// All bits following the highest order bit (which is a given in a `NonZero`) are
+    /// bits that have been read from `compressed` but not yet consumed (when decoding), or
+    /// that have not yet been written to `compressed` (when encoding).
+    compressed: Word::NonZero,
 
-    #[test]
-    fn restore_one() {
-        generic_restore_many::<u32, u64, u32, 24>(5, 1);
-    }
+    /// Lower bound of the current range of the range coder for the remainders.
+    lower: State,
 
-    #[test]
-    fn restore_two() {
-        generic_restore_many::<u32, u64, u32, 24>(5, 2);
-    }
+    /// Satisfies the invariant `range >= 1 << (State::BITS - Word::BITS)`.
+    range: State::NonZero,
 
-    #[test]
-    fn restore_ten() {
-        generic_restore_many::<u32, u64, u32, 24>(20, 10);
-    }
+    /// Position within the current range (only used when encoding, i.e., when reading
+    /// from the remainders).
+    point: State,
 
-    #[test]
-    fn restore_twenty() {
-        generic_restore_many::<u32, u64, u32, 24>(19, 20);
-    }
+    /// Held back words of remainders (only used when decoding, i.e., when writing to the
+    /// remainders).
+    situation: EncoderSituation<Word>,
+}
 
-    #[test]
-    fn restore_many_u32_u64_32() {
-        generic_restore_many::<u32, u64, u32, 32>(1024, 1000);
+impl<Word: BitArray, State: BitArray, const PRECISION: usize>
+    QueueChainCoderHeads<Word, State, PRECISION>
+{
+    /// Returns `true` iff there's currently an integer amount of `Words` on `compressed`
+    #[inline(always)]
+    pub fn is_whole(self) -> bool {
+        self.compressed.get() == Word::one()
     }
 
-    #[test]
-    fn restore_many_u32_u64_24() {
-        generic_restore_many::<u32, u64, u32, 24>(1024, 1000);
-    }
+    /// Private on purpose.
+    fn new(point: State) -> Self {
+        assert!(State::BITS >= Word::BITS + PRECISION);
+        assert!(PRECISION > 0);
+        assert!(PRECISION <= Word::BITS);
 
-    #[test]
-    fn restore_many_u32_u64_16() {
-        generic_restore_many::<u32, u64, u16, 16>(1024, 1000);
+        Self {
+            compressed: Word::one().into_nonzero().expect("1 != 0"),
+            lower: State::zero(),
+            range: State::max_value().into_nonzero().expect("max_value() != 0"),
+            point,
+            situation: EncoderSituation::Normal,
+        }
     }
 
-    #[test]
-    fn restore_many_u16_u64_16() {
-        generic_restore_many::<u16, u64, u16, 16>(1024, 1000);
-    }
+    /// Removes the next `num_bits` bits from the `compressed` head, reading a new word
+    /// from `source` if necessary. Requires `0 < num_bits <= Word::BITS`.
+    #[inline(always)]
+    fn pop_compressed<B: ReadWords<Word, Queue>>(
+        &mut self,
+        source: &mut B,
+        num_bits: usize,
+    ) -> Result<Word, CoderError<DecoderFrontendError, B::ReadError>> {
+        let head = self.compressed.get();
+        let available = Word::BITS - 1 - head.leading_zeros() as usize;
+
+        let (value, head, remaining) = if available >= num_bits {
+            let remaining = available - num_bits;
+            let value = low_bits(head >> remaining, num_bits);
+            (value, head, remaining)
+        } else {
+            let word = source.read()?.ok_or(CoderError::Frontend(
+                DecoderFrontendError::OutOfCompressedData,
+            ))?;
+            let missing = num_bits - available;
+            let remaining = Word::BITS - missing;
+            let mut value = word >> remaining;
+            if available != 0 {
+                // `missing < num_bits <= Word::BITS`, so the left-shift is valid.
+                value = value | low_bits(head, available) << missing;
+            }
+            (value, word, remaining)
+        };
 
-    #[test]
-    fn restore_many_u32_u64_8() {
-        generic_restore_many::<u32, u64, u8, 8>(1024, 1000);
+        // `remaining < Word::BITS` in both branches, so the result is nonzero.
+        self.compressed = (low_bits(head, remaining) | Word::one() << remaining)
+            .into_nonzero()
+            .expect("sentinel bit is set");
+        Ok(value)
     }
 
-    #[test]
-    fn restore_many_u16_u64_8() {
-        generic_restore_many::<u16, u64, u8, 8>(1024, 1000);
+    /// Appends the `num_bits` least significant bits of `value` to the `compressed` head
+    /// and returns a full word if one became available. Requires
+    /// `0 < num_bits <= Word::BITS` and `value < 1 << num_bits`.
+    #[inline(always)]
+    fn push_compressed(&mut self, value: Word, num_bits: usize) -> Option<Word> {
+        let head = self.compressed.get();
+        let pending = Word::BITS - 1 - head.leading_zeros() as usize;
+
+        if pending + num_bits < Word::BITS {
+            // The sentinel bit ends up at position `pending + num_bits < Word::BITS`.
+            self.compressed = (head << num_bits | value)
+                .into_nonzero()
+                .expect("sentinel bit is set");
+            None
+        } else {
+            let missing = Word::BITS - pending;
+            let remaining = num_bits - missing;
+            let mut word = value >> remaining;
+            if pending != 0 {
+                // `missing < Word::BITS` because `pending != 0`.
+                word = word | low_bits(head, pending) << missing;
+            }
+            // `remaining < num_bits <= Word::BITS` since `missing > 0`.
+            self.compressed = (low_bits(value, remaining) | Word::one() << remaining)
+                .into_nonzero()
+                .expect("sentinel bit is set");
+            Some(word)
+        }
     }
 
-    #[test]
-    fn restore_many_u8_u64_8() {
-        generic_restore_many::<u8, u64, u8, 8>(1024, 1000);
-    }
+    /// Appends a digit `digit < radix` to the range coded remainders. Requires
+    /// `0 < radix <= 1 << PRECISION`.
+    #[inline(always)]
+    fn push_remainder<B: WriteWords<Word>>(
+        &mut self,
+        sink: &mut B,
+        digit: State,
+        radix: State,
+    ) -> Result<(), B::WriteError>
+    where
+        State: AsPrimitive<Word>,
+    {
+        // This is the same algorithm as in `RangeEncoder::encode_symbol` except that we
+        // encode a digit from a uniform distribution with exact (i.e., not quantized)
+        // probabilities. Since `range >= 1 << (State::BITS - Word::BITS)` and
+        // `radix <= 1 << PRECISION <= 1 << (State::BITS - Word::BITS)`, we have `scale >= 1`.
+        let scale = self.range.get() / radix;
+        self.range = scale.into_nonzero().expect("scale >= 1");
+        let new_lower = self.lower.wrapping_add(&(scale * digit));
+
+        if let EncoderSituation::Inverted(num_inverted, first_inverted_lower_word) = self.situation
+        {
+            if new_lower.wrapping_add(&self.range.get()) > new_lower {
+                // We've transitioned from an inverted to a normal situation.
+                let (first_word, consecutive_words) = if new_lower < self.lower {
+                    (first_inverted_lower_word + Word::one(), Word::zero())
+                } else {
+                    (first_inverted_lower_word, Word::max_value())
+                };
 
-    #[test]
-    fn restore_many_u16_u32_16() {
-        generic_restore_many::<u16, u32, u16, 16>(1024, 1000);
-    }
+                sink.write(first_word)?;
+                for _ in 1..num_inverted.get() {
+                    sink.write(consecutive_words)?;
+                }
 
-    #[test]
-    fn restore_many_u16_u32_8() {
-        generic_restore_many::<u16, u32, u8, 8>(1024, 1000);
-    }
+                self.situation = EncoderSituation::Normal;
+            }
+        }
 
-    #[test]
-    fn restore_many_u8_u32_8() {
-        generic_restore_many::<u8, u32, u8, 8>(1024, 1000);
+        self.lower = new_lower;
+
+        if self.range.get() < State::one() << (State::BITS - Word::BITS) {
+            // A single shift suffices since `scale >= old_range >> PRECISION` and
+            // `PRECISION <= Word::BITS`.
+            self.range = (self.range.get() << Word::BITS)
+                .into_nonzero()
+                .expect("doesn't truncate");
+
+            let lower_word = (self.lower >> (State::BITS - Word::BITS)).as_();
+            self.lower = self.lower << Word::BITS;
+
+            if let EncoderSituation::Inverted(num_inverted, _) = &mut self.situation {
+                *num_inverted = NonZeroUsize::new(num_inverted.get().wrapping_add(1))
+                    .expect("Cannot encode more symbols than what's addressable with usize.");
+            } else if self.lower.wrapping_add(&self.range.get()) > self.lower {
+                sink.write(lower_word)?;
+            } else {
+                self.situation =
+                    EncoderSituation::Inverted(NonZeroUsize::new(1).expect("1 != 0"), lower_word);
+            }
+        }
+
+        Ok(())
     }
 
-    fn generic_restore_many<Word, State, Probability, const PRECISION: usize>(
-        amt_compressed_words: usize,
-        amt_symbols: usize,
-    ) where
-        State: BitArray + AsPrimitive<Word>,
-        Word: BitArray + Into<State> + AsPrimitive<Probability>,
-        Probability: BitArray + Into<Word> + AsPrimitive<usize> + Into<f64>,
-        u64: AsPrimitive<Word>,
-        u32: AsPrimitive<Probability>,
-        usize: AsPrimitive<Probability>,
-        f64: AsPrimitive<Probability>,
-        i32: AsPrimitive<Probability>,
+    /// Inverse of `push_remainder`.
+    #[inline(always)]
+    fn pop_remainder<B: ReadWords<Word, Queue>>(
+        &mut self,
+        source: &mut B,
+        radix: State,
+    ) -> Result<State, CoderError<EncoderFrontendError, B::ReadError>>
+    where
+        Word: Into<State>,
     {
-        #[cfg(miri)]
-        let (amt_compressed_words, amt_symbols) =
-            (amt_compressed_words.min(128), amt_symbols.min(100));
+        let scale = self.range.get() / radix;
+        let digit = self.point.wrapping_sub(&self.lower) / scale;
+        if digit >= radix {
+            return Err(CoderError::Frontend(
+                EncoderFrontendError::InvalidRemainders,
+            ));
+        }
 
-        let mut rng = Xoshiro256StarStar::seed_from_u64(
-            (amt_compressed_words as u64).rotate_left(32) ^ amt_symbols as u64,
-        );
-        let mut compressed = (0..amt_compressed_words)
-            .map(|_| rng.next_u64().as_())
-            .collect::<Vec<_>>();
+        if scale < State::one() << (State::BITS - Word::BITS) {
+            // Read before we modify any state so that we leave the coder in a consistent
+            // state in case of an error.
+            let word = source
+                .read()?
+                .ok_or(CoderError::Frontend(EncoderFrontendError::OutOfRemainders))?;
+            self.lower = self.lower.wrapping_add(&(scale * digit)) << Word::BITS;
+            self.range = (scale << Word::BITS)
+                .into_nonzero()
+                .expect("doesn't truncate");
+            self.point = self.point << Word::BITS | word.into();
+        } else {
+            self.lower = self.lower.wrapping_add(&(scale * digit));
+            self.range = scale.into_nonzero().expect("scale >= 1");
+        }
 
-        // Make the last compressed word have a random number of leading zero bits so that
-        // we test various filling levels.
-        let leading_zeros = (rng.next_u32() % (Word::BITS as u32 - 1)) as usize;
-        let last_word = compressed.last_mut().unwrap();
-        *last_word = *last_word | Word::one() << (Word::BITS - leading_zeros - 1);
-        *last_word = *last_word & Word::max_value() >> leading_zeros;
+        Ok(digit)
+    }
+}
 
-        let distributions = (0..amt_symbols)
-            .map(|_| {
-                let mean = (200.0 / u32::MAX as f64) * rng.next_u32() as f64 - 100.0;
-                let std_dev = (10.0 / u32::MAX as f64) * rng.next_u32() as f64 + 0.001;
-                Gaussian::new(mean, std_dev)
-            })
-            .collect::<Vec<_>>();
-        let quantizer = LeakyQuantizer::<_, _, Probability, PRECISION>::new(-100..=100);
+/// Returns the `num_bits` least significant bits of `x`; requires `num_bits <= Word::BITS`.
+#[inline(always)]
+fn low_bits<Word: BitArray>(x: Word, num_bits: usize) -> Word {
+    if num_bits == Word::BITS {
+        x
+    } else {
+        x & ((Word::one() << num_bits) - Word::one())
+    }
+}
 
-        let mut coder =
-            ChainCoder::<Word, State, Vec<Word>, Vec<Word>, PRECISION>::from_compressed(
-                compressed.clone(),
-            )
-            .unwrap();
+/// Type alias for a [`QueueChainCoder`] with sane parameters for typical use cases.
+///
+/// The default backends are suitable for decoding from (i.e., splitting up) some given data.
+/// For recovering the original data, use `DefaultQueueChainCoder<Vec<u32>, Cursor<u32,
+/// Vec<u32>>>` (see example in the documentation of [`QueueChainCoder`]).
+pub type DefaultQueueChainCoder<
+    CompressedBackend = Cursor<u32, Vec<u32>>,
+    RemaindersBackend = Vec<u32>,
+> = QueueChainCoder<u32, u64, CompressedBackend, RemaindersBackend, 24>;
+
+/// Type alias for a [`QueueChainCoder`] with a smaller word size and internal state.
+pub type SmallQueueChainCoder<
+    CompressedBackend = Cursor<u16, Vec<u16>>,
+    RemaindersBackend = Vec<u16>,
+> = QueueChainCoder<u16, u32, CompressedBackend, RemaindersBackend, 12>;
 
-        let symbols = coder
-            .decode_symbols(
-                distributions
+impl<Word, State, CompressedBackend, RemaindersBackend, const PRECISION: usize>
+    QueueChainCoder<Word, State, CompressedBackend, RemaindersBackend, PRECISION>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+{
+    /// Creates a new `QueueChainCoder` for decoding from the provided `data`.
+    ///
+    /// The reader `data` can be arbitrary. Reading from `data` happens lazily when
+    /// decoding symbols.
+    pub fn from_binary(data: CompressedBackend) -> Self
+    where
+        CompressedBackend: ReadWords<Word, Queue>,
+        RemaindersBackend: Default,
+    {
+        Self {
+            compressed: data,
+            remainders: RemaindersBackend::default(),
+            heads: QueueChainCoderHeads::new(State::zero()),
+        }
+    }
+
+    /// Terminates decoding and returns the remainders bit string as a tuple `(prefix,
+    /// suffix)`.
+    ///
+    /// - The `prefix` contains the remainders, i.e., the information from the original
+    ///   data that was not needed to identify the decoded symbols, including the bits
+    ///   of a partially consumed word.
+    /// - The `suffix` is the unaltered rest of the data from which you created this
+    ///   `QueueChainCoder` when you called [`QueueChainCoder::from_binary`], i.e., it
+    ///   starts with the first word that hasn't been read from yet.
+    ///
+    /// Either pass only the `prefix` to [`QueueChainCoder::from_remainders`], encode the
+    /// symbols, call [`.into_binary()`] and append the `suffix` to the result; or pass the
+    /// concatenation of `prefix` and `suffix` to `from_remainders`, in which case the
+    /// second entry of the tuple returned by `.into_binary()` will contain (a reader for)
+    /// the `suffix`.
+    ///
+    /// [`.into_binary()`]: Self::into_binary
+    pub fn into_remainders(
+        mut self,
+    ) -> Result<(RemaindersBackend, CompressedBackend), RemaindersBackend::WriteError>
+    where
+        RemaindersBackend: WriteWords<Word>,
+    {
+        // Transfer the unused bits of a partially consumed word onto the remainders.
+        let head = self.heads.compressed.get();
+        let mut num_bits = Word::BITS - 1 - head.leading_zeros() as usize;
+        while num_bits != 0 {
+            let chunk = num_bits.min(PRECISION);
+            num_bits -= chunk;
+            let digit = low_bits(head >> num_bits, chunk);
+            self.heads
+                .push_remainder(&mut self.remainders, digit.into(), State::one() << chunk)?;
+        }
+
+        // Flush held back words and the full `lower` bound, so that the remainders can be
+        // followed by arbitrary data.
+        if let EncoderSituation::Inverted(num_inverted, first_inverted_lower_word) =
+            self.heads.situation
+        {
+            self.remainders.write(first_inverted_lower_word)?;
+            for _ in 1..num_inverted.get() {
+                self.remainders.write(Word::max_value())?;
+            }
+        }
+        for i in (0..State::BITS / Word::BITS).rev() {
+            self.remainders
+                .write((self.heads.lower >> (i * Word::BITS)).as_())?;
+        }
+
+        Ok((self.remainders, self.compressed))
+    }
+
+    /// Creates a new `QueueChainCoder` for encoding some symbols together with the data
+    /// previously obtained from [`into_remainders`].
+    ///
+    /// Returns an error if `remainders` doesn't have enough words to initialize the range
+    /// coder or if reading from `remainders` lead to an error.
+    ///
+    /// See [`into_remainders`] for detailed explanation.
+    ///
+    /// [`into_remainders`]: Self::into_remainders
+    pub fn from_remainders(
+        mut remainders: RemaindersBackend,
+    ) -> Result<Self, CoderError<RemaindersBackend, RemaindersBackend::ReadError>>
+    where
+        RemaindersBackend: ReadWords<Word, Queue>,
+        CompressedBackend: Default,
+    {
+        let mut point = State::zero();
+        for _ in 0..State::BITS / Word::BITS {
+            match remainders.read()? {
+                Some(word) => point = point << Word::BITS | word.into(),
+                None => return Err(CoderError::Frontend(remainders)),
+            }
+        }
+
+        Ok(Self {
+            compressed: CompressedBackend::default(),
+            remainders,
+            heads: QueueChainCoderHeads::new(point),
+        })
+    }
+
+    /// Terminates encoding and returns the recovered data as a tuple `(prefix, suffix)`.
+    ///
+    /// The `prefix` contains the data that was consumed when decoding symbols with the
+    /// original `QueueChainCoder`, and the `suffix` contains everything from the
+    /// remainders that came after the data written by [`into_remainders`].
+    ///
+    /// Returns an error if the remainders are exhausted or invalid, which can only happen
+    /// if you've used the `QueueChainCoder` incorrectly.
+    ///
+    /// See [`into_remainders`] for usage instructions.
+    ///
+    /// [`into_remainders`]: Self::into_remainders
+    pub fn into_binary(
+        mut self,
+    ) -> Result<
+        (CompressedBackend, RemaindersBackend),
+        QueueEncoderError<Word, CompressedBackend, RemaindersBackend>,
+    >
+    where
+        CompressedBackend: WriteWords<Word>,
+        RemaindersBackend: ReadWords<Word, Queue>,
+    {
+        // Recover the unused bits of the last partially consumed word, if any.
+        let pending = Word::BITS - 1 - self.heads.compressed.get().leading_zeros() as usize;
+        let mut num_bits = if pending == 0 {
+            0
+        } else {
+            Word::BITS - pending
+        };
+        while num_bits != 0 {
+            let chunk = num_bits.min(PRECISION);
+            num_bits -= chunk;
+            let digit = self
+                .heads
+                .pop_remainder(&mut self.remainders, State::one() << chunk)
+                .map_err(|err| err.map_backend(BackendError::Remainders))?;
+            if let Some(word) = self.heads.push_compressed(digit.as_(), chunk) {
+                self.compressed
+                    .write(word)
+                    .map_err(BackendError::Compressed)?;
+            }
+        }
+        debug_assert!(self.is_whole());
+
+        Ok((self.compressed, self.remainders))
+    }
+
+    /// Returns `true` iff there's currently an integer amount of `Words` in the compressed data
+    #[inline(always)]
+    pub fn is_whole(&self) -> bool {
+        self.heads.is_whole()
+    }
+
+    /// Converts the `QueueChainCoder` into a new `QueueChainCoder` that accepts entropy
+    /// models with a different fixed-point precision.
+    ///
+    /// Unlike [`ChainCoder::change_precision`], this method cannot fail since the internal
+    /// state of a `QueueChainCoder` doesn't depend on the precision.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use constriction::{
+    ///     backends::Cursor,
+    ///     stream::{model::LeakyQuantizer, Decode, chain::DefaultQueueChainCoder},
+    /// };
+    ///
+    /// let continuous_distribution = probability::distribution::Gaussian::new(0.0, 10.0);
+    /// let quantizer24 = LeakyQuantizer::<_, _, u32, 24>::new(-100..=100);
+    /// let quantizer20 = LeakyQuantizer::<_, _, u32, 20>::new(-100..=100);
+    /// let distribution24 = quantizer24.quantize(continuous_distribution);
+    /// let distribution20 = quantizer20.quantize(continuous_distribution);
+    ///
+    /// let data = vec![0x0123_4567u32, 0x89ab_cdef];
+    /// let mut coder: DefaultQueueChainCoder = DefaultQueueChainCoder::from_binary(Cursor::new_at_write_beginning(data));
+    /// let _symbol_a = coder.decode_symbol(distribution24).unwrap();
+    ///
+    /// // The compiler can infer the new precision based on how `coder` will be used.
+    /// let mut coder = coder.change_precision();
+    /// let _symbol_b = coder.decode_symbol(distribution20).unwrap();
+    /// ```
+    #[inline(always)]
+    pub fn change_precision<const NEW_PRECISION: usize>(
+        self,
+    ) -> QueueChainCoder<Word, State, CompressedBackend, RemaindersBackend, NEW_PRECISION> {
+        assert!(NEW_PRECISION > 0);
+        assert!(NEW_PRECISION <= Word::BITS);
+        assert!(State::BITS >= Word::BITS + NEW_PRECISION);
+
+        let QueueChainCoderHeads {
+            compressed,
+            lower,
+            range,
+            point,
+            situation,
+        } = self.heads;
+
+        QueueChainCoder {
+            compressed: self.compressed,
+            remainders: self.remainders,
+            heads: QueueChainCoderHeads {
+                compressed,
+                lower,
+                range,
+                point,
+                situation,
+            },
+        }
+    }
+}
+
+impl<Word, State, CompressedBackend, RemaindersBackend, const PRECISION: usize> Code
+    for QueueChainCoder<Word, State, CompressedBackend, RemaindersBackend, PRECISION>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+{
+    type Word = Word;
+    type State = QueueChainCoderHeads<Word, State, PRECISION>;
+
+    fn state(&self) -> Self::State {
+        self.heads
+    }
+}
+
+#[allow(type_alias_bounds)]
+pub type QueueDecoderError<
+    Word,
+    CompressedBackend: ReadWords<Word, Queue>,
+    RemaindersBackend: WriteWords<Word>,
+> = CoderError<
+    DecoderFrontendError,
+    BackendError<CompressedBackend::ReadError, RemaindersBackend::WriteError>,
+>;
+
+#[allow(type_alias_bounds)]
+pub type QueueEncoderError<
+    Word,
+    CompressedBackend: WriteWords<Word>,
+    RemaindersBackend: ReadWords<Word, Queue>,
+> = CoderError<
+    EncoderFrontendError,
+    BackendError<CompressedBackend::WriteError, RemaindersBackend::ReadError>,
+>;
+
+impl<Word, State, CompressedBackend, RemaindersBackend, const PRECISION: usize> PosSeek
+    for QueueChainCoder<Word, State, CompressedBackend, RemaindersBackend, PRECISION>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+    CompressedBackend: PosSeek,
+    RemaindersBackend: PosSeek,
+{
+    type Position = (
+        BackendPosition<CompressedBackend::Position, RemaindersBackend::Position>,
+        QueueChainCoderHeads<Word, State, PRECISION>,
+    );
+}
+
+impl<Word, State, CompressedBackend, RemaindersBackend, const PRECISION: usize> Pos
+    for QueueChainCoder<Word, State, CompressedBackend, RemaindersBackend, PRECISION>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+    CompressedBackend: Pos,
+    RemaindersBackend: Pos,
+{
+    fn pos(&self) -> Self::Position {
+        (
+            BackendPosition {
+                compressed: self.compressed.pos(),
+                remainders: self.remainders.pos(),
+            },
+            self.state(),
+        )
+    }
+}
+
+impl<Word, State, CompressedBackend, RemaindersBackend, const PRECISION: usize> Seek
+    for QueueChainCoder<Word, State, CompressedBackend, RemaindersBackend, PRECISION>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+    CompressedBackend: Seek,
+    RemaindersBackend: Seek,
+{
+    fn seek(&mut self, (pos, state): Self::Position) -> Result<(), ()> {
+        self.compressed.seek(pos.compressed)?;
+        self.remainders.seek(pos.remainders)?;
+
+        // `state` is valid since we don't provide a public API to modify fields of
+        // `QueueChainCoderHeads` individually.
+        self.heads = state;
+
+        Ok(())
+    }
+}
+
+impl<Word, State, CompressedBackend, RemaindersBackend, const PRECISION: usize> Decode<PRECISION>
+    for QueueChainCoder<Word, State, CompressedBackend, RemaindersBackend, PRECISION>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+    CompressedBackend: ReadWords<Word, Queue>,
+    RemaindersBackend: WriteWords<Word>,
+{
+    type FrontendError = DecoderFrontendError;
+
+    type BackendError = BackendError<CompressedBackend::ReadError, RemaindersBackend::WriteError>;
+
+    fn decode_symbol<M>(
+        &mut self,
+        model: M,
+    ) -> Result<M::Symbol, QueueDecoderError<Word, CompressedBackend, RemaindersBackend>>
+    where
+        M: DecoderModel<PRECISION>,
+        M::Probability: Into<Self::Word>,
+        Self::Word: AsPrimitive<M::Probability>,
+    {
+        let quantile = self
+            .heads
+            .pop_compressed(&mut self.compressed, PRECISION)
+            .map_err(|err| err.map_backend(BackendError::Compressed))?
+            .as_();
+
+        let (symbol, left_sided_cumulative, probability) = model.quantile_function(quantile);
+        let remainder = quantile - left_sided_cumulative;
+
+        self.heads
+            .push_remainder(
+                &mut self.remainders,
+                remainder.into().into(),
+                probability.get().into().into(),
+            )
+            .map_err(BackendError::Remainders)?;
+
+        Ok(symbol)
+    }
+
+    fn maybe_exhausted(&self) -> bool {
+        self.compressed.maybe_exhausted() || self.remainders.maybe_full()
+    }
+}
+
+impl<Word, State, CompressedBackend, RemaindersBackend, const PRECISION: usize> Encode<PRECISION>
+    for QueueChainCoder<Word, State, CompressedBackend, RemaindersBackend, PRECISION>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+    CompressedBackend: WriteWords<Word>,
+    RemaindersBackend: ReadWords<Word, Queue>,
+{
+    type FrontendError = EncoderFrontendError;
+    type BackendError = BackendError<CompressedBackend::WriteError, RemaindersBackend::ReadError>;
+
+    fn encode_symbol<M>(
+        &mut self,
+        symbol: impl Borrow<M::Symbol>,
+        model: M,
+    ) -> Result<(), QueueEncoderError<Word, CompressedBackend, RemaindersBackend>>
+    where
+        M: EncoderModel<PRECISION>,
+        M::Probability: Into<Self::Word>,
+        Self::Word: AsPrimitive<M::Probability>,
+    {
+        let (left_sided_cumulative, probability) = model
+            .left_cumulative_and_probability(symbol)
+            .ok_or(CoderError::Frontend(EncoderFrontendError::ImpossibleSymbol))?;
+
+        let remainder = self
+            .heads
+            .pop_remainder(&mut self.remainders, probability.get().into().into())
+            .map_err(|err| err.map_backend(BackendError::Remainders))?;
+        let quantile = left_sided_cumulative.into() + remainder.as_();
+
+        if let Some(word) = self.heads.push_compressed(quantile, PRECISION) {
+            self.compressed
+                .write(word)
+                .map_err(BackendError::Compressed)?;
+        }
+
+        Ok(())
+    }
+
+    fn maybe_full(&self) -> bool {
+        self.remainders.maybe_exhausted() || self.compressed.maybe_full()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::model::{DefaultContiguousCategoricalEntropyModel, LeakyQuantizer};
+    use super::super::tests::random_gaussian_symbols;
+    use super::*;
+
+    use probability::distribution::Gaussian;
+    use rand_xoshiro::{
+        rand_core::{RngCore, SeedableRng},
+        Xoshiro256StarStar,
+    };
+
+    use alloc::vec;
+
+    #[test]
+    fn restore_none() {
+        generic_restore_many::<u32, u64, u32, 24>(4, 0);
+    }
+
+    #[test]
+    fn restore_one() {
+        generic_restore_many::<u32, u64, u32, 24>(5, 1);
+    }
+
+    #[test]
+    fn restore_two() {
+        generic_restore_many::<u32, u64, u32, 24>(5, 2);
+    }
+
+    #[test]
+    fn restore_ten() {
+        generic_restore_many::<u32, u64, u32, 24>(20, 10);
+    }
+
+    #[test]
+    fn restore_twenty() {
+        generic_restore_many::<u32, u64, u32, 24>(19, 20);
+    }
+
+    #[test]
+    fn restore_many_u32_u64_32() {
+        generic_restore_many::<u32, u64, u32, 32>(1024, 1000);
+    }
+
+    #[test]
+    fn restore_many_u32_u64_24() {
+        generic_restore_many::<u32, u64, u32, 24>(1024, 1000);
+    }
+
+    #[test]
+    fn restore_many_u32_u64_16() {
+        generic_restore_many::<u32, u64, u16, 16>(1024, 1000);
+    }
+
+    #[test]
+    fn restore_many_u16_u64_16() {
+        generic_restore_many::<u16, u64, u16, 16>(1024, 1000);
+    }
+
+    #[test]
+    fn restore_many_u32_u64_8() {
+        generic_restore_many::<u32, u64, u8, 8>(1024, 1000);
+    }
+
+    #[test]
+    fn restore_many_u16_u64_8() {
+        generic_restore_many::<u16, u64, u8, 8>(1024, 1000);
+    }
+
+    #[test]
+    fn restore_many_u8_u64_8() {
+        generic_restore_many::<u8, u64, u8, 8>(1024, 1000);
+    }
+
+    #[test]
+    fn restore_many_u64_u128_48() {
+        generic_restore_many::<u64, u128, u64, 48>(1024, 1000);
+    }
+
+    #[test]
+    fn restore_many_u64_u128_32() {
+        generic_restore_many::<u64, u128, u32, 32>(1024, 1000);
+    }
+
+    #[test]
+    fn restore_many_u16_u32_16() {
+        generic_restore_many::<u16, u32, u16, 16>(1024, 1000);
+    }
+
+    #[test]
+    fn restore_many_u16_u32_8() {
+        generic_restore_many::<u16, u32, u8, 8>(1024, 1000);
+    }
+
+    #[test]
+    fn restore_many_u8_u32_8() {
+        generic_restore_many::<u8, u32, u8, 8>(1024, 1000);
+    }
+
+    fn generic_restore_many<Word, State, Probability, const PRECISION: usize>(
+        amt_compressed_words: usize,
+        amt_symbols: usize,
+    ) where
+        State: BitArray + AsPrimitive<Word>,
+        Word: BitArray + Into<State> + AsPrimitive<Probability>,
+        Probability: BitArray + Into<Word> + AsPrimitive<usize>,
+        u64: AsPrimitive<Word>,
+        u32: AsPrimitive<Probability>,
+        usize: AsPrimitive<Probability>,
+        f64: AsPrimitive<Probability>,
+        i32: AsPrimitive<Probability>,
+    {
+        #[cfg(miri)]
+        let (amt_compressed_words, amt_symbols) =
+            (amt_compressed_words.min(128), amt_symbols.min(100));
+
+        let mut rng = Xoshiro256StarStar::seed_from_u64(
+            (amt_compressed_words as u64).rotate_left(32) ^ amt_symbols as u64,
+        );
+        let mut compressed = (0..amt_compressed_words)
+            .map(|_| rng.next_u64().as_())
+            .collect::<Vec<_>>();
+
+        // Make the last compressed word have a random number of leading zero bits so that
+        // we test various filling levels.
+        let leading_zeros = (rng.next_u32() % (Word::BITS as u32 - 1)) as usize;
+        let last_word = compressed.last_mut().unwrap();
+        *last_word = *last_word | Word::one() << (Word::BITS - leading_zeros - 1);
+        *last_word = *last_word & Word::max_value() >> leading_zeros;
+
+        let distributions = (0..amt_symbols)
+            .map(|_| {
+                let mean = (200.0 / u32::MAX as f64) * rng.next_u32() as f64 - 100.0;
+                let std_dev = (10.0 / u32::MAX as f64) * rng.next_u32() as f64 + 0.001;
+                Gaussian::new(mean, std_dev)
+            })
+            .collect::<Vec<_>>();
+        let quantizer = LeakyQuantizer::<_, _, Probability, PRECISION>::new(-100..=100);
+
+        let mut coder =
+            ChainCoder::<Word, State, Vec<Word>, Vec<Word>, PRECISION>::from_compressed(
+                compressed.clone(),
+            )
+            .unwrap();
+
+        let symbols = coder
+            .decode_symbols(
+                distributions
                     .iter()
                     .map(|&distribution| quantizer.quantize(distribution)),
             )
@@ -1320,4 +2267,386 @@ mod tests {
             assert_eq!(reconstructed, compressed);
         }
     }
+
+    #[test]
+    fn checkpoint_and_rollback_u32_u64_24() {
+        generic_checkpoint_and_rollback::<u32, u64, u32, 24>();
+    }
+
+    #[test]
+    fn checkpoint_and_rollback_u16_u32_12() {
+        generic_checkpoint_and_rollback::<u16, u32, u16, 12>();
+    }
+
+    fn generic_checkpoint_and_rollback<Word, State, Probability, const PRECISION: usize>()
+    where
+        State: BitArray + AsPrimitive<Word>,
+        Word: BitArray + Into<State> + AsPrimitive<Probability>,
+        Probability: BitArray + Into<Word> + AsPrimitive<usize>,
+        u64: AsPrimitive<Word>,
+        usize: AsPrimitive<Probability>,
+        f64: AsPrimitive<Probability>,
+        i32: AsPrimitive<Probability>,
+    {
+        #[cfg(not(miri))]
+        let (amt_remainders_words, num_batches) = (4096, 200);
+
+        #[cfg(miri)]
+        let (amt_remainders_words, num_batches) = (256, 10);
+
+        let mut rng = Xoshiro256StarStar::seed_from_u64(2022);
+        let mut remainders = (0..amt_remainders_words)
+            .map(|_| rng.next_u64().as_() | Word::one())
+            .collect::<Vec<Word>>();
+        *remainders.last_mut().unwrap() = Word::one();
+        let quantizer = LeakyQuantizer::<_, _, Probability, PRECISION>::new(-100..=100);
+
+        let mut coder =
+            ChainCoder::<Word, State, Vec<Word>, Vec<Word>, PRECISION>::from_remainders(
+                remainders.clone(),
+            )
+            .unwrap();
+        let mut reference = coder.clone();
+        let initial_remainders_len = coder.remainders.len();
+        let mut num_rollbacks_with_consumed_remainders = 0;
+
+        for _ in 0..num_batches {
+            let len = rng.next_u32() as usize % 10;
+            let batch = random_gaussian_symbols(quantizer, 10.0, len, &mut rng);
+
+            let checkpoint = coder.checkpoint();
+            coder.encode_symbols(batch.iter().cloned()).unwrap();
+            if rng.next_u32() % 2 == 0 {
+                if coder.remainders.len() != checkpoint.remainders_len {
+                    num_rollbacks_with_consumed_remainders += 1;
+                }
+                coder.rollback(checkpoint).unwrap();
+                assert_eq!(coder.checkpoint(), checkpoint);
+            } else {
+                reference.encode_symbols(batch.iter().cloned()).unwrap();
+            }
+            assert_eq!(coder.compressed, reference.compressed);
+            assert_eq!(coder.remainders, reference.remainders);
+            assert_eq!(coder.heads, reference.heads);
+        }
+        assert!(num_rollbacks_with_consumed_remainders > 0);
+        assert!(coder.remainders.len() < initial_remainders_len);
+
+        let model = quantizer.quantize(Gaussian::new(0.0, 0.1));
+        let consume_remainders =
+            |coder: &mut ChainCoder<Word, State, Vec<Word>, Vec<Word>, PRECISION>| {
+                let remainders_len = coder.remainders.len();
+                while coder.remainders.len() == remainders_len {
+                    coder.encode_symbol(0, model).unwrap();
+                }
+            };
+
+        // Only the most recent checkpoint remains valid once remainders were consumed.
+        let older = coder.checkpoint();
+        consume_remainders(&mut coder);
+        let newer = coder.checkpoint();
+        consume_remainders(&mut coder);
+        assert!(coder.rollback(older).is_err());
+        coder.rollback(newer).unwrap();
+        assert_eq!(coder.checkpoint(), newer);
+
+        // Decoding invalidates all checkpoints.
+        let checkpoint = coder.checkpoint();
+        coder.encode_symbol(3, model).unwrap();
+        assert_eq!(coder.decode_symbol(model).unwrap(), 3);
+        assert!(coder.rollback(checkpoint).is_err());
+
+        // Rollback recovers from running out of remainders.
+        let mut coder =
+            ChainCoder::<Word, State, Vec<Word>, Vec<Word>, PRECISION>::from_remainders(
+                remainders.split_off(amt_remainders_words - 8),
+            )
+            .unwrap();
+        let snapshot = coder.clone();
+        let checkpoint = coder.checkpoint();
+        let err = loop {
+            if let Err(err) = coder.encode_symbol(0, model) {
+                break err;
+            }
+        };
+        assert_eq!(
+            err,
+            CoderError::Frontend(EncoderFrontendError::OutOfRemainders)
+        );
+        assert!(coder.remainders.is_empty());
+        coder.rollback(checkpoint).unwrap();
+        assert_eq!(coder.compressed, snapshot.compressed);
+        assert_eq!(coder.remainders, snapshot.remainders);
+        assert_eq!(coder.heads, snapshot.heads);
+    }
+
+    #[test]
+    fn queue_restore_none() {
+        generic_queue_restore_many::<u32, u64, u32, 24>(4, 0);
+    }
+
+    #[test]
+    fn queue_restore_few() {
+        generic_queue_restore_many::<u32, u64, u32, 24>(5, 1);
+        generic_queue_restore_many::<u32, u64, u32, 24>(5, 2);
+        generic_queue_restore_many::<u32, u64, u32, 24>(20, 10);
+    }
+
+    #[test]
+    fn queue_restore_many() {
+        generic_queue_restore_many::<u32, u64, u32, 32>(1024, 1000);
+        generic_queue_restore_many::<u32, u64, u32, 24>(1024, 1000);
+        generic_queue_restore_many::<u32, u64, u16, 16>(1024, 1000);
+        generic_queue_restore_many::<u16, u64, u16, 16>(1024, 1000);
+        generic_queue_restore_many::<u32, u64, u8, 8>(1024, 1000);
+        generic_queue_restore_many::<u16, u64, u8, 8>(1024, 1000);
+        generic_queue_restore_many::<u8, u64, u8, 8>(1024, 1000);
+        generic_queue_restore_many::<u16, u32, u16, 16>(1024, 1000);
+        generic_queue_restore_many::<u16, u32, u8, 8>(1024, 1000);
+        generic_queue_restore_many::<u8, u32, u8, 8>(1024, 1000);
+    }
+
+    fn generic_queue_restore_many<Word, State, Probability, const PRECISION: usize>(
+        amt_compressed_words: usize,
+        amt_symbols: usize,
+    ) where
+        State: BitArray + AsPrimitive<Word>,
+        Word: BitArray + Into<State> + AsPrimitive<Probability>,
+        Probability: BitArray + Into<Word> + AsPrimitive<usize> + Into<f64>,
+        u64: AsPrimitive<Word>,
+        u32: AsPrimitive<Probability>,
+        usize: AsPrimitive<Probability>,
+        f64: AsPrimitive<Probability>,
+        i32: AsPrimitive<Probability>,
+    {
+        #[cfg(miri)]
+        let (amt_compressed_words, amt_symbols) =
+            (amt_compressed_words.min(128), amt_symbols.min(100));
+
+        let mut rng = Xoshiro256StarStar::seed_from_u64(
+            (amt_compressed_words as u64).rotate_left(32) ^ amt_symbols as u64 ^ 0x5eed,
+        );
+        let compressed = (0..amt_compressed_words)
+            .map(|_| rng.next_u64().as_())
+            .collect::<Vec<_>>();
+
+        let distributions = (0..amt_symbols)
+            .map(|_| {
+                let mean = (200.0 / u32::MAX as f64) * rng.next_u32() as f64 - 100.0;
+                let std_dev = (10.0 / u32::MAX as f64) * rng.next_u32() as f64 + 0.001;
+                Gaussian::new(mean, std_dev)
+            })
+            .collect::<Vec<_>>();
+        let quantizer = LeakyQuantizer::<_, _, Probability, PRECISION>::new(-100..=100);
+
+        let mut coder = QueueChainCoder::<Word, State, _, Vec<Word>, PRECISION>::from_binary(
+            Cursor::new_at_write_beginning(compressed.clone()),
+        );
+
+        let symbols = coder
+            .decode_symbols(
+                distributions
+                    .iter()
+                    .map(|&distribution| quantizer.quantize(distribution)),
+            )
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert!(!coder.maybe_exhausted());
+
+        let (remainders, suffix) = coder.into_remainders().unwrap();
+        let (suffix, pos) = suffix.into_buf_and_pos();
+        let suffix = &suffix[pos..];
+        let mut concatenated = remainders.clone();
+        concatenated.extend_from_slice(suffix);
+
+        for (remainders, expected_suffix) in [(remainders, &[][..]), (concatenated, suffix)] {
+            let mut coder =
+                QueueChainCoder::<Word, State, Vec<Word>, _, PRECISION>::from_remainders(
+                    Cursor::new_at_write_beginning(remainders),
+                )
+                .unwrap();
+            coder
+                .encode_symbols(
+                    symbols
+                        .iter()
+                        .zip(&distributions)
+                        .map(|(&symbol, &distribution)| (symbol, quantizer.quantize(distribution))),
+                )
+                .unwrap();
+
+            let (mut reconstructed, remaining) = coder.into_binary().unwrap();
+            let (remaining, pos) = remaining.into_buf_and_pos();
+            assert_eq!(&remaining[pos..], expected_suffix);
+
+            reconstructed.extend_from_slice(suffix);
+            assert_eq!(reconstructed, compressed);
+        }
+    }
+
+    #[test]
+    fn queue_locality() {
+        let data = vec![0x80d1_4131, 0xdda9_7c6c, 0x5017_a640, 0x0117_0a3d];
+        let decode = |probabilities: &[[f64; 4]]| {
+            let mut coder = DefaultQueueChainCoder::<_, Vec<u32>>::from_binary(
+                Cursor::new_at_write_beginning(data.clone()),
+            );
+            probabilities
+                .iter()
+                .map(|probs| {
+                    let model =
+                        DefaultContiguousCategoricalEntropyModel::from_floating_point_probabilities(
+                            probs,
+                        )
+                        .unwrap();
+                    coder.decode_symbol(model).unwrap()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let mut probabilities = [
+            [0.1, 0.7, 0.1, 0.1],
+            [0.2, 0.2, 0.1, 0.5],
+            [0.2, 0.1, 0.4, 0.3],
+            [0.3, 0.2, 0.4, 0.1],
+        ];
+        let symbols1 = decode(&probabilities);
+        probabilities[1] = [0.7, 0.1, 0.1, 0.1];
+        let symbols2 = decode(&probabilities);
+
+        // Only the symbol whose entropy model changed may differ.
+        for (i, (s1, s2)) in symbols1.iter().zip(&symbols2).enumerate() {
+            if i != 1 {
+                assert_eq!(s1, s2);
+            }
+        }
+    }
+
+    #[test]
+    fn queue_change_precision() {
+        let data = (0..50u32)
+            .map(|i| i.wrapping_mul(0xad5f_b2ed).wrapping_add(0xed55_4892))
+            .collect::<Vec<_>>();
+        let gaussian = Gaussian::new(3.0, 20.0);
+        let quantizer24 = LeakyQuantizer::<_, _, u32, 24>::new(-100..=100);
+        let quantizer9 = LeakyQuantizer::<_, _, u32, 9>::new(-100..=100);
+        let model24 = quantizer24.quantize(gaussian);
+        let model9 = quantizer9.quantize(gaussian);
+
+        let mut coder = DefaultQueueChainCoder::<_, Vec<u32>>::from_binary(
+            Cursor::new_at_write_beginning(data.clone()),
+        );
+        let symbols24a = coder
+            .decode_iid_symbols(7, model24)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let mut coder = coder.change_precision::<9>();
+        let symbols9 = coder
+            .decode_iid_symbols(13, model9)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let mut coder = coder.change_precision::<24>();
+        let symbols24b = coder
+            .decode_iid_symbols(5, model24)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert!(!coder.is_whole());
+
+        let (remainders, suffix) = coder.into_remainders().unwrap();
+        let (suffix, pos) = suffix.into_buf_and_pos();
+
+        let mut coder = DefaultQueueChainCoder::<Vec<u32>, _>::from_remainders(
+            Cursor::new_at_write_beginning(remainders),
+        )
+        .unwrap();
+        coder.encode_iid_symbols(&symbols24a, model24).unwrap();
+        let mut coder = coder.change_precision::<9>();
+        coder.encode_iid_symbols(&symbols9, model9).unwrap();
+        let mut coder = coder.change_precision::<24>();
+        coder.encode_iid_symbols(&symbols24b, model24).unwrap();
+
+        let (mut reconstructed, _) = coder.into_binary().unwrap();
+        reconstructed.extend_from_slice(&suffix[pos..]);
+        assert_eq!(reconstructed, data);
+    }
+
+    #[test]
+    fn queue_errors() {
+        let model =
+            LeakyQuantizer::<_, _, u32, 24>::new(-100..=100).quantize(Gaussian::new(0.0, 10.0));
+
+        // Running out of compressed data when decoding.
+        let mut coder: DefaultQueueChainCoder =
+            DefaultQueueChainCoder::from_binary(Cursor::new_at_write_beginning(vec![
+                0x0123_4567u32,
+            ]));
+        coder.decode_symbol(model).unwrap();
+        assert_eq!(
+            coder.decode_symbol(model),
+            Err(CoderError::Frontend(
+                DecoderFrontendError::OutOfCompressedData
+            ))
+        );
+
+        // Not enough remainders to initialize the coder.
+        assert!(DefaultQueueChainCoder::<Vec<u32>, _>::from_remainders(
+            Cursor::new_at_write_beginning(vec![1u32])
+        )
+        .is_err());
+
+        // Encoding more symbols than what was decoded.
+        let mut coder: DefaultQueueChainCoder =
+            DefaultQueueChainCoder::from_binary(Cursor::new_at_write_beginning(vec![
+                0x0123_4567u32,
+                0x89ab_cdef,
+            ]));
+        let symbol = coder.decode_symbol(model).unwrap();
+        let (remainders, _) = coder.into_remainders().unwrap();
+        let mut coder = DefaultQueueChainCoder::<Vec<u32>, _>::from_remainders(
+            Cursor::new_at_write_beginning(remainders),
+        )
+        .unwrap();
+        coder.encode_symbol(symbol, model).unwrap();
+        let result = (0..100).try_for_each(|_| coder.encode_symbol(symbol, model));
+        assert_eq!(
+            result,
+            Err(CoderError::Frontend(EncoderFrontendError::OutOfRemainders))
+        );
+    }
+
+    #[test]
+    fn finish() {
+        let data = (0..50u32)
+            .map(|i| i.wrapping_mul(0xad5f_b2ed).wrapping_add(0xed55_4892))
+            .collect::<Vec<_>>();
+        let quantizer = LeakyQuantizer::<_, _, u32, 24>::new(-100..=100);
+        let model = quantizer.quantize(Gaussian::new(3.0, 20.0));
+
+        let mut coder = DefaultChainCoder::from_binary(data.clone()).unwrap();
+        let symbols = coder
+            .decode_iid_symbols(20, model)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let (prefix, suffix) = coder.into_remainders().unwrap();
+
+        // Re-encoding too few symbols leaves some remainders unconsumed.
+        let mut coder = DefaultChainCoder::from_remainders(suffix.clone()).unwrap();
+        for &symbol in symbols.iter().rev() {
+            assert!(coder.finish().is_err());
+            coder.encode_symbol(symbol, model).unwrap();
+        }
+        assert_eq!(coder.finish(), Ok(()));
+        let (recovered_prefix, recovered_suffix) = coder.into_binary().unwrap();
+        assert!(recovered_prefix.is_empty());
+        let mut recovered = prefix.clone();
+        recovered.extend_from_slice(&recovered_suffix);
+        assert_eq!(recovered, data);
+
+        // Including the prefix leaves exactly the prefix unconsumed.
+        let mut remainders = prefix.clone();
+        remainders.extend_from_slice(&suffix);
+        let mut coder = DefaultChainCoder::from_remainders(remainders).unwrap();
+        coder.encode_iid_symbols_reverse(&symbols, model).unwrap();
+        assert_eq!(coder.finish(), Err(FinishError::UnreadWords(prefix.len())));
+    }
 }
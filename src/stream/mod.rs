@@ -23,10 +23,20 @@
 //!   effectiveness of stream codes with the locality of symbol codes; it is meant for
 //!   experimental new compression approaches that perform joint inference, quantization,
 //!   and bits-back coding in an end-to-end optimization. See submodule [`chain`].
+//! - **Table-based ANS (tANS):** a variant of ANS Coding that precomputes state transition
+//!   tables for a single static entropy model over a small alphabet; it trades some
+//!   flexibility and a bit of compression effectiveness for speed. See submodule [`tans`].
+//! - **Adaptive Binary Coding:** a Range Coder that is specialized to binary decisions with
+//!   slowly changing statistics; each bit is coded with an adaptive context that updates
+//!   its probability estimate after each bit (as in CABAC). See submodule [`binary`].
 //!
 //! All of these stream codes are provided through types that implement the [`Encode`] and
 //! [`Decode`] traits defined in this module.
 //!
+//! If the crate feature `parallel` is enabled, the submodule `parallel` additionally
+//! provides methods that encode and decode long sequences of symbols with an ANS or Range
+//! Coder in independent chunks on a thread pool.
+//!
 //! ## Provided Utilities for Entropy Models
 //!
 //! To encode or decode a sequence of symbols with one of the above stream codes, you have
@@ -213,6 +223,18 @@
 //!     [`SmallContiguousCategoricalEntropyModel`],
 //!     [`SmallNonContiguousCategoricalEncoderModel`],
 //!     [`SmallNonContiguousCategoricalDecoderModel`], and [`SmallLeakyQuantizer`].
+//! - **"Large" presets** may be considered if the (already very small) quantization
+//!   overhead of the "default" presets becomes relevant, e.g., for very large alphabets or
+//!   for extremely skewed distributions. The "large" presets use 64&nbsp;bit words, a
+//!   128&nbsp;bit state, and a numerical precision of 48&nbsp;bits. They are typically
+//!   somewhat slower than the "default" presets since 128&nbsp;bit arithmetic is not
+//!   natively supported on most hardware.
+//!   - entropy *coders* with "large" presets: [`LargeAnsCoder`], [`LargeRangeEncoder`],
+//!     [`LargeRangeDecoder`], and [`LargeChainCoder`];
+//!   - entropy *models* with "large" presets: [`LargeLeakyQuantizer`],
+//!     [`LargeContiguousCategoricalEntropyModel`],
+//!     [`LargeNonContiguousCategoricalEncoderModel`], and
+//!     [`LargeNonContiguousCategoricalDecoderModel`].
 //!
 //! You'll usually want to use matching presets for entropy *coders* and entropy *models*.
 //! However, it is legal to use entropy models with the "small" preset for an entropy coder
@@ -295,6 +317,14 @@
 //! [`SmallContiguousCategoricalEntropyModel`]: model::SmallContiguousCategoricalEntropyModel
 //! [`SmallNonContiguousCategoricalEncoderModel`]: model::SmallNonContiguousCategoricalEncoderModel
 //! [`SmallNonContiguousCategoricalDecoderModel`]: model::SmallNonContiguousCategoricalDecoderModel
+//! [`LargeAnsCoder`]: stack::LargeAnsCoder
+//! [`LargeRangeEncoder`]: queue::LargeRangeEncoder
+//! [`LargeRangeDecoder`]: queue::LargeRangeDecoder
+//! [`LargeChainCoder`]: chain::LargeChainCoder
+//! [`LargeLeakyQuantizer`]: model::LargeLeakyQuantizer
+//! [`LargeContiguousCategoricalEntropyModel`]: model::LargeContiguousCategoricalEntropyModel
+//! [`LargeNonContiguousCategoricalEncoderModel`]: model::LargeNonContiguousCategoricalEncoderModel
+//! [`LargeNonContiguousCategoricalDecoderModel`]: model::LargeNonContiguousCategoricalDecoderModel
 //! [`AnsCoder`]: stack::AnsCoder
 //! [`AnsCoder::from_binary`]: stack::AnsCoder::from_binary
 //! [`ChainCoder`]: chain::ChainCoder
@@ -306,10 +336,14 @@
 
 #![allow(clippy::type_complexity)]
 
+pub mod binary;
 pub mod chain;
 pub mod model;
+#[cfg(feature = "parallel")]
+pub mod parallel;
 pub mod queue;
 pub mod stack;
+pub mod tans;
 
 use core::{
     borrow::Borrow,
@@ -1356,3 +1390,96 @@ impl<CodingError, ModelError> From<CodingError> for TryCodingError<CodingError,
         Self::CodingError(err)
     }
 }
+
+/// The error type for the `finish` methods of decoders, which check that the compressed
+/// data was consumed exactly.
+///
+/// Returned by [`AnsCoder::finish`], [`RangeDecoder::finish`], and [`ChainCoder::finish`].
+/// Any of these errors indicates that the decoded symbols don't correspond exactly to the
+/// symbols that were encoded, e.g., because the number of decoded symbols doesn't match,
+/// because some entropy models differ between encoder and decoder, or because the
+/// compressed data was corrupted, truncated, or concatenated with some trailing data.
+///
+/// [`AnsCoder::finish`]: stack::AnsCoder::finish
+/// [`RangeDecoder::finish`]: queue::RangeDecoder::finish
+/// [`ChainCoder::finish`]: chain::ChainCoder::finish
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FinishError {
+    /// The decoder hasn't consumed all compressed data (or, for a `ChainCoder`, all
+    /// remainders). The variant holds the number of `Word`s that remain unread or that
+    /// trail the data written by the encoder.
+    UnreadWords(usize),
+
+    /// The internal state of the decoder doesn't match the state in which the encoder
+    /// started encoding.
+    InvalidFinalState,
+
+    /// The end of the compressed data doesn't match the padding that the encoder writes
+    /// when sealing the compressed data (only for [`RangeDecoder`](queue::RangeDecoder)).
+    InvalidPadding,
+}
+
+impl Display for FinishError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::UnreadWords(num_words) => write!(
+                f,
+                "Decoding finished with {num_words} words of compressed data left over."
+            ),
+            Self::InvalidFinalState => write!(
+                f,
+                "Decoding finished in a different state than the one in which encoding started."
+            ),
+            Self::InvalidPadding => write!(
+                f,
+                "The compressed data doesn't end in the padding written by the encoder."
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FinishError {}
+
+#[cfg(test)]
+mod tests {
+    use super::model::{LeakilyQuantizedDistribution, LeakyQuantizer};
+    use crate::BitArray;
+
+    use alloc::vec::Vec;
+    use num_traits::AsPrimitive;
+    use probability::distribution::{Gaussian, Inverse};
+    use rand_xoshiro::{rand_core::RngCore, Xoshiro256StarStar};
+
+    /// Samples `amt` symbols, each from a Gaussian with a random mean in `-100..100` and a
+    /// random standard deviation up to `max_std_dev`, and returns them together with
+    /// their entropy models.
+    ///
+    /// Symbols are clamped to the support of `quantizer`.
+    #[allow(clippy::type_complexity)]
+    pub(super) fn random_gaussian_symbols<Probability, const PRECISION: usize>(
+        quantizer: LeakyQuantizer<f64, i32, Probability, PRECISION>,
+        max_std_dev: f64,
+        amt: usize,
+        rng: &mut Xoshiro256StarStar,
+    ) -> Vec<(
+        i32,
+        LeakilyQuantizedDistribution<f64, i32, Probability, Gaussian, PRECISION>,
+    )>
+    where
+        Probability: BitArray,
+        i32: AsPrimitive<Probability>,
+    {
+        let support = quantizer.support();
+        (0..amt)
+            .map(|_| {
+                let mean = (200.0 / u32::MAX as f64) * rng.next_u32() as f64 - 100.0;
+                let std_dev = (max_std_dev / u32::MAX as f64) * rng.next_u32() as f64 + 0.001;
+                let quantile = (rng.next_u32() as f64 + 0.5) / (1u64 << 32) as f64;
+                let symbol = (Gaussian::new(mean, std_dev).inverse(quantile).round() as i32)
+                    .clamp(*support.start(), *support.end());
+                (symbol, quantizer.quantize(Gaussian::new(mean, std_dev)))
+            })
+            .collect()
+    }
+}
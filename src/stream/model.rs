@@ -1175,10 +1175,22 @@ pub type DefaultLeakyQuantizer<F, Symbol> = LeakyQuantizer<F, Symbol, u32, 24>;
 /// - [discussion of presets](super#presets)
 pub type SmallLeakyQuantizer<F, Symbol> = LeakyQuantizer<F, Symbol, u16, 12>;
 
+/// Type alias for a [`LeakyQuantizer`] with a high numerical precision.
+///
+/// Use with a "large" entropy coder, e.g., a [`LargeAnsCoder`] or a [`LargeRangeEncoder`].
+///
+/// See:
+/// - [`LeakyQuantizer`]
+/// - [discussion of presets](super#presets)
+///
+/// [`LargeAnsCoder`]: super::stack::LargeAnsCoder
+/// [`LargeRangeEncoder`]: super::queue::LargeRangeEncoder
+pub type LargeLeakyQuantizer<F, Symbol> = LeakyQuantizer<F, Symbol, u64, 48>;
+
 impl<F, Symbol, Probability, const PRECISION: usize>
     LeakyQuantizer<F, Symbol, Probability, PRECISION>
 where
-    Probability: BitArray + Into<F>,
+    Probability: BitArray,
     Symbol: PrimInt + AsPrimitive<Probability> + WrappingSub + WrappingAdd,
     F: FloatCore,
 {
@@ -1205,7 +1217,9 @@ where
     ///   distributions that put all probability mass on a single symbol); or
     /// - `support` is larger than `1 << PRECISION` (because in this case, assigning any
     ///   representable nonzero probability to all elements of `support` would exceed our
-    ///   probability budge).
+    ///   probability budge); or
+    /// - `PRECISION` is so high that the float type `F` cannot represent all probabilities
+    ///   exactly (e.g., `PRECISION > 53` for `F = f64`).
     ///
     /// [`quantize`]: #method.quantize
     pub fn new(support: RangeInclusive<Symbol>) -> Self {
@@ -1219,8 +1233,10 @@ where
         let max_probability = Probability::max_value() >> (Probability::BITS - PRECISION);
         let free_weight = max_probability
             .checked_sub(&support_size_minus_one)
-            .expect("The support is too large to assign a nonzero probability to each element.")
-            .into();
+            .expect("The support is too large to assign a nonzero probability to each element.");
+        let free_weight = F::from(free_weight)
+            .filter(|&f| Probability::from(f) == Some(free_weight))
+            .expect("`PRECISION` is too high to be represented exactly by the float type `F`.");
 
         LeakyQuantizer {
             min_symbol_inclusive: *support.start(),
@@ -1308,7 +1324,7 @@ pub struct LeakilyQuantizedDistribution<F, Symbol, Probability, D, const PRECISI
 impl<F, Symbol, Probability, D, const PRECISION: usize>
     LeakilyQuantizedDistribution<F, Symbol, Probability, D, PRECISION>
 where
-    Probability: BitArray + Into<F>,
+    Probability: BitArray,
     Symbol: PrimInt + AsPrimitive<Probability> + WrappingSub + WrappingAdd,
     F: FloatCore,
 {
@@ -1410,7 +1426,7 @@ impl<Symbol, Probability, D, const PRECISION: usize> EncoderModel<PRECISION>
 where
     f64: AsPrimitive<Probability>,
     Symbol: PrimInt + AsPrimitive<Probability> + Into<f64> + WrappingSub,
-    Probability: BitArray + Into<f64>,
+    Probability: BitArray,
     D: Distribution,
     D::Value: AsPrimitive<Symbol>,
 {
@@ -1484,7 +1500,7 @@ impl<Symbol, Probability, D, const PRECISION: usize> DecoderModel<PRECISION>
 where
     f64: AsPrimitive<Probability>,
     Symbol: PrimInt + AsPrimitive<Probability> + Into<f64> + WrappingSub + WrappingAdd,
-    Probability: BitArray + Into<f64>,
+    Probability: BitArray,
     D: Inverse,
     D::Value: AsPrimitive<Symbol>,
 {
@@ -1497,7 +1513,7 @@ where
         // of this method.
         assert!(quantile <= max_probability);
 
-        let inverse_denominator = 1.0 / (max_probability.into() + 1.0);
+        let inverse_denominator = 1.0 / (probability_to_f64(max_probability) + 1.0);
 
         let min_symbol_inclusive = self.quantizer.min_symbol_inclusive;
         let max_symbol_inclusive = self.quantizer.max_symbol_inclusive;
@@ -1506,7 +1522,7 @@ where
         // Make an initial guess for the inverse of the leaky CDF.
         let mut symbol: Self::Symbol = self
             .inner
-            .inverse((quantile.into() + 0.5) * inverse_denominator)
+            .inverse((probability_to_f64(quantile) + 0.5) * inverse_denominator)
             .as_();
 
         let mut left_sided_cumulative = if symbol <= min_symbol_inclusive {
@@ -1695,7 +1711,7 @@ impl<'m, 'q: 'm, Symbol, Probability, D, const PRECISION: usize> IterableEntropy
 where
     f64: AsPrimitive<Probability>,
     Symbol: PrimInt + AsPrimitive<Probability> + AsPrimitive<usize> + Into<f64> + WrappingSub,
-    Probability: BitArray + Into<f64>,
+    Probability: BitArray,
     D: Distribution + 'm,
     D::Value: AsPrimitive<Symbol>,
 {
@@ -1733,7 +1749,7 @@ impl<'m, Symbol, Probability, D, const PRECISION: usize> Iterator
 where
     f64: AsPrimitive<Probability>,
     Symbol: PrimInt + AsPrimitive<Probability> + AsPrimitive<usize> + Into<f64> + WrappingSub,
-    Probability: BitArray + Into<f64>,
+    Probability: BitArray,
     D: Distribution,
     D::Value: AsPrimitive<Symbol>,
 {
@@ -1749,7 +1765,7 @@ where
             let next_symbol = symbol + Symbol::one();
             self.symbol = Some(next_symbol);
             let non_leaky: Probability = (self.model.quantizer.free_weight
-                * self.model.inner.distribution((symbol).into() - 0.5))
+                * self.model.inner.distribution((symbol).into() + 0.5))
             .as_();
             non_leaky + slack(next_symbol, self.model.quantizer.min_symbol_inclusive)
         };
@@ -1769,7 +1785,7 @@ where
 
     fn size_hint(&self) -> (usize, Option<usize>) {
         if let Some(symbol) = self.symbol {
-            let len = slack::<usize, _>(symbol, self.model.quantizer.max_symbol_inclusive)
+            let len = slack::<usize, _>(self.model.quantizer.max_symbol_inclusive, symbol)
                 .saturating_add(1);
             (len, None)
         } else {
@@ -2193,6 +2209,14 @@ pub type DefaultContiguousCategoricalEntropyModel<Table = Vec<u32>> =
 pub type SmallContiguousCategoricalEntropyModel<Table = Vec<u16>> =
     ContiguousCategoricalEntropyModel<u16, Table, 12>;
 
+/// Type alias for a [`ContiguousCategoricalEntropyModel`] with a high numerical precision.
+///
+/// See:
+/// - [`ContiguousCategoricalEntropyModel`]
+/// - [discussion of presets](super#presets)
+pub type LargeContiguousCategoricalEntropyModel<Table = Vec<u64>> =
+    ContiguousCategoricalEntropyModel<u64, Table, 48>;
+
 /// Type alias for a typical [`NonContiguousCategoricalDecoderModel`].
 ///
 /// See:
@@ -2210,6 +2234,15 @@ pub type DefaultNonContiguousCategoricalDecoderModel<Symbol, Table = Vec<(u32, S
 pub type SmallNonContiguousCategoricalDecoderModel<Symbol, Table = Vec<(u16, Symbol)>> =
     NonContiguousCategoricalDecoderModel<Symbol, u16, Table, 12>;
 
+/// Type alias for a [`NonContiguousCategoricalDecoderModel`] with a high numerical
+/// precision.
+///
+/// See:
+/// - [`NonContiguousCategoricalDecoderModel`]
+/// - [discussion of presets](super#presets)
+pub type LargeNonContiguousCategoricalDecoderModel<Symbol, Table = Vec<(u64, Symbol)>> =
+    NonContiguousCategoricalDecoderModel<Symbol, u64, Table, 48>;
+
 impl<Probability: BitArray, const PRECISION: usize>
     ContiguousCategoricalEntropyModel<Probability, Vec<Probability>, PRECISION>
 {
@@ -2261,7 +2294,7 @@ impl<Probability: BitArray, const PRECISION: usize>
     pub fn from_floating_point_probabilities<F>(probabilities: &[F]) -> Result<Self, ()>
     where
         F: FloatCore + core::iter::Sum<F> + Into<f64>,
-        Probability: Into<f64> + AsPrimitive<usize>,
+        Probability: AsPrimitive<usize>,
         f64: AsPrimitive<Probability>,
         usize: AsPrimitive<Probability>,
     {
@@ -2459,7 +2492,7 @@ where
     ) -> Result<Self, ()>
     where
         F: FloatCore + core::iter::Sum<F> + Into<f64>,
-        Probability: Into<f64> + AsPrimitive<usize>,
+        Probability: AsPrimitive<usize>,
         f64: AsPrimitive<Probability>,
         usize: AsPrimitive<Probability>,
     {
@@ -2977,6 +3010,15 @@ pub type DefaultNonContiguousCategoricalEncoderModel<Symbol> =
 pub type SmallNonContiguousCategoricalEncoderModel<Symbol> =
     NonContiguousCategoricalEncoderModel<Symbol, u16, 12>;
 
+/// Type alias for a [`NonContiguousCategoricalEncoderModel`] with a high numerical
+/// precision.
+///
+/// See:
+/// - [`NonContiguousCategoricalEncoderModel`]
+/// - [discussion of presets](super#presets)
+pub type LargeNonContiguousCategoricalEncoderModel<Symbol> =
+    NonContiguousCategoricalEncoderModel<Symbol, u64, 48>;
+
 impl<Symbol, Probability, const PRECISION: usize>
     NonContiguousCategoricalEncoderModel<Symbol, Probability, PRECISION>
 where
@@ -2996,7 +3038,7 @@ where
     ) -> Result<Self, ()>
     where
         F: FloatCore + core::iter::Sum<F> + Into<f64>,
-        Probability: Into<f64> + AsPrimitive<usize>,
+        Probability: AsPrimitive<usize>,
         f64: AsPrimitive<Probability>,
         usize: AsPrimitive<Probability>,
     {
@@ -3200,7 +3242,7 @@ fn optimize_leaky_categorical<Probability, F, const PRECISION: usize>(
 ) -> Result<Vec<Slot<Probability>>, ()>
 where
     F: FloatCore + core::iter::Sum<F> + Into<f64>,
-    Probability: BitArray + Into<f64> + AsPrimitive<usize>,
+    Probability: BitArray + AsPrimitive<usize>,
     f64: AsPrimitive<Probability>,
     usize: AsPrimitive<Probability>,
 {
@@ -3218,7 +3260,7 @@ where
     if !normalization.is_normal() || !normalization.is_sign_positive() {
         return Err(());
     }
-    let scale = remaining_free_weight.into() / normalization;
+    let scale = probability_to_f64(remaining_free_weight) / normalization;
 
     let mut slots = probabilities
         .iter()
@@ -3228,18 +3270,20 @@ where
                 return Err(());
             }
             let prob: f64 = prob.into();
-            let current_free_weight = (prob * scale).as_();
+            // Clamp to guard against rounding errors, which become relevant if
+            // `Probability` has more bits than the mantissa of an `f64`.
+            let current_free_weight = (prob * scale).as_().min(remaining_free_weight);
             remaining_free_weight = remaining_free_weight - current_free_weight;
             let weight = current_free_weight + Probability::one();
 
             // How much the cross entropy would decrease when increasing the weight by one.
-            let win = prob * log1p(1.0f64 / weight.into());
+            let win = prob * log1p(1.0f64 / probability_to_f64(weight));
 
             // How much the cross entropy would increase when decreasing the weight by one.
             let loss = if weight == Probability::one() {
                 f64::infinity()
             } else {
-                -prob * log1p(-1.0f64 / weight.into())
+                -prob * log1p(-1.0f64 / probability_to_f64(weight))
             };
 
             Ok(Slot {
@@ -3260,8 +3304,8 @@ where
         let batch_size = core::cmp::min(remaining_free_weight.as_(), slots.len());
         for slot in &mut slots[..batch_size] {
             slot.weight = slot.weight + Probability::one(); // Cannot end up in `max_weight` because win would otherwise be -infinity.
-            slot.win = slot.prob * log1p(1.0f64 / slot.weight.into());
-            slot.loss = -slot.prob * log1p(-1.0f64 / slot.weight.into());
+            slot.win = slot.prob * log1p(1.0f64 / probability_to_f64(slot.weight));
+            slot.loss = -slot.prob * log1p(-1.0f64 / probability_to_f64(slot.weight));
         }
         remaining_free_weight = remaining_free_weight - batch_size.as_();
     }
@@ -3300,19 +3344,26 @@ where
         seller.loss = if seller.weight == Probability::one() {
             f64::infinity()
         } else {
-            -seller.prob * log1p(-1.0f64 / seller.weight.into())
+            -seller.prob * log1p(-1.0f64 / probability_to_f64(seller.weight))
         };
 
         let buyer = &mut slots[buyer_index];
         buyer.weight = buyer.weight + Probability::one();
         buyer.loss = f64::infinity(); // Once a weight gets increased it may never be decreased again.
-        buyer.win = buyer.prob * log1p(1.0f64 / buyer.weight.into());
+        buyer.win = buyer.prob * log1p(1.0f64 / probability_to_f64(buyer.weight));
     }
 
     slots.sort_unstable_by_key(|slot| slot.original_index);
     Ok(slots)
 }
 
+/// Unlike `Into<f64>`, this also works for `u64` and `u128` (where it may round).
+#[inline(always)]
+fn probability_to_f64<Probability: BitArray>(probability: Probability) -> f64 {
+    // Converting an unsigned integer to `f64` never fails.
+    probability.to_f64().unwrap()
+}
+
 // LOOKUP TABLE ENTROPY MODELS (FOR FAST DECODING) ================================================
 
 /// A tabularized [`DecoderModel`] that is optimized for fast decoding of i.i.d. symbols
@@ -3440,7 +3491,7 @@ where
     ) -> Result<Self, ()>
     where
         F: FloatCore + core::iter::Sum<F> + Into<f64>,
-        Probability: Into<f64> + AsPrimitive<usize>,
+        Probability: AsPrimitive<usize>,
         f64: AsPrimitive<Probability>,
         usize: AsPrimitive<Probability>,
     {
@@ -3551,7 +3602,7 @@ where
     pub fn from_floating_point_probabilities_contiguous<F>(probabilities: &[F]) -> Result<Self, ()>
     where
         F: FloatCore + core::iter::Sum<F> + Into<f64>,
-        Probability: Into<f64> + AsPrimitive<usize>,
+        Probability: AsPrimitive<usize>,
         f64: AsPrimitive<Probability>,
         usize: AsPrimitive<Probability>,
     {
@@ -3946,6 +3997,70 @@ mod tests {
         }
     }
 
+    #[test]
+    fn leakily_quantized_symbol_table() {
+        let quantizer = LeakyQuantizer::<_, _, u32, 24>::new(-20..=20);
+        for &(mean, std_dev) in &[(0.0, 3.5), (-7.3, 0.1), (12.6, 100.0)] {
+            let model = quantizer.quantize(Gaussian::new(mean, std_dev));
+            let mut symbol_table = model.symbol_table();
+            let mut expected_symbol = -20;
+            loop {
+                let num_remaining = (21 - expected_symbol) as usize;
+                assert_eq!(symbol_table.size_hint().0, num_remaining);
+                let Some((symbol, left_cumulative, probability)) = symbol_table.next() else {
+                    break;
+                };
+                assert_eq!(symbol, expected_symbol);
+                assert_eq!(
+                    model.left_cumulative_and_probability(symbol),
+                    Some((left_cumulative, probability))
+                );
+                expected_symbol += 1;
+            }
+            assert_eq!(expected_symbol, 21);
+        }
+    }
+
+    #[test]
+    fn large_precision() {
+        let quantizer = LargeLeakyQuantizer::new(-127..=127);
+        for &std_dev in &[0.0001, 3.5, 1234.56] {
+            for &mean in &[-300.6, -5.2, 50.3] {
+                let distribution = Gaussian::new(mean, std_dev);
+                test_entropy_model(&quantizer.quantize(distribution), -127..128);
+            }
+        }
+
+        let probabilities = [1e-11, 0.3, 2e-10, 0.5, 1e-12, 0.2];
+        let model = LargeContiguousCategoricalEntropyModel::from_floating_point_probabilities(
+            &probabilities,
+        )
+        .unwrap();
+        test_entropy_model(&model, 0..6);
+        // The tiny probabilities are still resolved to within a few percent.
+        for (symbol, &expected) in probabilities.iter().enumerate() {
+            let probability = model.left_cumulative_and_probability(symbol).unwrap().1;
+            let probability = probability.get() as f64 / (1u64 << 48) as f64;
+            assert!((probability / expected - 1.0).abs() < 0.05);
+        }
+
+        let model =
+            ContiguousCategoricalEntropyModel::<u64, _, 64>::from_floating_point_probabilities(
+                &probabilities,
+            )
+            .unwrap();
+        let total = model.symbol_table().fold(0u64, |acc, (_, _, probability)| {
+            acc.wrapping_add(probability.get())
+        });
+        assert_eq!(total, 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn leaky_quantizer_precision_exceeds_float() {
+        LeakyQuantizer::<f64, i32, u64, 64>::new(-127..=127);
+    }
+
     #[test]
     fn uniform() {
         for range in [2, 3, 4, 5, 6, 7, 8, 9, 62, 63, 64, 254, 255, 256] {
@@ -4155,6 +4270,16 @@ mod tests {
         }
         assert_eq!(sum, 1 << PRECISION);
 
+        // The symbol table has to be consistent with `left_cumulative_and_probability`.
+        let symbol_table = model.symbol_table();
+        assert!(symbol_table.size_hint().0 <= support.clone().count());
+        for (symbol, left_cumulative, prob) in symbol_table {
+            assert_eq!(
+                model.left_cumulative_and_probability(symbol),
+                Some((left_cumulative, prob))
+            );
+        }
+
         test_iterable_entropy_model(model, support);
     }
 
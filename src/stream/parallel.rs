@@ -0,0 +1,1180 @@
+//! Multithreaded encoding and decoding of independent chunks
+//!
+//! This module is only available if the crate feature `parallel` is enabled. It adds
+//! methods to [`AnsCoder`], [`RangeEncoder`], and [`RangeDecoder`] that split a long
+//! sequence of symbols into chunks of a fixed number of symbols, encode (or decode) each
+//! chunk with its own entropy coder on a thread pool, and concatenate the results into a
+//! single buffer of compressed words.
+//!
+//! In addition, the module provides [`SplitPoints`], which allow decoding even a *single*
+//! stream of compressed data from an [`AnsCoder`] on several threads without the overhead
+//! of independent chunks.
+//!
+//! Each chunk is an independent substream, i.e., it starts from a fresh entropy coder and
+//! is flushed separately. This costs a few bits of overhead per chunk (on the order of the
+//! size of the coder's `State`), so chunks should typically contain at least a few
+//! thousand symbols.
+//!
+//! # Container Format
+//!
+//! The returned buffer starts with a chunk index, followed by the compressed data of each
+//! chunk in order. The chunk index consists of the following entries, each of which is
+//! stored as a 64 bit unsigned integer in big-endian order and split into as many
+//! `Word`s as necessary (e.g., two `u32`s or a single `u64`):
+//!
+//! 1. the total number of symbols;
+//! 2. the number of symbols per chunk (all chunks except the last one are full);
+//! 3. for each chunk, the number of `Word`s of compressed data in this chunk.
+//!
+//! The compressed data of each chunk is exactly what [`AnsCoder::into_compressed`] or
+//! [`RangeEncoder::into_compressed`] would return for the symbols in this chunk.
+//!
+//! # Example
+//!
+//! ```
+//! use constriction::stream::{
+//!     model::DefaultLeakyQuantizer,
+//!     queue::{DefaultRangeDecoder, DefaultRangeEncoder},
+//!     stack::DefaultAnsCoder,
+//! };
+//! use probability::distribution::Gaussian;
+//!
+//! let symbols = (0..10_000).map(|i| (i % 100) - 50).collect::<Vec<i32>>();
+//! let quantizer = DefaultLeakyQuantizer::new(-100..=100);
+//! let model_for = |index: usize| {
+//!     quantizer.quantize(Gaussian::new((index % 100) as f64 - 50.3, 2.0))
+//! };
+//!
+//! // Encode and decode chunks of 1000 symbols each on the stack ...
+//! let compressed = DefaultAnsCoder::encode_symbols_parallel(&symbols, 1000, model_for)
+//!     .unwrap();
+//! let decoded = DefaultAnsCoder::decode_symbols_parallel(&compressed, model_for).unwrap();
+//! assert_eq!(decoded, symbols);
+//!
+//! // ... or in a queue.
+//! let compressed = DefaultRangeEncoder::encode_symbols_parallel(&symbols, 1000, model_for)
+//!     .unwrap();
+//! let decoded = DefaultRangeDecoder::decode_symbols_parallel(&compressed, model_for).unwrap();
+//! assert_eq!(decoded, symbols);
+//! ```
+//!
+//! Since chunks are processed out of order, entropy models are provided through a closure
+//! that maps the index of a symbol within the entire sequence to its entropy model rather
+//! than through an iterator. For i.i.d. symbols, use the `*_iid_*` variants of the above
+//! methods instead.
+
+use alloc::vec::Vec;
+use core::{borrow::Borrow, convert::TryFrom, fmt::Display};
+
+use num_traits::{AsPrimitive, NumCast};
+use rayon::prelude::*;
+
+use super::{
+    model::{DecoderModel, EncoderModel},
+    queue::{RangeDecoder, RangeEncoder},
+    stack::AnsCoder,
+    Decode, Encode,
+};
+use crate::{
+    backends::{Cursor, ReadWords, WriteWords},
+    BitArray, CoderError, DefaultEncoderError, Pos, Queue, Seek, UnwrapInfallible,
+};
+
+impl<Word, State> AnsCoder<Word, State>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+{
+    /// Encodes `symbols` in independent chunks of `chunk_size` symbols on a thread pool.
+    ///
+    /// The closure `models` maps the index of each symbol within `symbols` to its entropy
+    /// model. Returns a single buffer in the [container format](self#container-format)
+    /// of this module, which can be decoded with [`decode_symbols_parallel`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is zero.
+    ///
+    /// [`decode_symbols_parallel`]: Self::decode_symbols_parallel
+    pub fn encode_symbols_parallel<S, M, F, const PRECISION: usize>(
+        symbols: &[S],
+        chunk_size: usize,
+        models: F,
+    ) -> Result<Vec<Word>, DefaultEncoderError<core::convert::Infallible>>
+    where
+        S: Borrow<M::Symbol> + Sync,
+        M: EncoderModel<PRECISION>,
+        M::Probability: Into<Word>,
+        Word: AsPrimitive<M::Probability> + Send,
+        F: Fn(usize) -> M + Sync,
+    {
+        encode_chunks(symbols, chunk_size, |offset, chunk| {
+            let mut coder = Self::new();
+            coder.encode_symbols_reverse(
+                chunk
+                    .iter()
+                    .enumerate()
+                    .map(|(i, symbol)| (symbol.borrow(), models(offset + i))),
+            )?;
+            Ok(coder.into_compressed().unwrap_infallible())
+        })
+    }
+
+    /// Encodes i.i.d. `symbols` in independent chunks of `chunk_size` symbols on a thread
+    /// pool.
+    ///
+    /// See [`encode_symbols_parallel`](Self::encode_symbols_parallel).
+    pub fn encode_iid_symbols_parallel<S, M, const PRECISION: usize>(
+        symbols: &[S],
+        chunk_size: usize,
+        model: M,
+    ) -> Result<Vec<Word>, DefaultEncoderError<core::convert::Infallible>>
+    where
+        S: Borrow<M::Symbol> + Sync,
+        M: EncoderModel<PRECISION> + Copy + Sync,
+        M::Probability: Into<Word>,
+        Word: AsPrimitive<M::Probability> + Send,
+    {
+        Self::encode_symbols_parallel(symbols, chunk_size, move |_| model)
+    }
+
+    /// Decodes data that was encoded with [`encode_symbols_parallel`] on a thread pool.
+    ///
+    /// The closure `models` has to map the index of each symbol to the same entropy model
+    /// that was used for encoding.
+    ///
+    /// [`encode_symbols_parallel`]: Self::encode_symbols_parallel
+    pub fn decode_symbols_parallel<M, F, const PRECISION: usize>(
+        compressed: &[Word],
+        models: F,
+    ) -> Result<Vec<M::Symbol>, ParallelDecoderError>
+    where
+        M: DecoderModel<PRECISION>,
+        M::Symbol: Send,
+        M::Probability: Into<Word>,
+        Word: AsPrimitive<M::Probability> + Sync,
+        F: Fn(usize) -> M + Sync,
+    {
+        decode_chunks(compressed, |offset, amt, chunk| {
+            let mut coder = AnsCoder::<Word, State, _>::from_compressed_slice(chunk)
+                .map_err(|()| ParallelDecoderError::InvalidData)?;
+            coder
+                .decode_symbols((offset..offset + amt).map(&models))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| ParallelDecoderError::InvalidData)
+        })
+    }
+
+    /// Decodes data that was encoded with [`encode_iid_symbols_parallel`] on a thread
+    /// pool.
+    ///
+    /// [`encode_iid_symbols_parallel`]: Self::encode_iid_symbols_parallel
+    pub fn decode_iid_symbols_parallel<M, const PRECISION: usize>(
+        compressed: &[Word],
+        model: M,
+    ) -> Result<Vec<M::Symbol>, ParallelDecoderError>
+    where
+        M: DecoderModel<PRECISION> + Copy + Sync,
+        M::Symbol: Send,
+        M::Probability: Into<Word>,
+        Word: AsPrimitive<M::Probability> + Sync,
+    {
+        Self::decode_symbols_parallel(compressed, move |_| model)
+    }
+}
+
+impl<Word, State> RangeEncoder<Word, State>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+{
+    /// Encodes `symbols` in independent chunks of `chunk_size` symbols on a thread pool.
+    ///
+    /// The closure `models` maps the index of each symbol within `symbols` to its entropy
+    /// model. Returns a single buffer in the [container format](self#container-format)
+    /// of this module, which can be decoded with
+    /// [`RangeDecoder::decode_symbols_parallel`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is zero.
+    pub fn encode_symbols_parallel<S, M, F, const PRECISION: usize>(
+        symbols: &[S],
+        chunk_size: usize,
+        models: F,
+    ) -> Result<Vec<Word>, DefaultEncoderError<core::convert::Infallible>>
+    where
+        S: Borrow<M::Symbol> + Sync,
+        M: EncoderModel<PRECISION>,
+        M::Probability: Into<Word>,
+        Word: AsPrimitive<M::Probability> + Send,
+        F: Fn(usize) -> M + Sync,
+    {
+        encode_chunks(symbols, chunk_size, |offset, chunk| {
+            let mut encoder = Self::new();
+            encoder.encode_symbols(
+                chunk
+                    .iter()
+                    .enumerate()
+                    .map(|(i, symbol)| (symbol.borrow(), models(offset + i))),
+            )?;
+            Ok(encoder.into_compressed().unwrap_infallible())
+        })
+    }
+
+    /// Encodes i.i.d. `symbols` in independent chunks of `chunk_size` symbols on a thread
+    /// pool.
+    ///
+    /// See [`encode_symbols_parallel`](Self::encode_symbols_parallel).
+    pub fn encode_iid_symbols_parallel<S, M, const PRECISION: usize>(
+        symbols: &[S],
+        chunk_size: usize,
+        model: M,
+    ) -> Result<Vec<Word>, DefaultEncoderError<core::convert::Infallible>>
+    where
+        S: Borrow<M::Symbol> + Sync,
+        M: EncoderModel<PRECISION> + Copy + Sync,
+        M::Probability: Into<Word>,
+        Word: AsPrimitive<M::Probability> + Send,
+    {
+        Self::encode_symbols_parallel(symbols, chunk_size, move |_| model)
+    }
+}
+
+impl<'a, Word, State> RangeDecoder<Word, State, Cursor<Word, &'a [Word]>>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+{
+    /// Decodes data that was encoded with [`RangeEncoder::encode_symbols_parallel`] on a
+    /// thread pool.
+    ///
+    /// The closure `models` has to map the index of each symbol to the same entropy model
+    /// that was used for encoding. Each chunk is decoded by its own `RangeDecoder` that
+    /// reads directly from the corresponding part of `compressed`.
+    pub fn decode_symbols_parallel<M, F, const PRECISION: usize>(
+        compressed: &'a [Word],
+        models: F,
+    ) -> Result<Vec<M::Symbol>, ParallelDecoderError>
+    where
+        M: DecoderModel<PRECISION>,
+        M::Symbol: Send,
+        M::Probability: Into<Word>,
+        Word: AsPrimitive<M::Probability> + Sync,
+        F: Fn(usize) -> M + Sync,
+    {
+        decode_chunks(compressed, |offset, amt, chunk| {
+            let mut decoder =
+                RangeDecoder::<Word, State, _>::from_compressed(chunk).unwrap_infallible();
+            decoder
+                .decode_symbols((offset..offset + amt).map(&models))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| ParallelDecoderError::InvalidData)
+        })
+    }
+
+    /// Decodes data that was encoded with [`RangeEncoder::encode_iid_symbols_parallel`] on
+    /// a thread pool.
+    ///
+    /// See [`decode_symbols_parallel`](Self::decode_symbols_parallel).
+    pub fn decode_iid_symbols_parallel<M, const PRECISION: usize>(
+        compressed: &'a [Word],
+        model: M,
+    ) -> Result<Vec<M::Symbol>, ParallelDecoderError>
+    where
+        M: DecoderModel<PRECISION> + Copy + Sync,
+        M::Symbol: Send,
+        M::Probability: Into<Word>,
+        Word: AsPrimitive<M::Probability> + Sync,
+    {
+        Self::decode_symbols_parallel(compressed, move |_| model)
+    }
+}
+
+// SPLIT POINTS FOR A SINGLE CONTIGUOUS STREAM ===================================================
+
+/// A point from which decoding of a single [`AnsCoder`] stream can be restarted.
+///
+/// Seeking a decoder to `(pos, state)` (see [`Seek::seek`]) and then decoding yields the
+/// symbols starting at index `symbol_index` within the sequence of recorded symbols.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SplitPoint<State> {
+    pub symbol_index: usize,
+    pub pos: usize,
+    pub state: State,
+}
+
+/// Metadata that allows decoding a single [`AnsCoder`] stream on several threads.
+///
+/// Unlike the chunked methods at the top of this module, split points don't cut the
+/// compressed data into independent substreams, so they cost no compression
+/// effectiveness. Instead, they record snapshots of the encoder's position and state (as
+/// returned by [`Pos::pos`]) while encoding a single stream (as proposed in the "Recoil"
+/// paper \[1]). The decoder can then [`Seek::seek`] several independent decoders to these
+/// snapshots and decode the segments between them concurrently, see
+/// [`AnsCoder::decode_symbols_from_split_points`].
+///
+/// Obtain `SplitPoints` from a [`SplitPointRecorder`], store them next to the compressed
+/// data with [`serialize`](Self::serialize), and read them back with
+/// [`deserialize`](Self::deserialize).
+///
+/// # Example
+///
+/// ```
+/// use constriction::{
+///     backends::Cursor,
+///     stream::{
+///         model::DefaultContiguousCategoricalEntropyModel,
+///         parallel::{SplitPointRecorder, SplitPoints},
+///         stack::DefaultAnsCoder,
+///     },
+/// };
+///
+/// let model = DefaultContiguousCategoricalEntropyModel
+///     ::from_floating_point_probabilities(&[0.2, 0.5, 0.3]).unwrap();
+/// let symbols = (0..10_000).map(|i| (i * i) % 3).collect::<Vec<usize>>();
+///
+/// // Encode into a single stream and record a split point every 1000 symbols.
+/// let mut coder = DefaultAnsCoder::new();
+/// let mut recorder = SplitPointRecorder::new(&coder, 1000);
+/// recorder.encode_iid_symbols_reverse(&mut coder, &symbols, &model).unwrap();
+/// let split_points = recorder.into_split_points();
+/// let compressed = coder.into_compressed().unwrap();
+/// assert_eq!(split_points.points().len(), 9);
+///
+/// // Serialize the split points, e.g., to store them in a header.
+/// let mut header = Vec::new();
+/// split_points.serialize(&mut header).unwrap();
+///
+/// // Decode on a thread pool.
+/// let mut source = Cursor::new_at_write_beginning(&header[..]);
+/// let split_points = SplitPoints::deserialize(&mut source).unwrap();
+/// let decoded = DefaultAnsCoder::decode_iid_symbols_from_split_points(
+///     &compressed, &split_points, &model
+/// ).unwrap();
+/// assert_eq!(decoded, symbols);
+/// ```
+///
+/// # References
+///
+/// \[1] Lin, Fangzhou, Kazuki Arunruangsirilert, Heming Sun, and Jiro Katto. "Recoil:
+/// Parallel rANS Decoding with Decoder-Adaptive Scalability." Proceedings of the 52nd
+/// International Conference on Parallel Processing (2023).
+///
+/// [`Pos::pos`]: crate::Pos::pos
+/// [`Seek::seek`]: crate::Seek::seek
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SplitPoints<State> {
+    num_symbols: usize,
+
+    /// Sorted by `symbol_index` in strictly ascending order, with all indices in
+    /// `1..num_symbols`.
+    points: Vec<SplitPoint<State>>,
+
+    /// Position and state of the coder *before* the first recorded symbol was encoded
+    /// (i.e., after the last symbol has been decoded).
+    end: (usize, State),
+}
+
+impl<State: BitArray> SplitPoints<State> {
+    /// Returns the total number of symbols covered by these split points.
+    pub fn num_symbols(&self) -> usize {
+        self.num_symbols
+    }
+
+    /// Returns the split points, sorted by ascending `symbol_index`.
+    ///
+    /// The beginning of the stream (`symbol_index == 0`) is not included since decoding can
+    /// always start there.
+    pub fn points(&self) -> &[SplitPoint<State>] {
+        &self.points
+    }
+
+    /// Writes the split points to `sink` in a compact representation.
+    ///
+    /// Each `usize` is written as two `u32` words, and each `State` as `State::BITS / 32`
+    /// words (but at least one), all in big-endian order. The representation can be read
+    /// back with [`deserialize`](Self::deserialize).
+    pub fn serialize<B: WriteWords<u32>>(&self, sink: &mut B) -> Result<(), B::WriteError> {
+        sink.write(State::BITS as u32)?;
+        write_u64(sink, self.num_symbols)?;
+        write_u64(sink, self.points.len())?;
+        write_u64(sink, self.end.0)?;
+        write_state(sink, self.end.1)?;
+        for point in &self.points {
+            write_u64(sink, point.symbol_index)?;
+            write_u64(sink, point.pos)?;
+            write_state(sink, point.state)?;
+        }
+        Ok(())
+    }
+
+    /// Reads split points that were written with [`serialize`](Self::serialize).
+    ///
+    /// Returns `Err(CoderError::Frontend(ParallelDecoderError::InvalidIndex))` if `source`
+    /// does not contain valid serialized split points for the same `State` type.
+    pub fn deserialize<B: ReadWords<u32, Queue>>(
+        source: &mut B,
+    ) -> Result<Self, CoderError<ParallelDecoderError, B::ReadError>> {
+        if read_u32(source)? as usize != State::BITS {
+            return Err(CoderError::Frontend(ParallelDecoderError::InvalidIndex));
+        }
+        let num_symbols = read_u64(source)?;
+        let num_points = read_u64(source)?;
+        if num_points >= num_symbols.max(1) {
+            return Err(CoderError::Frontend(ParallelDecoderError::InvalidIndex));
+        }
+        let end = (read_u64(source)?, read_state(source)?);
+
+        let mut points = Vec::new();
+        for _ in 0..num_points {
+            let symbol_index = read_u64(source)?;
+            let pos = read_u64(source)?;
+            let state = read_state(source)?;
+            points.push(SplitPoint {
+                symbol_index,
+                pos,
+                state,
+            });
+        }
+
+        let split_points = Self {
+            num_symbols,
+            points,
+            end,
+        };
+        if split_points.is_valid() {
+            Ok(split_points)
+        } else {
+            Err(CoderError::Frontend(ParallelDecoderError::InvalidIndex))
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        let mut last_index = 0;
+        for point in &self.points {
+            if point.symbol_index <= last_index {
+                return false;
+            }
+            last_index = point.symbol_index;
+        }
+        last_index < self.num_symbols || self.points.is_empty()
+    }
+}
+
+/// Records [`SplitPoints`] while encoding symbols on an [`AnsCoder`].
+///
+/// Records a split point after every `interval` encoded symbols. Since an `AnsCoder`
+/// operates as a stack, the recorded symbols are decoded in reverse order, and the symbol
+/// indices of the resulting split points count from the symbol that was encoded *last*
+/// (i.e., the one that gets decoded first).
+///
+/// See [`SplitPoints`] for an example.
+#[derive(Debug, Clone)]
+pub struct SplitPointRecorder<State> {
+    interval: usize,
+    num_encoded: usize,
+
+    /// Tuples `(num_encoded, pos, state)`, in the order in which they were recorded.
+    recorded: Vec<(usize, usize, State)>,
+
+    start: (usize, State),
+}
+
+impl<State: BitArray> SplitPointRecorder<State> {
+    /// Creates a recorder for symbols that will be encoded onto `coder`.
+    ///
+    /// Any compressed data that is already on `coder` remains untouched by decoding with
+    /// the resulting [`SplitPoints`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `interval` is zero.
+    pub fn new<Word, Backend>(coder: &AnsCoder<Word, State, Backend>, interval: usize) -> Self
+    where
+        Word: BitArray + Into<State>,
+        State: AsPrimitive<Word>,
+        Backend: Pos<Position = usize>,
+    {
+        assert!(
+            interval != 0,
+            "Split points must be at least one symbol apart."
+        );
+        Self {
+            interval,
+            num_encoded: 0,
+            recorded: Vec::new(),
+            start: coder.pos(),
+        }
+    }
+
+    /// Encodes symbols in reverse order onto `coder` (see
+    /// [`AnsCoder::encode_symbols_reverse`]) and records split points along the way.
+    pub fn encode_symbols_reverse<Word, Backend, S, M, I, const PRECISION: usize>(
+        &mut self,
+        coder: &mut AnsCoder<Word, State, Backend>,
+        symbols_and_models: I,
+    ) -> Result<(), DefaultEncoderError<Backend::WriteError>>
+    where
+        Word: BitArray + Into<State>,
+        State: AsPrimitive<Word>,
+        Backend: WriteWords<Word> + Pos<Position = usize>,
+        S: Borrow<M::Symbol>,
+        M: EncoderModel<PRECISION>,
+        M::Probability: Into<Word>,
+        Word: AsPrimitive<M::Probability>,
+        I: IntoIterator<Item = (S, M)>,
+        I::IntoIter: DoubleEndedIterator,
+    {
+        for (symbol, model) in symbols_and_models.into_iter().rev() {
+            coder.encode_symbol(symbol, model)?;
+            self.num_encoded += 1;
+            if self.num_encoded.is_multiple_of(self.interval) {
+                let (pos, state) = coder.pos();
+                self.recorded.push((self.num_encoded, pos, state));
+            }
+        }
+        Ok(())
+    }
+
+    /// Encodes i.i.d. symbols in reverse order onto `coder` (see
+    /// [`AnsCoder::encode_iid_symbols_reverse`]) and records split points along the way.
+    pub fn encode_iid_symbols_reverse<Word, Backend, S, M, I, const PRECISION: usize>(
+        &mut self,
+        coder: &mut AnsCoder<Word, State, Backend>,
+        symbols: I,
+        model: M,
+    ) -> Result<(), DefaultEncoderError<Backend::WriteError>>
+    where
+        Word: BitArray + Into<State>,
+        State: AsPrimitive<Word>,
+        Backend: WriteWords<Word> + Pos<Position = usize>,
+        S: Borrow<M::Symbol>,
+        M: EncoderModel<PRECISION> + Copy,
+        M::Probability: Into<Word>,
+        Word: AsPrimitive<M::Probability>,
+        I: IntoIterator<Item = S>,
+        I::IntoIter: DoubleEndedIterator,
+    {
+        self.encode_symbols_reverse(coder, symbols.into_iter().map(|symbol| (symbol, model)))
+    }
+
+    /// Returns the number of symbols that have been encoded through this recorder so far.
+    pub fn num_encoded(&self) -> usize {
+        self.num_encoded
+    }
+
+    /// Finishes recording.
+    ///
+    /// Don't encode any further symbols onto the coder if you want to decode its data with
+    /// the returned [`SplitPoints`].
+    pub fn into_split_points(self) -> SplitPoints<State> {
+        let num_symbols = self.num_encoded;
+        let points = self
+            .recorded
+            .into_iter()
+            .rev()
+            .filter(|&(num_encoded, _, _)| num_encoded != num_symbols)
+            .map(|(num_encoded, pos, state)| SplitPoint {
+                symbol_index: num_symbols - num_encoded,
+                pos,
+                state,
+            })
+            .collect();
+
+        SplitPoints {
+            num_symbols,
+            points,
+            end: self.start,
+        }
+    }
+}
+
+impl<Word, State> AnsCoder<Word, State>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+{
+    /// Decodes a single stream on a thread pool, starting one decoder at each split point.
+    ///
+    /// The argument `compressed` has to be the data returned by [`into_compressed`] on the
+    /// coder onto which the symbols were encoded (with no further symbols encoded after
+    /// finishing the [`SplitPointRecorder`]). The closure `models` maps the index of each
+    /// symbol (counting from the first decoded symbol) to its entropy model.
+    ///
+    /// Each decoder verifies that it ends up exactly at the next split point, so using the
+    /// wrong entropy models or mismatching split points is likely (but not guaranteed) to
+    /// be detected and reported as `Err(ParallelDecoderError::InvalidData)`.
+    ///
+    /// [`into_compressed`]: Self::into_compressed
+    pub fn decode_symbols_from_split_points<M, F, const PRECISION: usize>(
+        compressed: &[Word],
+        split_points: &SplitPoints<State>,
+        models: F,
+    ) -> Result<Vec<M::Symbol>, ParallelDecoderError>
+    where
+        M: DecoderModel<PRECISION>,
+        M::Symbol: Send,
+        M::Probability: Into<Word>,
+        Word: AsPrimitive<M::Probability> + Sync,
+        State: Send + Sync,
+        F: Fn(usize) -> M + Sync,
+    {
+        if !split_points.is_valid() {
+            return Err(ParallelDecoderError::InvalidIndex);
+        }
+
+        let starts = core::iter::once(None)
+            .chain(split_points.points.iter().map(Some))
+            .collect::<Vec<_>>();
+        let decoded = starts
+            .into_par_iter()
+            .enumerate()
+            .map(|(segment, start)| {
+                let mut coder = AnsCoder::<Word, State, _>::from_compressed_slice(compressed)
+                    .map_err(|()| ParallelDecoderError::InvalidData)?;
+                let begin = match start {
+                    None => 0,
+                    Some(point) => {
+                        coder
+                            .seek((point.pos, point.state))
+                            .map_err(|()| ParallelDecoderError::InvalidIndex)?;
+                        point.symbol_index
+                    }
+                };
+                let (end, expected_pos) = match split_points.points.get(segment) {
+                    Some(next) => (next.symbol_index, (next.pos, next.state)),
+                    None => (split_points.num_symbols, split_points.end),
+                };
+
+                let symbols = coder
+                    .decode_symbols((begin..end).map(&models))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|_| ParallelDecoderError::InvalidData)?;
+                if coder.pos() != expected_pos {
+                    return Err(ParallelDecoderError::InvalidData);
+                }
+                Ok(symbols)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(decoded.into_iter().flatten().collect())
+    }
+
+    /// Decodes i.i.d. symbols from a single stream on a thread pool.
+    ///
+    /// See [`decode_symbols_from_split_points`](Self::decode_symbols_from_split_points).
+    pub fn decode_iid_symbols_from_split_points<M, const PRECISION: usize>(
+        compressed: &[Word],
+        split_points: &SplitPoints<State>,
+        model: M,
+    ) -> Result<Vec<M::Symbol>, ParallelDecoderError>
+    where
+        M: DecoderModel<PRECISION> + Copy + Sync,
+        M::Symbol: Send,
+        M::Probability: Into<Word>,
+        Word: AsPrimitive<M::Probability> + Sync,
+        State: Send + Sync,
+    {
+        Self::decode_symbols_from_split_points(compressed, split_points, move |_| model)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParallelDecoderError {
+    /// The chunk index at the beginning of the compressed data (or the [`SplitPoints`]) is
+    /// malformed or inconsistent with the compressed data.
+    InvalidIndex,
+
+    /// The compressed data of at least one chunk is invalid for the employed entropy
+    /// models.
+    InvalidData,
+}
+
+impl Display for ParallelDecoderError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::InvalidIndex => write!(f, "Invalid chunk index or split points."),
+            Self::InvalidData => write!(f, "Invalid compressed data in a chunk."),
+        }
+    }
+}
+
+impl std::error::Error for ParallelDecoderError {}
+
+fn encode_chunks<Word, S, E>(
+    symbols: &[S],
+    chunk_size: usize,
+    encode_chunk: impl Fn(usize, &[S]) -> Result<Vec<Word>, CoderError<E, core::convert::Infallible>>
+        + Sync,
+) -> Result<Vec<Word>, CoderError<E, core::convert::Infallible>>
+where
+    Word: BitArray + Send,
+    S: Sync,
+    E: Send,
+{
+    assert!(chunk_size != 0, "Chunks must contain at least one symbol.");
+
+    let chunks = symbols
+        .par_chunks(chunk_size)
+        .enumerate()
+        .map(|(index, chunk)| encode_chunk(index * chunk_size, chunk))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let index_len = (2 + chunks.len()) * words_per_index_entry::<Word>();
+    let data_len = chunks.iter().map(Vec::len).sum::<usize>();
+    let mut compressed = Vec::with_capacity(index_len + data_len);
+
+    push_index_entry(&mut compressed, symbols.len());
+    push_index_entry(&mut compressed, chunk_size);
+    for chunk in &chunks {
+        push_index_entry(&mut compressed, chunk.len());
+    }
+    for chunk in chunks {
+        compressed.extend_from_slice(&chunk);
+    }
+
+    Ok(compressed)
+}
+
+fn decode_chunks<Word, Symbol>(
+    compressed: &[Word],
+    decode_chunk: impl Fn(usize, usize, &[Word]) -> Result<Vec<Symbol>, ParallelDecoderError> + Sync,
+) -> Result<Vec<Symbol>, ParallelDecoderError>
+where
+    Word: BitArray + Sync,
+    Symbol: Send,
+{
+    let mut remaining = compressed;
+    let num_symbols = pop_index_entry(&mut remaining)?;
+    let chunk_size = pop_index_entry(&mut remaining)?;
+    let num_chunks = match (num_symbols, chunk_size) {
+        (0, _) => 0,
+        (_, 0) => return Err(ParallelDecoderError::InvalidIndex),
+        _ => (num_symbols - 1) / chunk_size + 1,
+    };
+
+    // Check this before allocating anything so that a corrupted index can't make us
+    // allocate huge amounts of memory.
+    if num_chunks > remaining.len() / words_per_index_entry::<Word>() {
+        return Err(ParallelDecoderError::InvalidIndex);
+    }
+    let lengths = (0..num_chunks)
+        .map(|_| pop_index_entry(&mut remaining))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut chunks = Vec::with_capacity(num_chunks);
+    for len in lengths {
+        if len > remaining.len() {
+            return Err(ParallelDecoderError::InvalidIndex);
+        }
+        let (chunk, rest) = remaining.split_at(len);
+        chunks.push(chunk);
+        remaining = rest;
+    }
+    if !remaining.is_empty() {
+        return Err(ParallelDecoderError::InvalidIndex);
+    }
+
+    let decoded = chunks
+        .into_par_iter()
+        .enumerate()
+        .map(|(index, chunk)| {
+            let offset = index * chunk_size;
+            decode_chunk(offset, chunk_size.min(num_symbols - offset), chunk)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(decoded.into_iter().flatten().collect())
+}
+
+/// Number of `Word`s that make up each (64 bit) entry of the chunk index.
+#[inline(always)]
+fn words_per_index_entry<Word: BitArray>() -> usize {
+    64usize.div_ceil(Word::BITS)
+}
+
+fn push_index_entry<Word: BitArray>(compressed: &mut Vec<Word>, value: usize) {
+    let value = value as u64;
+    let mask = u64::MAX >> (64 - Word::BITS.min(64));
+    for i in (0..words_per_index_entry::<Word>()).rev() {
+        let word = <Word as NumCast>::from((value >> (i * Word::BITS)) & mask);
+        compressed.push(word.expect("masked to `Word::BITS` bits"));
+    }
+}
+
+fn pop_index_entry<Word: BitArray>(remaining: &mut &[Word]) -> Result<usize, ParallelDecoderError> {
+    let len = words_per_index_entry::<Word>();
+    if remaining.len() < len {
+        return Err(ParallelDecoderError::InvalidIndex);
+    }
+    let (entry, rest) = remaining.split_at(len);
+    *remaining = rest;
+
+    let value = entry.iter().fold(0u128, |acc, word| {
+        acc << Word::BITS | word.to_u128().expect("`Word` has at most 128 bits")
+    });
+    usize::try_from(value).map_err(|_| ParallelDecoderError::InvalidIndex)
+}
+
+fn write_u64<B: WriteWords<u32>>(sink: &mut B, value: usize) -> Result<(), B::WriteError> {
+    let value = value as u64;
+    sink.write((value >> 32) as u32)?;
+    sink.write(value as u32)
+}
+
+fn write_state<State: BitArray, B: WriteWords<u32>>(
+    sink: &mut B,
+    state: State,
+) -> Result<(), B::WriteError> {
+    let state = state.to_u128().expect("`State` has at most 128 bits");
+    for i in (0..State::BITS.div_ceil(32)).rev() {
+        sink.write((state >> (32 * i)) as u32)?;
+    }
+    Ok(())
+}
+
+fn read_u32<B: ReadWords<u32, Queue>>(
+    source: &mut B,
+) -> Result<u32, CoderError<ParallelDecoderError, B::ReadError>> {
+    source
+        .read()?
+        .ok_or(CoderError::Frontend(ParallelDecoderError::InvalidIndex))
+}
+
+fn read_u64<B: ReadWords<u32, Queue>>(
+    source: &mut B,
+) -> Result<usize, CoderError<ParallelDecoderError, B::ReadError>> {
+    let high = read_u32(source)? as u64;
+    let low = read_u32(source)? as u64;
+    usize::try_from(high << 32 | low)
+        .map_err(|_| CoderError::Frontend(ParallelDecoderError::InvalidIndex))
+}
+
+fn read_state<State: BitArray, B: ReadWords<u32, Queue>>(
+    source: &mut B,
+) -> Result<State, CoderError<ParallelDecoderError, B::ReadError>> {
+    let mut state = 0u128;
+    for _ in 0..State::BITS.div_ceil(32) {
+        state = state << 32 | read_u32(source)? as u128;
+    }
+    <State as NumCast>::from(state).ok_or(CoderError::Frontend(ParallelDecoderError::InvalidIndex))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        model::{
+            DefaultContiguousCategoricalEntropyModel, DefaultLeakyQuantizer,
+            SmallContiguousCategoricalEntropyModel,
+        },
+        queue::{DefaultRangeDecoder, DefaultRangeEncoder, SmallRangeDecoder, SmallRangeEncoder},
+        stack::{DefaultAnsCoder, SmallAnsCoder},
+    };
+    use super::*;
+
+    use probability::distribution::Gaussian;
+    use rand_xoshiro::{
+        rand_core::{RngCore, SeedableRng},
+        Xoshiro256StarStar,
+    };
+
+    #[cfg(not(miri))]
+    const AMT: usize = 10_000;
+
+    #[cfg(miri)]
+    const AMT: usize = 100;
+
+    fn random_symbols(amt: usize, seed: u64) -> Vec<i32> {
+        let mut rng = Xoshiro256StarStar::seed_from_u64(seed);
+        (0..amt).map(|_| (rng.next_u32() % 9) as i32 - 4).collect()
+    }
+
+    #[test]
+    fn ans_round_trip() {
+        let symbols = random_symbols(AMT, 1);
+        let quantizer = DefaultLeakyQuantizer::new(-10..=10);
+        let models = |i: usize| quantizer.quantize(Gaussian::new((i % 5) as f64 - 2.0, 3.0));
+
+        for &chunk_size in &[1, 7, 1000, AMT - 1, AMT, 3 * AMT] {
+            let compressed =
+                DefaultAnsCoder::encode_symbols_parallel(&symbols, chunk_size, models).unwrap();
+            let decoded = DefaultAnsCoder::decode_symbols_parallel(&compressed, models).unwrap();
+            assert_eq!(decoded, symbols);
+        }
+
+        // The data of each chunk is what a sequential `AnsCoder` would produce.
+        let compressed = DefaultAnsCoder::encode_symbols_parallel(&symbols, 1000, models).unwrap();
+        let num_chunks = AMT.div_ceil(1000);
+        let mut expected = Vec::new();
+        for (index, chunk) in symbols.chunks(1000).enumerate() {
+            let mut coder = DefaultAnsCoder::new();
+            coder
+                .encode_symbols_reverse(
+                    chunk
+                        .iter()
+                        .enumerate()
+                        .map(|(i, &symbol)| (symbol, models(1000 * index + i))),
+                )
+                .unwrap();
+            let chunk_compressed = coder.into_compressed().unwrap();
+            assert_eq!(
+                compressed[2 * (2 + index)..2 * (3 + index)],
+                [0, chunk_compressed.len() as u32]
+            );
+            expected.extend(chunk_compressed);
+        }
+        assert_eq!(compressed[..4], [0, AMT as u32, 0, 1000]);
+        assert_eq!(compressed[2 * (2 + num_chunks)..], expected[..]);
+    }
+
+    #[test]
+    fn range_round_trip() {
+        let symbols = random_symbols(AMT, 2);
+        let quantizer = DefaultLeakyQuantizer::new(-10..=10);
+        let models = |i: usize| quantizer.quantize(Gaussian::new((i % 5) as f64 - 2.0, 3.0));
+
+        for &chunk_size in &[1, 7, 1000, AMT - 1, AMT, 3 * AMT] {
+            let compressed =
+                DefaultRangeEncoder::encode_symbols_parallel(&symbols, chunk_size, models).unwrap();
+            let decoded =
+                DefaultRangeDecoder::decode_symbols_parallel(&compressed, models).unwrap();
+            assert_eq!(decoded, symbols);
+        }
+    }
+
+    #[test]
+    fn iid_and_small_words() {
+        let symbols = random_symbols(AMT, 3)
+            .into_iter()
+            .map(|x| (x + 4) as usize)
+            .collect::<Vec<_>>();
+        let model = DefaultContiguousCategoricalEntropyModel::from_floating_point_probabilities(&[
+            0.1, 0.05, 0.2, 0.1, 0.15, 0.1, 0.05, 0.2, 0.05,
+        ])
+        .unwrap();
+        let small_model =
+            SmallContiguousCategoricalEntropyModel::from_floating_point_probabilities(&[
+                0.1, 0.05, 0.2, 0.1, 0.15, 0.1, 0.05, 0.2, 0.05,
+            ])
+            .unwrap();
+
+        let compressed =
+            DefaultAnsCoder::encode_iid_symbols_parallel(&symbols, 999, &model).unwrap();
+        let decoded = DefaultAnsCoder::decode_iid_symbols_parallel(&compressed, &model).unwrap();
+        assert_eq!(decoded, symbols);
+
+        let compressed =
+            DefaultRangeEncoder::encode_iid_symbols_parallel(&symbols, 999, &model).unwrap();
+        let decoded =
+            DefaultRangeDecoder::decode_iid_symbols_parallel(&compressed, &model).unwrap();
+        assert_eq!(decoded, symbols);
+
+        // With 16 bit words, each entry of the chunk index occupies four words.
+        let compressed =
+            SmallAnsCoder::encode_iid_symbols_parallel(&symbols, 999, &small_model).unwrap();
+        assert_eq!(compressed[..8], [0, 0, 0, AMT as u16, 0, 0, 0, 999]);
+        let decoded =
+            SmallAnsCoder::decode_iid_symbols_parallel(&compressed, &small_model).unwrap();
+        assert_eq!(decoded, symbols);
+
+        let compressed =
+            SmallRangeEncoder::encode_iid_symbols_parallel(&symbols, 999, &small_model).unwrap();
+        let decoded =
+            SmallRangeDecoder::decode_iid_symbols_parallel(&compressed, &small_model).unwrap();
+        assert_eq!(decoded, symbols);
+    }
+
+    #[test]
+    fn empty() {
+        let model = DefaultContiguousCategoricalEntropyModel::from_floating_point_probabilities(&[
+            0.3, 0.7,
+        ])
+        .unwrap();
+        let compressed =
+            DefaultAnsCoder::encode_iid_symbols_parallel(&[] as &[usize], 10, &model).unwrap();
+        assert_eq!(compressed, [0, 0, 0, 10]);
+        let decoded = DefaultAnsCoder::decode_iid_symbols_parallel(&compressed, &model).unwrap();
+        assert!(decoded.is_empty());
+    }
+
+    #[test]
+    fn invalid_index() {
+        let model = DefaultContiguousCategoricalEntropyModel::from_floating_point_probabilities(&[
+            0.3, 0.7,
+        ])
+        .unwrap();
+        let symbols = [0, 1, 1, 0, 1, 1, 1];
+        let compressed =
+            DefaultRangeEncoder::encode_iid_symbols_parallel(&symbols, 3, &model).unwrap();
+
+        let decode = |compressed: &[u32]| {
+            DefaultRangeDecoder::decode_iid_symbols_parallel(compressed, &model)
+        };
+        assert_eq!(decode(&compressed).unwrap(), symbols);
+
+        // Truncated data or index.
+        for len in 0..compressed.len() {
+            assert_eq!(
+                decode(&compressed[..len]),
+                Err(ParallelDecoderError::InvalidIndex)
+            );
+        }
+
+        // Trailing data.
+        let mut extended = compressed.clone();
+        extended.push(0);
+        assert_eq!(decode(&extended), Err(ParallelDecoderError::InvalidIndex));
+
+        // Zero chunk size.
+        let mut corrupted = compressed.clone();
+        corrupted[3] = 0;
+        assert_eq!(decode(&corrupted), Err(ParallelDecoderError::InvalidIndex));
+
+        // Number of chunks that can't possibly fit.
+        let mut corrupted = compressed;
+        corrupted[0] = u32::MAX;
+        assert_eq!(decode(&corrupted), Err(ParallelDecoderError::InvalidIndex));
+    }
+
+    #[test]
+    fn split_points() {
+        let symbols = random_symbols(AMT, 4);
+        let quantizer = DefaultLeakyQuantizer::new(-10..=10);
+        let models = |i: usize| quantizer.quantize(Gaussian::new((i % 5) as f64 - 2.0, 3.0));
+
+        for &interval in &[1, 7, 1000, AMT - 1, AMT, 3 * AMT] {
+            let mut coder = DefaultAnsCoder::new();
+            let mut recorder = SplitPointRecorder::new(&coder, interval);
+            recorder
+                .encode_symbols_reverse(
+                    &mut coder,
+                    symbols
+                        .iter()
+                        .enumerate()
+                        .map(|(i, &symbol)| (symbol, models(i))),
+                )
+                .unwrap();
+            assert_eq!(recorder.num_encoded(), AMT);
+            let split_points = recorder.into_split_points();
+            assert_eq!(split_points.num_symbols(), AMT);
+            assert_eq!(split_points.points().len(), (AMT - 1) / interval);
+            let compressed = coder.into_compressed().unwrap();
+
+            // Split points cost no compression effectiveness.
+            let mut reference = DefaultAnsCoder::new();
+            reference
+                .encode_symbols_reverse(
+                    symbols
+                        .iter()
+                        .enumerate()
+                        .map(|(i, &symbol)| (symbol, models(i))),
+                )
+                .unwrap();
+            assert_eq!(reference.into_compressed().unwrap(), compressed);
+
+            let decoded = DefaultAnsCoder::decode_symbols_from_split_points(
+                &compressed,
+                &split_points,
+                models,
+            )
+            .unwrap();
+            assert_eq!(decoded, symbols);
+
+            let mut serialized = Vec::new();
+            split_points.serialize(&mut serialized).unwrap();
+            assert_eq!(serialized.len(), 9 + 6 * split_points.points().len());
+            let deserialized =
+                SplitPoints::deserialize(&mut Cursor::new_at_write_beginning(&serialized[..]))
+                    .unwrap();
+            assert_eq!(deserialized, split_points);
+
+            // Truncated metadata is detected.
+            assert!(
+                SplitPoints::<u64>::deserialize(&mut Cursor::new_at_write_beginning(
+                    &serialized[..serialized.len() - 1]
+                ))
+                .is_err()
+            );
+            // Metadata for a different `State` type is rejected.
+            assert_eq!(
+                SplitPoints::<u32>::deserialize(&mut Cursor::new_at_write_beginning(
+                    &serialized[..]
+                )),
+                Err(CoderError::Frontend(ParallelDecoderError::InvalidIndex))
+            );
+        }
+    }
+
+    #[test]
+    fn split_points_on_nonempty_coder() {
+        let model = DefaultContiguousCategoricalEntropyModel::from_floating_point_probabilities(&[
+            0.2, 0.5, 0.3,
+        ])
+        .unwrap();
+        let prefix = [2, 0, 1, 1];
+        let symbols = random_symbols(AMT, 5)
+            .into_iter()
+            .map(|x| (x + 4) as usize % 3)
+            .collect::<Vec<_>>();
+
+        // Data that was already on the coder before recording remains on the stack.
+        let mut coder = DefaultAnsCoder::new();
+        coder.encode_iid_symbols_reverse(&prefix, &model).unwrap();
+        let mut recorder = SplitPointRecorder::new(&coder, 100);
+        recorder
+            .encode_iid_symbols_reverse(&mut coder, &symbols, &model)
+            .unwrap();
+        let split_points = recorder.into_split_points();
+        let compressed = coder.into_compressed().unwrap();
+
+        let decoded = DefaultAnsCoder::decode_iid_symbols_from_split_points(
+            &compressed,
+            &split_points,
+            &model,
+        )
+        .unwrap();
+        assert_eq!(decoded, symbols);
+
+        // Decoding with the wrong model is detected.
+        let wrong_model =
+            DefaultContiguousCategoricalEntropyModel::from_floating_point_probabilities(&[
+                0.3, 0.5, 0.2,
+            ])
+            .unwrap();
+        assert_eq!(
+            DefaultAnsCoder::decode_iid_symbols_from_split_points(
+                &compressed,
+                &split_points,
+                &wrong_model
+            ),
+            Err(ParallelDecoderError::InvalidData)
+        );
+
+        // Small coders (with a 32 bit state) work too.
+        let small_model =
+            SmallContiguousCategoricalEntropyModel::from_floating_point_probabilities(&[
+                0.2, 0.5, 0.3,
+            ])
+            .unwrap();
+        let mut coder = SmallAnsCoder::new();
+        let mut recorder = SplitPointRecorder::new(&coder, 333);
+        recorder
+            .encode_iid_symbols_reverse(&mut coder, &symbols, &small_model)
+            .unwrap();
+        let split_points = recorder.into_split_points();
+        let compressed = coder.into_compressed().unwrap();
+        let mut serialized = Vec::new();
+        split_points.serialize(&mut serialized).unwrap();
+        assert_eq!(serialized.len(), 8 + 5 * split_points.points().len());
+        let split_points =
+            SplitPoints::deserialize(&mut Cursor::new_at_write_beginning(&serialized[..])).unwrap();
+        let decoded = SmallAnsCoder::decode_iid_symbols_from_split_points(
+            &compressed,
+            &split_points,
+            &small_model,
+        )
+        .unwrap();
+        assert_eq!(decoded, symbols);
+    }
+}
@@ -17,7 +17,9 @@
 //! Range Coder cannot be interleaved: once you've *sealed* a `RangeEncoder` (e.g., by
 //! calling [`.into_compressed()`] on it) you cannot add any more compressed data onto it.
 //! This makes Range Coding difficult to use for advanced compression techniques such as
-//! bits-back coding with hierarchical models.
+//! bits-back coding with hierarchical models. (If you only need to make the data written
+//! so far decodable and then continue encoding, e.g., for low-latency streaming, see
+//! [`RangeEncoder::flush_to_boundary`].)
 //!
 //! The parent module contains a more detailed discussion of the [differences between ANS
 //! Coding and Range Coding](super#which-stream-code-should-i-use) .
@@ -44,10 +46,15 @@ use num_traits::AsPrimitive;
 
 use super::{
     model::{DecoderModel, EncoderModel},
-    Code, Decode, Encode, IntoDecoder,
+    Code, Decode, Encode, FinishError, IntoDecoder,
 };
+#[cfg(feature = "async")]
+use crate::backends::{AsyncReadWords, AsyncWriteWords};
 use crate::{
-    backends::{AsReadWords, BoundedReadWords, Cursor, IntoReadWords, ReadWords, WriteWords},
+    backends::{
+        AsReadWords, BoundedReadWords, BoundedWriteError, ByteVec, Cursor, Endianness,
+        IntoReadWords, PushReadWords, ReadWords, WriteWords,
+    },
     BitArray, CoderError, DefaultEncoderError, DefaultEncoderFrontendError, NonZeroBitArray, Pos,
     PosSeek, Queue, Seek, UnwrapInfallible,
 };
@@ -119,8 +126,9 @@ where
 /// [`RangeEncoder`].
 ///
 /// This type is mostly for internal use. It is only expsed via
-/// [`RangeEncoder::into_raw_parts`] and [`RangeEncoder::from_raw_parts`].
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// [`RangeEncoder::into_raw_parts`], [`RangeEncoder::from_raw_parts`], and
+/// [`RangeEncoder::situation`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum EncoderSituation<Word> {
     /// In the `Normal` situation, all full `Words` of compressed data have been written to
     /// the backend (or "bulk"), and the internal coder state holds less than one word of
@@ -136,6 +144,13 @@ pub enum EncoderSituation<Word> {
     /// held-back words can become either `first_word + 1` followed by `num_subsequent` zero
     /// words, or `first_word` followed by `num_subsequent` words that have all bits set.
     Inverted(NonZeroUsize, Word),
+
+    /// The encoder is at a flush boundary, i.e., [`RangeEncoder::flush_to_boundary`] has
+    /// been called and no symbols have been encoded since. In this situation, the data on
+    /// the backend (or "bulk") can be decoded on its own, and the internal coder state is
+    /// the same as that of a newly created encoder. Encoding the next symbol transitions
+    /// back to the `Normal` situation.
+    Flushed,
 }
 
 impl<Word> Default for EncoderSituation<Word> {
@@ -144,6 +159,17 @@ impl<Word> Default for EncoderSituation<Word> {
     }
 }
 
+/// A snapshot of a [`RangeEncoder`] that can be restored with [`RangeEncoder::rollback`].
+///
+/// Obtained from [`RangeEncoder::checkpoint`]. A checkpoint doesn't copy any compressed
+/// data, so it is cheap to create.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RangeEncoderCheckpoint<Word, State: BitArray> {
+    num_words: usize,
+    state: RangeCoderState<Word, State>,
+    situation: EncoderSituation<Word>,
+}
+
 /// Type alias for an [`RangeEncoder`] with sane parameters for typical use cases.
 pub type DefaultRangeEncoder<Backend = Vec<u32>> = RangeEncoder<u32, u64, Backend>;
 
@@ -159,6 +185,17 @@ pub type DefaultRangeEncoder<Backend = Vec<u32>> = RangeEncoder<u32, u64, Backen
 /// [`SmallNonContiguousLookupDecoderModel`]: super::model::SmallNonContiguousLookupDecoderModel
 pub type SmallRangeEncoder<Backend = Vec<u16>> = RangeEncoder<u16, u32, Backend>;
 
+/// Type alias for a [`RangeEncoder`] for use with high-precision entropy models
+///
+/// This encoder has a larger word size and internal state than [`DefaultRangeEncoder`],
+/// which allows it to be used with entropy models whose `PRECISION` exceeds 32 bits, such
+/// as a [`LargeContiguousCategoricalEntropyModel`] or a [`LargeLeakyQuantizer`]. Decode
+/// the resulting data with a [`LargeRangeDecoder`].
+///
+/// [`LargeContiguousCategoricalEntropyModel`]: super::model::LargeContiguousCategoricalEntropyModel
+/// [`LargeLeakyQuantizer`]: super::model::LargeLeakyQuantizer
+pub type LargeRangeEncoder<Backend = Vec<u64>> = RangeEncoder<u64, u128, Backend>;
+
 impl<Word, State, Backend> Code for RangeEncoder<Word, State, Backend>
 where
     Word: BitArray + Into<State>,
@@ -356,7 +393,93 @@ where
         Ok(())
     }
 
-    fn num_seal_words(&self) -> usize {
+    /// Makes all data encoded so far decodable without terminating the stream.
+    ///
+    /// After calling this method, the compressed data on the backend can be decoded on its
+    /// own, i.e., a [`RangeDecoder`] that reads only the data written so far recovers all
+    /// symbols encoded so far. This is useful for low-latency streaming, where you
+    /// periodically want to send out a decodable prefix of the compressed data but then
+    /// continue encoding. Unlike [`into_compressed`](Self::into_compressed), this method
+    /// doesn't consume the encoder, and you can encode more symbols afterwards.
+    ///
+    /// Flushing costs one or two `Word`s of compressed data (the same words that
+    /// `into_compressed` appends), plus any words that the encoder held back (see
+    /// [`EncoderSituation::Inverted`]). It then resets the internal coder state and sets the
+    /// [`situation`](Self::situation) to [`EncoderSituation::Flushed`]. Calling this method
+    /// again without encoding any symbols in between is a no-op.
+    ///
+    /// A decoder has to call [`RangeDecoder::skip_flush_padding`] at the same position in
+    /// the sequence of symbols, i.e., after decoding all symbols that were encoded before
+    /// the call to `flush_to_boundary`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use constriction::stream::{
+    ///     model::DefaultLeakyQuantizer,
+    ///     queue::{DefaultRangeDecoder, DefaultRangeEncoder, EncoderSituation},
+    ///     Decode, Encode,
+    /// };
+    ///
+    /// let quantizer = DefaultLeakyQuantizer::new(-100..=100);
+    /// let model = quantizer.quantize(probability::distribution::Gaussian::new(0.0, 10.0));
+    /// let batch1 = [3, -7, 12, 0];
+    /// let batch2 = [-2, 9, 5];
+    ///
+    /// let mut encoder = DefaultRangeEncoder::new();
+    /// encoder.encode_iid_symbols(&batch1, &model).unwrap();
+    /// encoder.flush_to_boundary().unwrap();
+    /// assert_eq!(encoder.situation(), EncoderSituation::Flushed);
+    ///
+    /// // The data written so far can already be decoded.
+    /// let prefix = encoder.bulk().clone();
+    /// let mut decoder = DefaultRangeDecoder::from_compressed(prefix).unwrap();
+    /// let decoded = decoder
+    ///     .decode_iid_symbols(batch1.len(), &model)
+    ///     .collect::<Result<Vec<_>, _>>()
+    ///     .unwrap();
+    /// assert_eq!(decoded, batch1);
+    ///
+    /// // Continue encoding and decode the full stream.
+    /// encoder.encode_iid_symbols(&batch2, &model).unwrap();
+    /// let compressed = encoder.into_compressed().unwrap();
+    /// let mut decoder = DefaultRangeDecoder::from_compressed(compressed).unwrap();
+    /// let decoded1 = decoder
+    ///     .decode_iid_symbols(batch1.len(), &model)
+    ///     .collect::<Result<Vec<_>, _>>()
+    ///     .unwrap();
+    /// decoder.skip_flush_padding().unwrap();
+    /// let decoded2 = decoder
+    ///     .decode_iid_symbols(batch2.len(), &model)
+    ///     .collect::<Result<Vec<_>, _>>()
+    ///     .unwrap();
+    /// assert_eq!(decoded1, batch1);
+    /// assert_eq!(decoded2, batch2);
+    /// ```
+    pub fn flush_to_boundary(&mut self) -> Result<(), Backend::WriteError> {
+        self.seal()?;
+        self.state = RangeCoderState::default();
+        self.situation = EncoderSituation::Flushed;
+        Ok(())
+    }
+
+    /// Returns the current [`EncoderSituation`].
+    ///
+    /// In particular, this returns [`EncoderSituation::Flushed`] if the encoder is at a
+    /// boundary created by [`flush_to_boundary`](Self::flush_to_boundary).
+    pub fn situation(&self) -> EncoderSituation<Word> {
+        self.situation
+    }
+
+    /// Returns the number of `Word`s that [`into_compressed`](Self::into_compressed) would
+    /// currently write to the backend (in addition to what's already on the backend).
+    ///
+    /// This is useful for backends with a fixed capacity, such as a [`RingBuffer`]: if the
+    /// backend has at least this much space left, then sealing cannot fail because the
+    /// backend is full.
+    ///
+    /// [`RingBuffer`]: crate::backends::RingBuffer
+    pub fn num_seal_words(&self) -> usize {
         if self.state.range.get() == State::max_value() {
             return 0;
         }
@@ -377,6 +500,24 @@ where
         count
     }
 
+    /// Returns an upper bound on the number of `Word`s that encoding the next symbol will
+    /// write to the backend.
+    ///
+    /// This is useful for backends with a fixed capacity, such as a [`RingBuffer`]: if the
+    /// backend has at least this much space left, then encoding the next symbol cannot
+    /// fail because the backend is full. The returned value is `1` except in the rare
+    /// situation where the encoder holds back some words because their values are not yet
+    /// determined. In this situation, the returned value can exceed the capacity of the
+    /// backend (see [`RingBuffer`] for a discussion).
+    ///
+    /// [`RingBuffer`]: crate::backends::RingBuffer#capacity
+    pub fn max_num_words_next_symbol(&self) -> usize {
+        match self.situation {
+            EncoderSituation::Normal | EncoderSituation::Flushed => 1,
+            EncoderSituation::Inverted(num_inverted, _) => num_inverted.get() + 1,
+        }
+    }
+
     /// Returns the number of compressed words on the ans.
     ///
     /// This includes a constant overhead of between one and two words unless the
@@ -463,6 +604,182 @@ where
     pub fn clear(&mut self) {
         self.bulk.clear();
         self.state = RangeCoderState::default();
+        self.situation = EncoderSituation::Normal;
+    }
+
+    /// Records the current state of the encoder so that you can undo subsequent encoding
+    /// operations with [`rollback`](Self::rollback).
+    ///
+    /// This is useful, e.g., for rate control: take a checkpoint, encode a candidate batch
+    /// of symbols, inspect [`num_bits`](Self::num_bits), and roll back if the batch turned
+    /// out to be too expensive. Creating a checkpoint is cheap since it doesn't copy any
+    /// compressed data, and you can keep several checkpoints around at the same time.
+    ///
+    /// The checkpoint also captures the [`EncoderSituation`], including any compressed
+    /// words that are currently held back in an [`Inverted`](EncoderSituation::Inverted)
+    /// situation, so rolling back is correct regardless of whether the encoded symbols
+    /// after the checkpoint resolved an inversion, caused a new one, or followed a call to
+    /// [`flush_to_boundary`](Self::flush_to_boundary).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use constriction::stream::{
+    ///     model::DefaultLeakyQuantizer, queue::DefaultRangeEncoder, Decode, Encode,
+    /// };
+    ///
+    /// let quantizer = DefaultLeakyQuantizer::new(-100..=100);
+    /// let model = quantizer.quantize(probability::distribution::Gaussian::new(0.0, 10.0));
+    ///
+    /// let mut encoder = DefaultRangeEncoder::new();
+    /// encoder.encode_iid_symbols(&[3, 1, 4], &model).unwrap();
+    /// let checkpoint = encoder.checkpoint();
+    ///
+    /// // Encode a candidate batch that turns out to be too expensive, and undo it.
+    /// encoder.encode_iid_symbols(&[-50, 80, 95, -99], &model).unwrap();
+    /// assert!(encoder.num_bits() > 64);
+    /// encoder.rollback(checkpoint).unwrap();
+    ///
+    /// // Encode a cheaper batch instead.
+    /// encoder.encode_iid_symbols(&[1, 5], &model).unwrap();
+    /// let mut decoder = encoder.into_decoder().unwrap();
+    /// let decoded = decoder
+    ///     .decode_iid_symbols(5, &model)
+    ///     .collect::<Result<Vec<_>, _>>()
+    ///     .unwrap();
+    /// assert_eq!(decoded, [3, 1, 4, 1, 5]);
+    /// ```
+    pub fn checkpoint(&self) -> RangeEncoderCheckpoint<Word, State> {
+        RangeEncoderCheckpoint {
+            num_words: self.bulk.len(),
+            state: self.state,
+            situation: self.situation,
+        }
+    }
+
+    /// Restores the state of the encoder at the time when `checkpoint` was created.
+    ///
+    /// This discards all symbols that have been encoded since the call to
+    /// [`checkpoint`](Self::checkpoint). The compressed data is truncated in place, i.e.,
+    /// no compressed data is copied. The same checkpoint can be used for several rollbacks.
+    ///
+    /// Returns `Err(())` and leaves the encoder unchanged if there is less compressed data
+    /// on the encoder than there was at the time of the checkpoint, which can happen if
+    /// the encoder was [`clear`](Self::clear)ed or if `checkpoint` was created on a
+    /// different encoder. Note that not all such misuses can be detected.
+    #[allow(clippy::result_unit_err)]
+    pub fn rollback(&mut self, checkpoint: RangeEncoderCheckpoint<Word, State>) -> Result<(), ()> {
+        if self.bulk.len() < checkpoint.num_words {
+            return Err(());
+        }
+        self.bulk.truncate(checkpoint.num_words);
+        self.state = checkpoint.state;
+        self.situation = checkpoint.situation;
+        Ok(())
+    }
+
+    /// Encodes symbols from an iterator as long as the compressed data fits into a fixed
+    /// budget of `max_num_bits` bits, then flushes the encoder.
+    ///
+    /// Encodes the symbols from `symbols_and_models` in order until encoding the next
+    /// symbol would make the compressed data exceed `max_num_bits` (or until the iterator
+    /// is exhausted). The symbol that would exceed the budget is not encoded, and no further
+    /// symbols are taken from the iterator. The encoder is then flushed with
+    /// [`flush_to_boundary`](Self::flush_to_boundary), so that the compressed data on the
+    /// backend can be decoded on its own and is at most `max_num_bits` long (rounded down
+    /// to a multiple of `Word::BITS`).
+    ///
+    /// The budget includes any compressed data that was already on the encoder before
+    /// calling this method, as reported by [`num_words`](Self::num_words) (i.e., including
+    /// the words that flushing the encoder would write). Thus, you can fill several
+    /// consecutive packets by calling this method repeatedly with increasing budgets.
+    ///
+    /// Returns the number of symbols that were encoded, i.e., the length of the prefix of
+    /// `symbols_and_models` that fit into the budget. Since the decoder can't know where the
+    /// cut happened, you'll typically have to transmit this number alongside the
+    /// compressed data.
+    ///
+    /// Internally, this method takes a [`checkpoint`](Self::checkpoint) before each symbol
+    /// and rolls back the symbol that exceeds the budget, so the result is exact: no symbol
+    /// that would still fit is cut off. Unlike encoding onto a backend with a fixed
+    /// capacity (see [`BoundedWriteWords`]), running out of space is therefore not an
+    /// error, and the compressed data is always left in a decodable state.
+    ///
+    /// # Errors
+    ///
+    /// - Returns `Err(CoderError::Backend(BoundedWriteError::OutOfSpace))` if the
+    ///   compressed data on the encoder already exceeds the budget before encoding any
+    ///   symbols. In this case, the encoder is left unchanged (in particular, it is not
+    ///   flushed).
+    /// - Returns `Err(CoderError::Frontend(DefaultEncoderFrontendError::ImpossibleSymbol))`
+    ///   if a symbol has zero probability under its entropy model. In this case, all
+    ///   preceding symbols remain encoded but the encoder is not flushed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use constriction::stream::{
+    ///     model::DefaultLeakyQuantizer, queue::DefaultRangeEncoder, Decode,
+    /// };
+    ///
+    /// let quantizer = DefaultLeakyQuantizer::new(-100..=100);
+    /// let model = quantizer.quantize(probability::distribution::Gaussian::new(0.0, 10.0));
+    /// let symbols = (0..100).map(|i| (i * 7) % 41 - 20).collect::<Vec<i32>>();
+    ///
+    /// // Fit as many symbols as possible into a packet of 128 bits.
+    /// let mut encoder = DefaultRangeEncoder::new();
+    /// let num_encoded = encoder
+    ///     .encode_symbols_within_budget(symbols.iter().map(|symbol| (symbol, model)), 128)
+    ///     .unwrap();
+    /// assert!(0 < num_encoded && num_encoded < symbols.len());
+    /// let compressed = encoder.into_compressed().unwrap();
+    /// assert!(compressed.len() * 32 <= 128);
+    ///
+    /// let mut decoder = constriction::stream::queue::DefaultRangeDecoder::from_compressed(
+    ///     compressed,
+    /// )
+    /// .unwrap();
+    /// let decoded = decoder
+    ///     .decode_iid_symbols(num_encoded, model)
+    ///     .collect::<Result<Vec<_>, _>>()
+    ///     .unwrap();
+    /// assert_eq!(decoded, symbols[..num_encoded]);
+    /// ```
+    ///
+    /// [`BoundedWriteWords`]: crate::backends::BoundedWriteWords
+    pub fn encode_symbols_within_budget<S, M, I, const PRECISION: usize>(
+        &mut self,
+        symbols_and_models: I,
+        max_num_bits: usize,
+    ) -> Result<usize, CoderError<DefaultEncoderFrontendError, BoundedWriteError>>
+    where
+        S: Borrow<M::Symbol>,
+        M: EncoderModel<PRECISION>,
+        M::Probability: Into<Word>,
+        Word: AsPrimitive<M::Probability>,
+        I: IntoIterator<Item = (S, M)>,
+    {
+        let max_num_words = max_num_bits / Word::BITS;
+        if self.num_words() > max_num_words {
+            // Flushing alone would already exceed the budget.
+            return Err(CoderError::Backend(BoundedWriteError::OutOfSpace));
+        }
+
+        let mut num_encoded = 0;
+        for (symbol, model) in symbols_and_models {
+            let checkpoint = self.checkpoint();
+            self.encode_symbol(symbol, model)
+                .map_err(|err| CoderError::Frontend(err.into_frontend_error()))?;
+            if self.num_words() > max_num_words {
+                self.rollback(checkpoint)
+                    .expect("We only encoded onto the backend since the checkpoint.");
+                break;
+            }
+            num_encoded += 1;
+        }
+
+        self.flush_to_boundary().unwrap_infallible();
+        Ok(num_encoded)
     }
 
     /// Assembles the current compressed data into a single slice.
@@ -507,6 +824,184 @@ where
     }
 }
 
+impl<Word, State, E> RangeEncoder<Word, State, ByteVec<Word, E>>
+where
+    Word: BitArray + Into<State> + AsPrimitive<u8>,
+    State: BitArray + AsPrimitive<Word>,
+    E: Endianness,
+{
+    /// Seals the encoder with byte granularity and returns the compressed data.
+    ///
+    /// This is an alternative to [`into_compressed`] for encoders backed by a [`ByteVec`].
+    /// Rather than appending one or two full `Word`s that identify the final range
+    /// regardless of what the compressed data may be concatenated with, this method
+    /// appends the shortest sequence of bytes that identifies the final range under the
+    /// assumption that the compressed data is followed by nothing but zero bytes, and it
+    /// then drops all trailing zero bytes. This is exactly how a [`RangeDecoder`] with a
+    /// [`ByteCursor`] backend interprets the end of the data, so decoding works as usual.
+    /// The returned `ByteVec` typically doesn't end at a `Word` boundary, so you must not
+    /// concatenate it with any other data. Use [`into_compressed`] if you need word-aligned
+    /// compressed data.
+    ///
+    /// The consistency check in [`RangeDecoder::maybe_exhausted`] assumes data that was
+    /// sealed by [`into_compressed`]. When decoding trimmed data, it may therefore return
+    /// `false` even after all encoded symbols have been decoded.
+    ///
+    /// The trimming is most effective with [`BigEndian`] byte order because this byte
+    /// order serializes the most significant bytes of each `Word` first, so the redundant
+    /// low-order bytes of the last `Word` end up at the end of the data.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use constriction::{
+    ///     backends::{BigEndian, ByteVec},
+    ///     stream::{
+    ///         model::DefaultLeakyQuantizer,
+    ///         queue::{DefaultRangeDecoder, DefaultRangeEncoder},
+    ///         Decode, Encode,
+    ///     },
+    ///     UnwrapInfallible,
+    /// };
+    ///
+    /// let quantizer = DefaultLeakyQuantizer::new(-100..=100);
+    /// let model = quantizer.quantize(probability::distribution::Gaussian::new(0.0, 10.0));
+    /// let symbols = [3, -8, 12, 0, 5];
+    ///
+    /// let mut encoder = DefaultRangeEncoder::with_backend(ByteVec::<u32, BigEndian>::new());
+    /// encoder.encode_iid_symbols(&symbols, &model).unwrap();
+    /// let untrimmed_len = encoder.clone().into_compressed().unwrap_infallible().num_bytes();
+    /// let compressed = encoder.into_trimmed_compressed();
+    /// assert!(compressed.num_bytes() < untrimmed_len);
+    ///
+    /// let mut decoder = DefaultRangeDecoder::from_compressed(compressed).unwrap_infallible();
+    /// let decoded = decoder.decode_iid_symbols(5, &model).collect::<Result<Vec<_>, _>>();
+    /// assert_eq!(decoded.unwrap(), symbols);
+    /// ```
+    ///
+    /// [`into_compressed`]: Self::into_compressed
+    /// [`ByteCursor`]: crate::backends::ByteCursor
+    /// [`BigEndian`]: crate::backends::BigEndian
+    pub fn into_trimmed_compressed(mut self) -> ByteVec<Word, E> {
+        self.seal_trimmed();
+        let mut bulk = self.bulk;
+        bulk.trim_trailing_zeros();
+        bulk
+    }
+
+    /// Private method; like `seal`, but picks the point with the most trailing zero bytes.
+    fn seal_trimmed(&mut self) {
+        if self.state.range.get() == State::max_value() {
+            // Nothing has been encoded yet (see `seal`).
+            return;
+        }
+
+        // Any `point` with `point (-) lower < range` identifies the range if the decoder
+        // pads the data with zeros. Such a `point` always exists for `num_bits ==
+        // Word::BITS` because `range >= 1 << (State::BITS - Word::BITS)`.
+        let mut num_bits = 0;
+        let point = loop {
+            let point = if num_bits == 0 {
+                State::zero()
+            } else {
+                let mask = (State::one() << (State::BITS - num_bits)) - State::one();
+                self.state.lower.wrapping_add(&mask) & !mask
+            };
+            if point.wrapping_sub(&self.state.lower) < self.state.range.get() {
+                break point;
+            }
+            num_bits += 8;
+            debug_assert!(num_bits <= Word::BITS);
+        };
+
+        if let EncoderSituation::Inverted(num_inverted, first_inverted_lower_word) = self.situation
+        {
+            let (first_word, consecutive_words) = if point < self.state.lower {
+                // Addition has wrapped.
+                (first_inverted_lower_word + Word::one(), Word::zero())
+            } else {
+                (first_inverted_lower_word, Word::max_value())
+            };
+
+            self.bulk.write(first_word).unwrap_infallible();
+            for _ in 1..num_inverted.get() {
+                self.bulk.write(consecutive_words).unwrap_infallible();
+            }
+        }
+
+        if num_bits != 0 {
+            let point_word = (point >> (State::BITS - Word::BITS)).as_();
+            self.bulk.write(point_word).unwrap_infallible();
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<Word, State, W, E> RangeEncoder<Word, State, AsyncWriteWords<W, E>>
+where
+    Word: BitArray + Into<State> + AsPrimitive<u8>,
+    State: BitArray + AsPrimitive<Word>,
+    W: futures_io::AsyncWrite + Unpin,
+    E: Endianness,
+{
+    /// Encodes a single symbol and writes out the backend's buffer if it is full.
+    ///
+    /// This is the asynchronous counterpart of [`Encode::encode_symbol`]. It yields while
+    /// the buffered compressed data is written to the wrapped writer, and it reports
+    /// errors of the wrapped writer as `CoderError::Backend`. See [`AsyncWriteWords`] for
+    /// an example.
+    pub async fn encode_symbol_async<D, const PRECISION: usize>(
+        &mut self,
+        symbol: impl Borrow<D::Symbol>,
+        model: D,
+    ) -> Result<(), DefaultEncoderError<std::io::Error>>
+    where
+        D: EncoderModel<PRECISION>,
+        D::Probability: Into<Word>,
+        Word: AsPrimitive<D::Probability>,
+    {
+        Encode::<PRECISION>::encode_symbol(self, symbol, model)
+            .map_err(|err| err.map_backend(|never| match never {}))?;
+        if self.bulk.num_buffered_bytes() >= self.bulk.buffer_size() {
+            self.bulk.write_out().await?;
+        }
+        Ok(())
+    }
+
+    /// Encodes a sequence of symbols, all with the same entropy model, and writes out the
+    /// backend's buffer whenever it is full.
+    ///
+    /// This is the asynchronous counterpart of [`Encode::encode_iid_symbols`].
+    pub async fn encode_iid_symbols_async<D, I, const PRECISION: usize>(
+        &mut self,
+        symbols: I,
+        model: D,
+    ) -> Result<(), DefaultEncoderError<std::io::Error>>
+    where
+        D: EncoderModel<PRECISION> + Copy,
+        D::Probability: Into<Word>,
+        Word: AsPrimitive<D::Probability>,
+        I: IntoIterator,
+        I::Item: Borrow<D::Symbol>,
+    {
+        for symbol in symbols {
+            self.encode_symbol_async(symbol, model).await?;
+        }
+        Ok(())
+    }
+
+    /// Seals the compressed data, writes it out, flushes the wrapped writer, and returns
+    /// the wrapped writer.
+    ///
+    /// This is the asynchronous counterpart of [`into_compressed`](Self::into_compressed).
+    /// It doesn't close the wrapped writer.
+    pub async fn into_compressed_async(mut self) -> std::io::Result<W> {
+        self.seal().unwrap_infallible();
+        self.bulk.flush().await?;
+        Ok(self.bulk.into_inner())
+    }
+}
+
 impl<Word, State, Backend, const PRECISION: usize> IntoDecoder<PRECISION>
     for RangeEncoder<Word, State, Backend>
 where
@@ -577,6 +1072,8 @@ where
 
                 self.situation = EncoderSituation::Normal;
             }
+        } else if self.situation == EncoderSituation::Flushed {
+            self.situation = EncoderSituation::Normal;
         }
 
         self.state.lower = new_lower;
@@ -636,6 +1133,13 @@ where
 
     /// Invariant: `point.wrapping_sub(&state.lower) < state.range`
     point: State,
+
+    /// Number of low-order bits of `point` that were filled with zeros because the
+    /// compressed data ended. Used by [`decode_symbol_from_prefix`] to determine which
+    /// symbols are fully determined by the available data.
+    ///
+    /// [`decode_symbol_from_prefix`]: RangeDecoder::decode_symbol_from_prefix
+    num_unknown_bits: usize,
 }
 
 /// Type alias for a [`RangeDecoder`] with sane parameters for typical use cases.
@@ -660,6 +1164,11 @@ pub type DefaultRangeDecoder<Backend = Cursor<u32, Vec<u32>>> = RangeDecoder<u32
 /// [`SmallNonContiguousLookupDecoderModel`]: super::model::SmallNonContiguousLookupDecoderModel
 pub type SmallRangeDecoder<Backend> = RangeDecoder<u16, u32, Backend>;
 
+/// Type alias for a [`RangeDecoder`] for use with high-precision entropy models
+///
+/// Decodes data that was encoded with a [`LargeRangeEncoder`].
+pub type LargeRangeDecoder<Backend = Cursor<u64, Vec<u64>>> = RangeDecoder<u64, u128, Backend>;
+
 impl<Word, State, Backend> RangeDecoder<Word, State, Backend>
 where
     Word: BitArray + Into<State>,
@@ -674,12 +1183,13 @@ where
         assert_eq!(State::BITS % Word::BITS, 0);
 
         let mut bulk = compressed.into_read_words();
-        let point = Self::read_point(&mut bulk)?;
+        let (point, num_unknown_bits) = Self::read_point(&mut bulk)?;
 
         Ok(RangeDecoder {
             bulk,
             state: RangeCoderState::default(),
             point,
+            num_unknown_bits,
         })
     }
 
@@ -688,12 +1198,13 @@ where
         assert_eq!(State::BITS % Word::BITS, 0);
 
         let mut bulk = backend;
-        let point = Self::read_point(&mut bulk)?;
+        let (point, num_unknown_bits) = Self::read_point(&mut bulk)?;
 
         Ok(RangeDecoder {
             bulk,
             state: RangeCoderState::default(),
             point,
+            num_unknown_bits,
         })
     }
 
@@ -705,19 +1216,23 @@ where
         assert_eq!(State::BITS % Word::BITS, 0);
 
         let mut bulk = compressed.as_read_words();
-        let point = Self::read_point(&mut bulk)?;
+        let (point, num_unknown_bits) = Self::read_point(&mut bulk)?;
 
         Ok(RangeDecoder {
             bulk,
             state: RangeCoderState::default(),
             point,
+            num_unknown_bits,
         })
     }
 
     /// Low-level constructor that assembles a `RangeDecoder` from its internal components.
     ///
     /// The arguments `bulk`, `state`, and `point` correspond to the three return values of
-    /// the method [`into_raw_parts`](Self::into_raw_parts).
+    /// the method [`into_raw_parts`](Self::into_raw_parts). The resulting decoder assumes
+    /// that all bits of `point` are known, i.e., that `point` wasn't padded with zeros
+    /// because the compressed data ended (see
+    /// [`from_raw_parts_with_unknown_bits`](Self::from_raw_parts_with_unknown_bits)).
     ///
     /// The construction fails if the argument `point` lies outside of the range represented
     /// by `state`. In this case, the method returns the (unmodified) argument `bulk` back
@@ -726,26 +1241,65 @@ where
         bulk: Backend,
         state: RangeCoderState<Word, State>,
         point: State,
+    ) -> Result<Self, Backend> {
+        Self::from_raw_parts_with_unknown_bits(bulk, state, point, 0)
+    }
+
+    /// Same as [`from_raw_parts`](Self::from_raw_parts), but for a decoder whose lowest
+    /// `num_unknown_bits` bits of `point` are unknown because the compressed data ended.
+    ///
+    /// Use this method together with
+    /// [`into_raw_parts_with_unknown_bits`](Self::into_raw_parts_with_unknown_bits) to
+    /// disassemble and reassemble a decoder that you use with
+    /// [`decode_symbol_from_prefix`](Self::decode_symbol_from_prefix). The construction
+    /// also fails if `num_unknown_bits` exceeds `State::BITS`.
+    pub fn from_raw_parts_with_unknown_bits(
+        bulk: Backend,
+        state: RangeCoderState<Word, State>,
+        point: State,
+        num_unknown_bits: usize,
     ) -> Result<Self, Backend> {
         assert!(State::BITS >= 2 * Word::BITS);
         assert_eq!(State::BITS % Word::BITS, 0);
         // The invariants for `state` are already enforced statically.
 
-        if point.wrapping_sub(&state.lower) >= state.range.get() {
+        if point.wrapping_sub(&state.lower) >= state.range.get() || num_unknown_bits > State::BITS {
             Err(bulk)
         } else {
-            Ok(Self { bulk, state, point })
+            Ok(Self {
+                bulk,
+                state,
+                point,
+                num_unknown_bits,
+            })
         }
     }
 
     /// Low-level method that disassembles the `RangeDecoder` into its internal components.
     ///
-    /// Can be used together with [`from_raw_parts`](Self::from_raw_parts).
+    /// Can be used together with [`from_raw_parts`](Self::from_raw_parts). This method
+    /// discards the number of unknown bits of `point`, which only matters if the
+    /// compressed data ended (see
+    /// [`into_raw_parts_with_unknown_bits`](Self::into_raw_parts_with_unknown_bits)).
     pub fn into_raw_parts(self) -> (Backend, RangeCoderState<Word, State>, State) {
         (self.bulk, self.state, self.point)
     }
 
-    fn read_point<B: ReadWords<Word, Queue>>(bulk: &mut B) -> Result<State, B::ReadError> {
+    /// Same as [`into_raw_parts`](Self::into_raw_parts), but additionally returns the
+    /// number of low-order bits of `point` that are unknown because the compressed data
+    /// ended.
+    ///
+    /// Can be used together with
+    /// [`from_raw_parts_with_unknown_bits`](Self::from_raw_parts_with_unknown_bits).
+    pub fn into_raw_parts_with_unknown_bits(
+        self,
+    ) -> (Backend, RangeCoderState<Word, State>, State, usize) {
+        (self.bulk, self.state, self.point, self.num_unknown_bits)
+    }
+
+    /// Returns the initial `point` and the number of its low-order bits that are unknown
+    /// because the compressed data ended.
+    fn read_point<B: ReadWords<Word, Queue>>(bulk: &mut B) -> Result<(State, usize), B::ReadError> {
         let mut num_read = 0;
         let mut point = State::zero();
         while let Some(word) = bulk.read()? {
@@ -765,7 +1319,243 @@ where
             // `PosBackend` consistent with its implementation for the encoder?
         }
 
-        Ok(point)
+        Ok((point, State::BITS - num_read * Word::BITS))
+    }
+
+    /// Updates `state` after decoding a symbol in the same way as `RangeEncoder::encode_symbol`
+    /// does when encoding it, and reads the next word of compressed data if necessary.
+    ///
+    /// Leaves the decoder unchanged if reading from the backend fails.
+    #[inline(always)]
+    fn update_state(
+        &mut self,
+        scale: State,
+        left_sided_cumulative: State,
+        probability: State,
+    ) -> Result<(), Backend::ReadError> {
+        let old_state = self.state;
+
+        // Update `state` in the same way as we do in `encode_symbol` (see comments there):
+        self.state.lower = self
+            .state
+            .lower
+            .wrapping_add(&(scale * left_sided_cumulative));
+        self.state.range = (scale * probability).into_nonzero().expect("TODO");
+
+        if self.state.range.get() < State::one() << (State::BITS - Word::BITS) {
+            // Read the next word before anything else so that we can leave the decoder
+            // unchanged if the backend fails (e.g., because it needs more input).
+            let word = match self.bulk.read() {
+                Ok(word) => word,
+                Err(err) => {
+                    self.state = old_state;
+                    return Err(err);
+                }
+            };
+
+            // Update `state` in the same way as we do in `encode_symbol`:
+            self.state.lower = self.state.lower << Word::BITS;
+            self.state.range = unsafe {
+                // SAFETY:
+                // - `range` is nonzero because it is a `State::NonZero`
+                // - Shifting `range` left by `Word::BITS` bits doesn't truncate
+                //   because we checked that `range < 1 << (State::BITS - Word::Bits)`.
+                (self.state.range.get() << Word::BITS).into_nonzero_unchecked()
+            };
+
+            // Then update `point`, which restores the invariant on `point`:
+            self.shift_into_point(word);
+
+            // TODO: register reads past end?
+        }
+
+        Ok(())
+    }
+
+    /// Shifts the next `word` of compressed data into `point`, where `None` indicates that
+    /// the compressed data has ended.
+    #[inline(always)]
+    fn shift_into_point(&mut self, word: Option<Word>) {
+        self.point = self.point << Word::BITS;
+        if let Some(word) = word {
+            self.point = self.point | word.into();
+        }
+
+        // Words can only be missing at the end of the compressed data, so the unknown bits
+        // are always the lowest bits of `point`.
+        if self.num_unknown_bits != 0 || word.is_none() {
+            self.num_unknown_bits = core::cmp::min(self.num_unknown_bits + Word::BITS, State::BITS);
+        }
+    }
+
+    /// Skips over the padding written by [`RangeEncoder::flush_to_boundary`].
+    ///
+    /// Call this method after decoding all symbols that were encoded before the encoder was
+    /// flushed, i.e., at the same position in the sequence of symbols where the encoder
+    /// called `flush_to_boundary`. The method consumes the one or two words of padding that
+    /// the encoder wrote and resets the internal coder state, so that you can continue
+    /// decoding the symbols that were encoded after the flush. Calling this method at a
+    /// position where nothing was decoded since the last flush boundary (or since the
+    /// decoder was created) is a no-op, which matches the encoder's behavior.
+    ///
+    /// Returns `Err(CoderError::Frontend(DecoderFrontendError::InvalidData))` if the
+    /// compressed data doesn't contain the expected padding at the current position. In
+    /// this case, the decoder is left unchanged. This typically indicates that the decoder
+    /// and the encoder called `skip_flush_padding` and `flush_to_boundary`, respectively, at
+    /// different positions. Since the check only inspects one or two words, it may not
+    /// detect all such mistakes.
+    ///
+    /// If reading the padding fails with a backend error (e.g., [`NeedMoreInput`] from a
+    /// [`PushReadWords`]) then you can call this method again once more data is available;
+    /// it then continues with any padding words that it hasn't consumed yet.
+    ///
+    /// See [`RangeEncoder::flush_to_boundary`] for an example.
+    ///
+    /// [`NeedMoreInput`]: crate::backends::NeedMoreInput
+    pub fn skip_flush_padding(
+        &mut self,
+    ) -> Result<(), CoderError<DecoderFrontendError, Backend::ReadError>> {
+        let num_padding_words = self.num_padding_words();
+        if num_padding_words == 0 {
+            // Nothing has been decoded since the last flush boundary, so the encoder didn't
+            // write any padding either (see `RangeEncoder::seal`).
+            return Ok(());
+        }
+        if !self.has_expected_padding(num_padding_words) {
+            return Err(CoderError::Frontend(DecoderFrontendError::InvalidData));
+        }
+
+        let word = self.bulk.read()?;
+        self.shift_into_point(word);
+        if num_padding_words == 2 {
+            // The remaining padding is a single zero word. Before reading it, switch to a
+            // state for which `RangeEncoder::seal` would write exactly this word, so that
+            // the decoder remains consistent if the read fails (e.g., with `NeedMoreInput`
+            // from a `PushReadWords`) and calling this method again resumes from here.
+            self.state = RangeCoderState {
+                lower: State::zero(),
+                range: (State::max_value() - State::one())
+                    .into_nonzero()
+                    .expect("max_value() > 1"),
+                phantom: PhantomData,
+            };
+            let word = self.bulk.read()?;
+            self.shift_into_point(word);
+        }
+        self.state = RangeCoderState::default();
+
+        Ok(())
+    }
+
+    /// Returns the number of words that `RangeEncoder::seal` writes in the current state.
+    fn num_padding_words(&self) -> usize {
+        if self.state.range.get() == State::max_value() {
+            return 0;
+        }
+
+        let point = self
+            .state
+            .lower
+            .wrapping_add(&((State::one() << (State::BITS - Word::BITS)) - State::one()));
+        let point_word: Word = (point >> (State::BITS - Word::BITS)).as_();
+        let upper_word: Word = (self.state.lower.wrapping_add(&self.state.range.get())
+            >> (State::BITS - Word::BITS))
+            .as_();
+        if upper_word == point_word {
+            2
+        } else {
+            1
+        }
+    }
+
+    /// Checks if the top `num_padding_words` words of `point` match the words that
+    /// `RangeEncoder::seal` writes in the current state.
+    ///
+    /// Any words that the encoder may have held back have already been shifted out of
+    /// `point`, so the padding is at the top of `point`.
+    fn has_expected_padding(&self, num_padding_words: usize) -> bool {
+        let point = self
+            .state
+            .lower
+            .wrapping_add(&((State::one() << (State::BITS - Word::BITS)) - State::one()));
+        let point_word: Word = (point >> (State::BITS - Word::BITS)).as_();
+
+        let padding = self.point >> (State::BITS - num_padding_words * Word::BITS);
+        let expected_padding = point_word.into() << ((num_padding_words - 1) * Word::BITS);
+        padding == expected_padding
+    }
+
+    /// Checks that decoding consumed the compressed data exactly.
+    ///
+    /// Call this method after decoding all symbols that you expect in the compressed data.
+    /// It returns `Ok(())` if the decoder has read all compressed data and if the data
+    /// ends in exactly the padding that [`RangeEncoder::into_compressed`] writes after the
+    /// last decoded symbol. Otherwise, it returns one of the following errors:
+    ///
+    /// - [`FinishError::UnreadWords`] if there is more compressed data than expected (the
+    ///   variant holds the number of superfluous words, which may have been read ahead by
+    ///   the decoder but haven't been used for decoding any symbols yet);
+    /// - [`FinishError::InvalidPadding`] if the compressed data has the expected length
+    ///   but the padding at its end doesn't match what the encoder would have written (or
+    ///   if the compressed data is too short to contain the padding).
+    ///
+    /// Either error indicates that you decoded a different number of symbols than what was
+    /// encoded, that the entropy models used for decoding differ from the ones used for
+    /// encoding, or that the compressed data was corrupted, truncated, or concatenated with
+    /// some trailing data. The converse doesn't hold: the padding carries only little
+    /// redundancy, so mismatched entropy models may go unnoticed, in particular for small
+    /// `Word` types. If the encoder was flushed with
+    /// [`RangeEncoder::flush_to_boundary`] after the last symbol, then call
+    /// [`skip_flush_padding`](Self::skip_flush_padding) before calling this method.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use constriction::stream::{
+    ///     model::DefaultLeakyQuantizer,
+    ///     queue::{DefaultRangeDecoder, DefaultRangeEncoder},
+    ///     Decode, Encode, FinishError,
+    /// };
+    ///
+    /// let quantizer = DefaultLeakyQuantizer::new(-100..=100);
+    /// let model = quantizer.quantize(probability::distribution::Gaussian::new(0.0, 10.0));
+    /// let symbols = (0..100).map(|i| (i * 7) % 41 - 20).collect::<Vec<i32>>();
+    ///
+    /// let mut encoder = DefaultRangeEncoder::new();
+    /// encoder.encode_iid_symbols(&symbols, model).unwrap();
+    /// let mut compressed = encoder.into_compressed().unwrap();
+    ///
+    /// // Decoding exactly the encoded symbols passes the check.
+    /// let mut decoder = DefaultRangeDecoder::from_compressed(&compressed).unwrap();
+    /// let decoded = decoder.decode_iid_symbols(100, model).collect::<Result<Vec<_>, _>>();
+    /// assert_eq!(decoded.unwrap(), symbols);
+    /// assert_eq!(decoder.finish(), Ok(()));
+    ///
+    /// // Trailing data is detected even though it doesn't affect the decoded symbols.
+    /// compressed.push(0);
+    /// let mut decoder = DefaultRangeDecoder::from_compressed(&compressed).unwrap();
+    /// let decoded = decoder.decode_iid_symbols(100, model).collect::<Result<Vec<_>, _>>();
+    /// assert_eq!(decoded.unwrap(), symbols);
+    /// assert_eq!(decoder.finish(), Err(FinishError::UnreadWords(1)));
+    /// ```
+    pub fn finish(&self) -> Result<(), FinishError>
+    where
+        Backend: BoundedReadWords<Word, Queue>,
+    {
+        let num_padding_words = self.num_padding_words();
+        let num_read_ahead = (State::BITS - self.num_unknown_bits) / Word::BITS;
+        if num_read_ahead < num_padding_words {
+            return Err(FinishError::InvalidPadding);
+        }
+
+        let num_unread = self.bulk.remaining() + (num_read_ahead - num_padding_words);
+        if num_unread != 0 {
+            Err(FinishError::UnreadWords(num_unread))
+        } else if num_padding_words != 0 && !self.has_expected_padding(num_padding_words) {
+            Err(FinishError::InvalidPadding)
+        } else {
+            Ok(())
+        }
     }
 
     /// Same as `Decoder::maybe_exhausted`, but can be called on a concrete type without
@@ -782,10 +1572,229 @@ where
             && (self.state.range.get() == State::max_value()
                 || self.point.wrapping_sub(&self.state.lower) < max_difference)
     }
-}
 
-impl<Word, State, Backend> Code for RangeDecoder<Word, State, Backend>
-where
+    /// Decodes a symbol from compressed data that may have been truncated, but only if the
+    /// symbol is guaranteed to be correct.
+    ///
+    /// If the compressed data was cut off (e.g., because it was truncated in transit), then
+    /// the decoder treats all words beyond the end of the available data as unknown. This
+    /// method checks whether the symbol at the current position would be decoded the same
+    /// regardless of the values of these unknown words. If so, it decodes the symbol and
+    /// returns `Ok(Some(symbol))`. Otherwise, it returns `Ok(None)` and leaves the decoder
+    /// unchanged. Once this method returned `Ok(None)`, you shouldn't decode any further
+    /// symbols since they would not be trustworthy either.
+    ///
+    /// Since the data written by [`RangeEncoder::into_compressed`] identifies the encoded
+    /// symbols regardless of what follows it, all symbols of a *complete* stream are
+    /// reported as guaranteed correct. For a truncated stream, typically all but the last
+    /// few symbols that were (partially) encoded into the available prefix are reported as
+    /// correct. This allows for progressive transmission of range-coded data, where you
+    /// decode as many symbols as possible from the data that has arrived so far.
+    ///
+    /// Note that this method has no way to detect that the stream ended *before* the
+    /// current position, i.e., if you decode more symbols than were encoded, then you may
+    /// obtain "correct" garbage as with [`decode_symbol`](Decode::decode_symbol).
+    ///
+    /// See also [`decode_symbols_from_prefix`](Self::decode_symbols_from_prefix).
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err(CoderError::Frontend(DecoderFrontendError::InvalidData))` if the
+    /// available data cannot have been produced by an encoder with the given entropy
+    /// models, and `Err(CoderError::Backend(_))` if reading from the backend fails. In both
+    /// cases, the decoder is left unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use constriction::stream::{
+    ///     model::DefaultLeakyQuantizer,
+    ///     queue::{DefaultRangeDecoder, DefaultRangeEncoder},
+    ///     Encode,
+    /// };
+    ///
+    /// let quantizer = DefaultLeakyQuantizer::new(-100..=100);
+    /// let model = quantizer.quantize(probability::distribution::Gaussian::new(0.0, 10.0));
+    /// let symbols = (0..100).map(|i| (i * 7) % 41 - 20).collect::<Vec<i32>>();
+    ///
+    /// let mut encoder = DefaultRangeEncoder::new();
+    /// encoder.encode_iid_symbols(&symbols, model).unwrap();
+    /// let mut compressed = encoder.into_compressed().unwrap();
+    ///
+    /// // Simulate that only the first half of the compressed data arrived.
+    /// compressed.truncate(compressed.len() / 2);
+    ///
+    /// let mut decoder = DefaultRangeDecoder::from_compressed(compressed).unwrap();
+    /// let mut decoded = Vec::new();
+    /// while let Some(symbol) = decoder.decode_symbol_from_prefix(model).unwrap() {
+    ///     decoded.push(symbol);
+    /// }
+    ///
+    /// // We recovered a prefix of the symbols, and all of it is correct.
+    /// assert!(decoded.len() > 30 && decoded.len() < 60);
+    /// assert_eq!(decoded, symbols[..decoded.len()]);
+    /// ```
+    pub fn decode_symbol_from_prefix<D, const PRECISION: usize>(
+        &mut self,
+        model: D,
+    ) -> Result<Option<D::Symbol>, CoderError<DecoderFrontendError, Backend::ReadError>>
+    where
+        D: DecoderModel<PRECISION>,
+        D::Probability: Into<Word>,
+        Word: AsPrimitive<D::Probability>,
+    {
+        let scale = self.state.range.get() >> PRECISION;
+        let limit = scale << PRECISION;
+        let offset = self.point.wrapping_sub(&self.state.lower);
+
+        // The unknown bits of `point` are set to zero, so the point that we'd obtain from
+        // the full compressed data lies within `point..=point + slack`. Of these candidates,
+        // only the ones with an offset (relative to `lower`) below `limit` are valid.
+        let (min_offset, max_offset) = if self.num_unknown_bits == 0 {
+            (offset, offset)
+        } else {
+            let slack = if self.num_unknown_bits == State::BITS {
+                State::max_value()
+            } else {
+                (State::one() << self.num_unknown_bits) - State::one()
+            };
+            match offset.checked_add(&slack) {
+                Some(max_offset) => (offset, core::cmp::min(max_offset, limit - State::one())),
+                None => {
+                    // The candidates wrap around. This can only happen if a lot of bits are
+                    // unknown or if the point is very close to `lower`, so we conservatively
+                    // treat the symbol as undetermined.
+                    return Ok(None);
+                }
+            }
+        };
+
+        if min_offset >= limit {
+            return Err(CoderError::Frontend(DecoderFrontendError::InvalidData));
+        }
+        let min_quantile = min_offset / scale;
+        let max_quantile = max_offset / scale;
+
+        let (symbol, left_sided_cumulative, probability) =
+            model.quantile_function(min_quantile.as_().as_());
+        let left_sided_cumulative = left_sided_cumulative.into().into();
+        let probability = probability.get().into().into();
+        if max_quantile - left_sided_cumulative >= probability {
+            // Some valid candidates for `point` would decode to a different symbol.
+            return Ok(None);
+        }
+
+        self.update_state(scale, left_sided_cumulative, probability)
+            .map_err(CoderError::Backend)?;
+        Ok(Some(symbol))
+    }
+
+    /// Decodes symbols from compressed data that may have been truncated, stopping at the
+    /// first symbol that isn't guaranteed to be correct.
+    ///
+    /// Returns an iterator that calls [`decode_symbol_from_prefix`] with the entropy models
+    /// from `models`. The iterator ends either when `models` is exhausted or at the first
+    /// symbol that is not fully determined by the available compressed data. Thus, every
+    /// symbol yielded by the iterator is guaranteed to be correct, and you can tell how many
+    /// symbols could be recovered by counting them. The iterator yields an `Err` if
+    /// decoding a symbol fails (see [`decode_symbol_from_prefix`]).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use constriction::stream::{
+    ///     model::DefaultLeakyQuantizer,
+    ///     queue::{DefaultRangeDecoder, DefaultRangeEncoder},
+    ///     Encode,
+    /// };
+    /// use probability::distribution::Gaussian;
+    ///
+    /// let quantizer = DefaultLeakyQuantizer::new(-100..=100);
+    /// let models = (0..100).map(|i| quantizer.quantize(Gaussian::new(i as f64 - 50.0, 10.0)));
+    /// let symbols = (0..100).map(|i| i - 53).collect::<Vec<i32>>();
+    ///
+    /// let mut encoder = DefaultRangeEncoder::new();
+    /// encoder.encode_symbols(symbols.iter().zip(models.clone())).unwrap();
+    /// let compressed = encoder.into_compressed().unwrap();
+    ///
+    /// // Decode progressively as more and more compressed data arrives.
+    /// let mut num_decoded = 0;
+    /// for available in 0..=compressed.len() {
+    ///     let mut decoder = DefaultRangeDecoder::from_compressed(&compressed[..available]).unwrap();
+    ///     let decoded = decoder
+    ///         .decode_symbols_from_prefix(models.clone())
+    ///         .collect::<Result<Vec<_>, _>>()
+    ///         .unwrap();
+    ///     assert!(decoded.len() >= num_decoded);
+    ///     assert_eq!(decoded, symbols[..decoded.len()]);
+    ///     num_decoded = decoded.len();
+    /// }
+    /// assert_eq!(num_decoded, symbols.len());
+    /// ```
+    ///
+    /// [`decode_symbol_from_prefix`]: Self::decode_symbol_from_prefix
+    pub fn decode_symbols_from_prefix<'s, D, I, const PRECISION: usize>(
+        &'s mut self,
+        models: I,
+    ) -> impl Iterator<Item = Result<D::Symbol, CoderError<DecoderFrontendError, Backend::ReadError>>> + 's
+    where
+        D: DecoderModel<PRECISION> + 's,
+        D::Probability: Into<Word>,
+        Word: AsPrimitive<D::Probability>,
+        I: IntoIterator<Item = D>,
+        I::IntoIter: 's,
+    {
+        let mut models = models.into_iter();
+        core::iter::from_fn(move || self.decode_symbol_from_prefix(models.next()?).transpose())
+    }
+}
+
+impl<Word, State> RangeDecoder<Word, State, PushReadWords<Word>>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+{
+    /// Creates a resumable decoder for compressed data that arrives incrementally.
+    ///
+    /// Initializing a `RangeDecoder` requires `State::BITS / Word::BITS` words of compressed
+    /// data (or less if this is all there is). If `backend` doesn't contain enough words yet
+    /// and hasn't been [closed](PushReadWords::close) then this method returns the
+    /// (unmodified) `backend` back to the caller, wrapped in an `Err` variant. In this case,
+    /// push more words to `backend` and try again.
+    ///
+    /// Once the decoder is constructed, decoding a symbol returns
+    /// `Err(CoderError::Backend(NeedMoreInput))` whenever it runs out of data, and it
+    /// leaves the decoder unchanged in this case. Call [`push_words`](Self::push_words)
+    /// (or [`close_input`](Self::close_input)) and then retry.
+    ///
+    /// See [`PushReadWords`] for an example.
+    pub fn from_push_backend(backend: PushReadWords<Word>) -> Result<Self, PushReadWords<Word>> {
+        if backend.len() < State::BITS / Word::BITS && !backend.is_closed() {
+            return Err(backend);
+        }
+        Ok(Self::with_backend(backend).expect("we checked that enough data is available"))
+    }
+
+    /// Appends the `Word`s from `words` to the end of the compressed data.
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`close_input`](Self::close_input) has been called before.
+    pub fn push_words(&mut self, words: impl IntoIterator<Item = Word>) {
+        self.bulk.extend(words);
+    }
+
+    /// Indicates that no more compressed data will arrive.
+    ///
+    /// After calling this method, running out of data is no longer an error but is treated
+    /// as the end of the compressed data, just like for any other backend.
+    pub fn close_input(&mut self) {
+        self.bulk.close();
+    }
+}
+
+impl<Word, State, Backend> Code for RangeDecoder<Word, State, Backend>
+where
     Word: BitArray + Into<State>,
     State: BitArray + AsPrimitive<Word>,
     Backend: ReadWords<Word, Queue>,
@@ -819,7 +1828,9 @@ where
         let (pos, state) = pos_and_state;
 
         self.bulk.seek(pos)?;
-        self.point = Self::read_point(&mut self.bulk).map_err(|_| ())?;
+        let (point, num_unknown_bits) = Self::read_point(&mut self.bulk).map_err(|_| ())?;
+        self.point = point;
+        self.num_unknown_bits = num_unknown_bits;
         self.state = state;
 
         // TODO: deal with positions very close to end.
@@ -882,6 +1893,13 @@ where
     /// recover any previously encoded data and will generally have low entropy.
     /// Still, being able to pop off an arbitrary number of symbols can sometimes be
     /// useful in edge cases of, e.g., the bits-back algorithm.
+    ///
+    /// If the backend returns an error, then this method returns `Err(CoderError::Backend(_))`
+    /// and leaves the decoder unchanged. Thus, if the backend error is recoverable (e.g., a
+    /// [`NeedMoreInput`] from a [`PushReadWords`]), you can retry decoding the same symbol
+    /// later.
+    ///
+    /// [`NeedMoreInput`]: crate::backends::NeedMoreInput
     fn decode_symbol<D>(
         &mut self,
         model: D,
@@ -904,41 +1922,18 @@ where
         let (symbol, left_sided_cumulative, probability) =
             model.quantile_function(quantile.as_().as_());
 
-        // Update `state` in the same way as we do in `encode_symbol` (see comments there):
-        self.state.lower = self
-            .state
-            .lower
-            .wrapping_add(&(scale * left_sided_cumulative.into().into()));
-        self.state.range = (scale * probability.get().into().into())
-            .into_nonzero()
-            .expect("TODO");
-
-        // Invariant (*) is still satisfied at this point because:
+        // Invariant (*) is still satisfied after updating `state` because:
         //   (point (-) lower) / scale = (point (-) old_lower) / scale (-) left_sided_cumulative
         //                             = quantile (-) left_sided_cumulative
         //                             < probability
         // Therefore, we have:
         //   point (-) lower < scale * probability <= range
-
-        if self.state.range.get() < State::one() << (State::BITS - Word::BITS) {
-            // First update `state` in the same way as we do in `encode_symbol`:
-            self.state.lower = self.state.lower << Word::BITS;
-            self.state.range = unsafe {
-                // SAFETY:
-                // - `range` is nonzero because it is a `State::NonZero`
-                // - Shifting `range` left by `Word::BITS` bits doesn't truncate
-                //   because we checked that `range < 1 << (State::BITS - Word::Bits)`.
-                (self.state.range.get() << Word::BITS).into_nonzero_unchecked()
-            };
-
-            // Then update `point`, which restores invariant (*):
-            self.point = self.point << Word::BITS;
-            if let Some(word) = self.bulk.read()? {
-                self.point = self.point | word.into();
-            }
-
-            // TODO: register reads past end?
-        }
+        self.update_state(
+            scale,
+            left_sided_cumulative.into().into(),
+            probability.get().into().into(),
+        )
+        .map_err(CoderError::Backend)?;
 
         Ok(symbol)
     }
@@ -948,6 +1943,71 @@ where
     }
 }
 
+#[cfg(feature = "async")]
+impl<Word, State, R, E> RangeDecoder<Word, State, AsyncReadWords<Word, R, E>>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+    u8: AsPrimitive<Word>,
+    R: futures_io::AsyncRead + Unpin,
+    E: Endianness,
+{
+    /// Creates a decoder that reads compressed data from an asynchronous reader.
+    ///
+    /// This is the asynchronous counterpart of [`with_backend`](Self::with_backend). It
+    /// yields until the wrapped reader provides enough data to initialize the decoder.
+    /// See [`AsyncWriteWords`] for an example.
+    pub async fn with_async_backend(
+        mut backend: AsyncReadWords<Word, R, E>,
+    ) -> std::io::Result<Self> {
+        backend.fill(State::BITS / Word::BITS).await?;
+        Self::with_backend(backend)
+    }
+
+    /// Decodes a single symbol, yielding until the wrapped reader provides enough data.
+    ///
+    /// This is the asynchronous counterpart of [`Decode::decode_symbol`]. Decoding a
+    /// symbol reads at most one `Word`, so this method makes sure that at least one `Word`
+    /// is buffered (unless the wrapped reader reached its end) before it calls
+    /// `decode_symbol`. Thus, the decoder's state is never left inconsistent due to a
+    /// blocking reader.
+    pub async fn decode_symbol_async<D, const PRECISION: usize>(
+        &mut self,
+        model: D,
+    ) -> Result<D::Symbol, CoderError<DecoderFrontendError, std::io::Error>>
+    where
+        D: DecoderModel<PRECISION>,
+        D::Probability: Into<Word>,
+        Word: AsPrimitive<D::Probability>,
+    {
+        self.bulk.fill(1).await?;
+        Decode::<PRECISION>::decode_symbol(self, model)
+    }
+
+    /// Decodes `amt` symbols, all with the same entropy model, yielding whenever the
+    /// wrapped reader doesn't provide enough data.
+    ///
+    /// This is the asynchronous counterpart of [`Decode::decode_iid_symbols`]. Unlike the
+    /// latter, it doesn't return an iterator but collects the decoded symbols into a
+    /// `Vec`, short-circuiting on the first error.
+    pub async fn decode_iid_symbols_async<D, const PRECISION: usize>(
+        &mut self,
+        amt: usize,
+        model: D,
+    ) -> Result<Vec<D::Symbol>, CoderError<DecoderFrontendError, std::io::Error>>
+    where
+        D: DecoderModel<PRECISION> + Copy,
+        D::Probability: Into<Word>,
+        Word: AsPrimitive<D::Probability>,
+    {
+        let mut symbols = Vec::with_capacity(amt);
+        for _ in 0..amt {
+            symbols.push(self.decode_symbol_async(model).await?);
+        }
+        Ok(symbols)
+    }
+}
+
 /// Provides temporary read-only access to the compressed data wrapped in an
 /// [`RangeEncoder`].
 ///
@@ -1022,9 +2082,14 @@ mod tests {
     use std::dbg;
 
     use super::super::model::{
-        ContiguousCategoricalEntropyModel, IterableEntropyModel, LeakyQuantizer,
+        ContiguousCategoricalEntropyModel, IterableEntropyModel,
+        LargeContiguousCategoricalEntropyModel, LargeLeakyQuantizer, LeakyQuantizer,
     };
+    use super::super::tests::random_gaussian_symbols;
     use super::*;
+    #[cfg(feature = "async")]
+    use crate::backends::BigEndian;
+    use crate::backends::NeedMoreInput;
 
     use probability::distribution::{Gaussian, Inverse};
     use rand_xoshiro::{
@@ -1242,6 +2307,71 @@ mod tests {
         assert_eq!(symbols_gaussian, reconstructed_gaussian);
     }
 
+    #[test]
+    fn compress_many_u64_u128_48() {
+        #[cfg(not(miri))]
+        const AMT: usize = 1000;
+
+        #[cfg(miri)]
+        const AMT: usize = 100;
+
+        let mut rng = Xoshiro256StarStar::seed_from_u64(48);
+
+        // A very skewed distribution, which can't be represented faithfully with a
+        // precision of only 32 bits.
+        let categorical =
+            LargeContiguousCategoricalEntropyModel::from_floating_point_probabilities(&[
+                1e-13, 0.3, 2e-11, 0.5, 1e-15, 0.2,
+            ])
+            .unwrap();
+        let symbols_categorical = (0..AMT)
+            .map(|_| categorical.quantile_function(rng.next_u64() >> 16).0)
+            .collect::<Vec<_>>();
+
+        let means = (0..AMT)
+            .map(|_| (200.0 / u32::MAX as f64) * rng.next_u32() as f64 - 100.0)
+            .collect::<Vec<_>>();
+        let symbols_gaussian = means
+            .iter()
+            .map(|&mean| (mean + (rng.next_u32() % 5) as f64 - 2.0).round() as i32)
+            .collect::<Vec<_>>();
+        let quantizer = LargeLeakyQuantizer::new(-127..=127);
+
+        let mut encoder = LargeRangeEncoder::new();
+        encoder
+            .encode_iid_symbols(&symbols_categorical, &categorical)
+            .unwrap();
+        encoder
+            .encode_symbols(
+                symbols_gaussian
+                    .iter()
+                    .zip(&means)
+                    .map(|(&symbol, &mean)| (symbol, quantizer.quantize(Gaussian::new(mean, 1.5)))),
+            )
+            .unwrap();
+
+        let compressed = encoder.into_compressed().unwrap();
+        let mut decoder = LargeRangeDecoder::from_compressed(compressed).unwrap();
+
+        let reconstructed_categorical = decoder
+            .decode_iid_symbols(AMT, &categorical)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let reconstructed_gaussian = decoder
+            .decode_symbols(
+                means
+                    .iter()
+                    .map(|&mean| quantizer.quantize(Gaussian::new(mean, 1.5))),
+            )
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert!(decoder.maybe_exhausted());
+
+        assert_eq!(symbols_categorical, reconstructed_categorical);
+        assert_eq!(symbols_gaussian, reconstructed_gaussian);
+    }
+
     #[test]
     fn seek() {
         #[cfg(not(miri))]
@@ -1307,6 +2437,786 @@ mod tests {
         decoder.seek(final_pos_and_state).unwrap();
         assert!(decoder.maybe_exhausted());
     }
+
+    #[test]
+    #[cfg(feature = "async")]
+    fn async_duplex() {
+        use alloc::{collections::VecDeque, rc::Rc};
+        use core::{
+            cell::RefCell,
+            pin::Pin,
+            task::{Context, Poll, Waker},
+        };
+        use futures::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
+
+        /// In-memory duplex stream with a tiny capacity, so that the encoder blocks unless
+        /// the decoder consumes the compressed data concurrently.
+        #[derive(Default)]
+        struct Pipe {
+            bytes: VecDeque<u8>,
+            closed: bool,
+            reader: Option<Waker>,
+            writer: Option<Waker>,
+        }
+        const CAPACITY: usize = 16;
+
+        struct PipeWriter(Rc<RefCell<Pipe>>);
+        struct PipeReader(Rc<RefCell<Pipe>>);
+
+        impl AsyncWrite for PipeWriter {
+            fn poll_write(
+                self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+                buf: &[u8],
+            ) -> Poll<std::io::Result<usize>> {
+                let mut pipe = self.0.borrow_mut();
+                let amt = buf.len().min(CAPACITY - pipe.bytes.len());
+                if amt == 0 {
+                    pipe.writer = Some(cx.waker().clone());
+                    return Poll::Pending;
+                }
+                pipe.bytes.extend(&buf[..amt]);
+                if let Some(waker) = pipe.reader.take() {
+                    waker.wake();
+                }
+                Poll::Ready(Ok(amt))
+            }
+
+            fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+                Poll::Ready(Ok(()))
+            }
+
+            fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+                let mut pipe = self.0.borrow_mut();
+                pipe.closed = true;
+                if let Some(waker) = pipe.reader.take() {
+                    waker.wake();
+                }
+                Poll::Ready(Ok(()))
+            }
+        }
+
+        impl AsyncRead for PipeReader {
+            fn poll_read(
+                self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+                buf: &mut [u8],
+            ) -> Poll<std::io::Result<usize>> {
+                let mut pipe = self.0.borrow_mut();
+                if pipe.bytes.is_empty() && !pipe.closed {
+                    pipe.reader = Some(cx.waker().clone());
+                    return Poll::Pending;
+                }
+                let amt = buf.len().min(pipe.bytes.len());
+                for (dst, src) in buf.iter_mut().zip(pipe.bytes.drain(..amt)) {
+                    *dst = src;
+                }
+                if let Some(waker) = pipe.writer.take() {
+                    waker.wake();
+                }
+                Poll::Ready(Ok(amt))
+            }
+        }
+
+        let quantizer = LeakyQuantizer::<_, _, u32, 24>::new(-100..=100);
+        let model = quantizer.quantize(Gaussian::new(0.0, 10.0));
+        let mut rng = Xoshiro256StarStar::seed_from_u64(123);
+        let symbols = (0..1000)
+            .map(|_| model.quantile_function(rng.next_u32() % (1 << 24)).0)
+            .collect::<Vec<_>>();
+
+        for amt in [0, 1, 2, 10, 1000] {
+            let pipe = Rc::new(RefCell::new(Pipe::default()));
+            let backend =
+                AsyncWriteWords::<_, BigEndian>::with_buffer_size(PipeWriter(Rc::clone(&pipe)), 8);
+            let mut encoder = DefaultRangeEncoder::with_backend(backend);
+            let encode = async {
+                encoder
+                    .encode_iid_symbols_async(&symbols[..amt], &model)
+                    .await
+                    .unwrap();
+                let mut writer = encoder.into_compressed_async().await.unwrap();
+                writer.close().await.unwrap();
+            };
+
+            let backend = AsyncReadWords::<u32, _, BigEndian>::new(PipeReader(Rc::clone(&pipe)));
+            let decode = async {
+                let mut decoder = DefaultRangeDecoder::with_async_backend(backend)
+                    .await
+                    .unwrap();
+                let decoded = decoder.decode_iid_symbols_async(amt, &model).await.unwrap();
+                assert_eq!(decoded, &symbols[..amt]);
+                assert!(decoder.maybe_exhausted());
+            };
+
+            futures::executor::block_on(futures::future::join(encode, decode));
+        }
+
+        let mut reference = DefaultRangeEncoder::new();
+        reference.encode_iid_symbols(&symbols, &model).unwrap();
+        let reference = reference.into_compressed().unwrap();
+        assert!(reference.len() * 4 > 10 * CAPACITY);
+    }
+
+    #[test]
+    fn flush_to_boundary_u32_u64_24() {
+        generic_flush_to_boundary::<u32, u64, u32, 24>();
+    }
+
+    #[test]
+    fn flush_to_boundary_u16_u64_12() {
+        generic_flush_to_boundary::<u16, u64, u16, 12>();
+    }
+
+    #[test]
+    fn flush_to_boundary_u16_u32_12() {
+        generic_flush_to_boundary::<u16, u32, u16, 12>();
+    }
+
+    #[test]
+    fn flush_to_boundary_u8_u16_8() {
+        generic_flush_to_boundary::<u8, u16, u8, 8>();
+    }
+
+    #[test]
+    fn resumable_decoder_u32_u64() {
+        generic_resumable_decoder::<u32, u64, u32, 24>();
+    }
+
+    #[test]
+    fn resumable_decoder_u16_u32() {
+        generic_resumable_decoder::<u16, u32, u16, 12>();
+    }
+
+    fn generic_resumable_decoder<Word, State, Probability, const PRECISION: usize>()
+    where
+        State: BitArray + AsPrimitive<Word>,
+        Word: BitArray + Into<State> + AsPrimitive<Probability>,
+        Probability: BitArray + Into<Word> + AsPrimitive<usize>,
+        usize: AsPrimitive<Probability>,
+        f64: AsPrimitive<Probability>,
+        i32: AsPrimitive<Probability>,
+    {
+        #[cfg(not(miri))]
+        const AMT: usize = 1000;
+
+        #[cfg(miri)]
+        const AMT: usize = 100;
+
+        let quantizer = LeakyQuantizer::<_, _, Probability, PRECISION>::new(-127..=127);
+        let model = quantizer.quantize(Gaussian::new(3.2, 25.0));
+        let mut rng = Xoshiro256StarStar::seed_from_u64(2121);
+        let symbols = (0..AMT)
+            .map(|_| (rng.next_u32() % 255) as i32 - 127)
+            .collect::<Vec<_>>();
+
+        let mut encoder = RangeEncoder::<Word, State>::new();
+        encoder.encode_iid_symbols(&symbols, &model).unwrap();
+        let compressed = encoder.into_compressed().unwrap();
+
+        // Push one word at a time, and only when the decoder asks for it.
+        let mut words = compressed.iter().copied();
+        let mut backend = PushReadWords::new();
+        let mut decoder = loop {
+            match RangeDecoder::<Word, State, _>::from_push_backend(backend) {
+                Ok(decoder) => break decoder,
+                Err(returned_backend) => {
+                    backend = returned_backend;
+                    match words.next() {
+                        Some(word) => backend.push(word),
+                        None => backend.close(),
+                    }
+                }
+            }
+        };
+
+        let mut decoded = Vec::with_capacity(AMT);
+        let mut num_retries = 0;
+        while decoded.len() != AMT {
+            let state = decoder.state();
+            match decoder.decode_symbol(model) {
+                Ok(symbol) => decoded.push(symbol),
+                Err(CoderError::Backend(NeedMoreInput)) => {
+                    assert_eq!(decoder.state(), state);
+                    num_retries += 1;
+                    match words.next() {
+                        Some(word) => decoder.push_words([word]),
+                        None => decoder.close_input(),
+                    }
+                }
+                Err(err) => panic!("{:?}", err),
+            }
+        }
+
+        assert_eq!(decoded, symbols);
+        assert!(num_retries > 0);
+        assert!(decoder.maybe_exhausted());
+    }
+
+    #[test]
+    fn resumable_skip_flush_padding_u32_u64() {
+        generic_resumable_skip_flush_padding::<u32, u64, u32, 24>();
+    }
+
+    #[test]
+    fn resumable_skip_flush_padding_u16_u32() {
+        generic_resumable_skip_flush_padding::<u16, u32, u16, 12>();
+    }
+
+    fn generic_resumable_skip_flush_padding<Word, State, Probability, const PRECISION: usize>()
+    where
+        State: BitArray + AsPrimitive<Word>,
+        Word: BitArray + Into<State> + AsPrimitive<Probability>,
+        Probability: BitArray + Into<Word> + AsPrimitive<usize>,
+        usize: AsPrimitive<Probability>,
+        f64: AsPrimitive<Probability>,
+        i32: AsPrimitive<Probability>,
+    {
+        #[cfg(not(miri))]
+        const NUM_BATCHES: usize = 500;
+
+        #[cfg(miri)]
+        const NUM_BATCHES: usize = 20;
+
+        let quantizer = LeakyQuantizer::<_, _, Probability, PRECISION>::new(-127..=127);
+        let model = quantizer.quantize(Gaussian::new(3.2, 25.0));
+        let mut rng = Xoshiro256StarStar::seed_from_u64(2323);
+        let batches = (0..NUM_BATCHES)
+            .map(|_| {
+                let len = rng.next_u32() as usize % 5 + 1;
+                (0..len)
+                    .map(|_| (rng.next_u32() % 255) as i32 - 127)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let mut encoder = RangeEncoder::<Word, State>::new();
+        for batch in &batches {
+            encoder.encode_iid_symbols(batch, &model).unwrap();
+            encoder.flush_to_boundary().unwrap();
+        }
+        let compressed = encoder.into_compressed().unwrap();
+
+        // Push one word at a time, and only when the decoder asks for it, so that the
+        // input gets split at every position, including between two words of padding.
+        let mut words = compressed.iter().copied();
+        let mut backend = PushReadWords::new();
+        let mut decoder = loop {
+            match RangeDecoder::<Word, State, _>::from_push_backend(backend) {
+                Ok(decoder) => break decoder,
+                Err(returned_backend) => {
+                    backend = returned_backend;
+                    match words.next() {
+                        Some(word) => backend.push(word),
+                        None => backend.close(),
+                    }
+                }
+            }
+        };
+        let mut push_next_word = |decoder: &mut RangeDecoder<Word, State, _>| match words.next() {
+            Some(word) => decoder.push_words([word]),
+            None => decoder.close_input(),
+        };
+
+        let mut num_split_paddings = 0;
+        for batch in &batches {
+            let mut num_decoded = 0;
+            while num_decoded != batch.len() {
+                match decoder.decode_symbol(model) {
+                    Ok(symbol) => {
+                        assert_eq!(symbol, batch[num_decoded]);
+                        num_decoded += 1;
+                    }
+                    Err(CoderError::Backend(NeedMoreInput)) => push_next_word(&mut decoder),
+                    Err(err) => panic!("{:?}", err),
+                }
+            }
+
+            loop {
+                let num_padding_words = decoder.num_padding_words();
+                match decoder.skip_flush_padding() {
+                    Ok(()) => break,
+                    Err(CoderError::Backend(NeedMoreInput)) => {
+                        if num_padding_words == 2 && decoder.num_padding_words() == 1 {
+                            num_split_paddings += 1;
+                        }
+                        push_next_word(&mut decoder);
+                    }
+                    Err(err) => panic!("{:?}", err),
+                }
+            }
+        }
+
+        assert!(num_split_paddings > 0);
+        assert_eq!(decoder.finish(), Ok(()));
+    }
+
+    fn generic_flush_to_boundary<Word, State, Probability, const PRECISION: usize>()
+    where
+        State: BitArray + AsPrimitive<Word>,
+        Word: BitArray + Into<State> + AsPrimitive<Probability>,
+        Probability: BitArray + Into<Word> + AsPrimitive<usize>,
+        usize: AsPrimitive<Probability>,
+        f64: AsPrimitive<Probability>,
+        i32: AsPrimitive<Probability>,
+    {
+        #[cfg(not(miri))]
+        const NUM_BATCHES: usize = 50;
+
+        #[cfg(miri)]
+        const NUM_BATCHES: usize = 5;
+
+        let quantizer = LeakyQuantizer::<_, _, Probability, PRECISION>::new(-127..=127);
+        let mut rng = Xoshiro256StarStar::seed_from_u64(2020);
+        let batches = (0..NUM_BATCHES)
+            .map(|_| {
+                let len = rng.next_u32() as usize % 40;
+                random_gaussian_symbols(quantizer, 30.0, len, &mut rng)
+            })
+            .collect::<Vec<_>>();
+
+        let mut encoder = RangeEncoder::<Word, State>::new();
+        encoder.flush_to_boundary().unwrap();
+        assert!(encoder.is_empty());
+        assert_eq!(encoder.situation(), EncoderSituation::Flushed);
+
+        let mut boundaries = Vec::with_capacity(NUM_BATCHES);
+        for batch in &batches {
+            encoder.encode_symbols(batch.iter().cloned()).unwrap();
+            if !batch.is_empty() {
+                assert_ne!(encoder.situation(), EncoderSituation::Flushed);
+            }
+            encoder.flush_to_boundary().unwrap();
+            assert_eq!(encoder.situation(), EncoderSituation::Flushed);
+            assert_eq!(encoder.num_seal_words(), 0);
+
+            // Flushing again is a no-op.
+            let len = encoder.bulk().len();
+            encoder.flush_to_boundary().unwrap();
+            assert_eq!(encoder.bulk().len(), len);
+            boundaries.push(len);
+        }
+        let compressed = encoder.into_compressed().unwrap();
+        assert_eq!(compressed.len(), *boundaries.last().unwrap());
+
+        // Decoding any prefix that ends at a flush boundary recovers all batches up to the
+        // boundary. The last boundary is the end of the full compressed data.
+        for (num_batches, &boundary) in boundaries.iter().enumerate() {
+            let mut decoder =
+                RangeDecoder::<Word, State, _>::from_compressed(compressed[..boundary].to_vec())
+                    .unwrap();
+            decoder.skip_flush_padding().unwrap(); // No-op at the beginning.
+            for batch in &batches[..=num_batches] {
+                for (symbol, model) in batch {
+                    assert_eq!(decoder.decode_symbol(model).unwrap(), *symbol);
+                }
+                decoder.skip_flush_padding().unwrap();
+            }
+            assert!(decoder.maybe_exhausted());
+        }
+    }
+
+    #[test]
+    fn encode_within_budget_u32_u64_24() {
+        generic_encode_within_budget::<u32, u64, u32, 24>();
+    }
+
+    #[test]
+    fn encode_within_budget_u16_u32_12() {
+        generic_encode_within_budget::<u16, u32, u16, 12>();
+    }
+
+    #[test]
+    fn encode_within_budget_u8_u16_8() {
+        generic_encode_within_budget::<u8, u16, u8, 8>();
+    }
+
+    fn generic_encode_within_budget<Word, State, Probability, const PRECISION: usize>()
+    where
+        State: BitArray + AsPrimitive<Word>,
+        Word: BitArray + Into<State> + AsPrimitive<Probability>,
+        Probability: BitArray + Into<Word> + AsPrimitive<usize>,
+        usize: AsPrimitive<Probability>,
+        f64: AsPrimitive<Probability>,
+        i32: AsPrimitive<Probability>,
+    {
+        #[cfg(not(miri))]
+        let (num_symbols, max_budget) = (1000, 2048);
+
+        #[cfg(miri)]
+        let (num_symbols, max_budget) = (50, 128);
+
+        let quantizer = LeakyQuantizer::<_, _, Probability, PRECISION>::new(-127..=127);
+        let mut rng = Xoshiro256StarStar::seed_from_u64(2023);
+        let symbols = random_gaussian_symbols(quantizer, 30.0, num_symbols, &mut rng);
+
+        for max_num_bits in (0..max_budget).step_by(13) {
+            let mut encoder = RangeEncoder::<Word, State>::new();
+            let num_encoded = encoder
+                .encode_symbols_within_budget(symbols.iter().cloned(), max_num_bits)
+                .unwrap();
+            assert_eq!(encoder.situation(), EncoderSituation::Flushed);
+            let compressed = encoder.into_compressed().unwrap();
+            assert!(compressed.len() * Word::BITS <= max_num_bits);
+
+            // The cut is exact: encoding one more symbol would have exceeded the budget.
+            if num_encoded < symbols.len() {
+                let mut reference = RangeEncoder::<Word, State>::new();
+                reference
+                    .encode_symbols(symbols[..=num_encoded].iter().cloned())
+                    .unwrap();
+                assert!(reference.num_bits() > max_num_bits);
+            }
+
+            let mut decoder = RangeDecoder::<Word, State, _>::from_compressed(compressed).unwrap();
+            for (symbol, model) in &symbols[..num_encoded] {
+                assert_eq!(decoder.decode_symbol(model).unwrap(), *symbol);
+            }
+        }
+
+        // If the data on the encoder already exceeds the budget, nothing is encoded or flushed.
+        let mut encoder = RangeEncoder::<Word, State>::new();
+        encoder
+            .encode_symbols(symbols[..10].iter().cloned())
+            .unwrap();
+        let situation = encoder.situation();
+        let num_bits = encoder.num_bits();
+        let compressed = encoder.clone().into_compressed().unwrap();
+        assert_eq!(
+            encoder.encode_symbols_within_budget(symbols[10..].iter().cloned(), num_bits - 1),
+            Err(CoderError::Backend(BoundedWriteError::OutOfSpace))
+        );
+        assert_eq!(encoder.situation(), situation);
+        assert_eq!(encoder.num_bits(), num_bits);
+        assert_eq!(encoder.into_compressed().unwrap(), compressed);
+
+        // Filling several consecutive packets on the same encoder.
+        let mut encoder = RangeEncoder::<Word, State>::new();
+        let mut remaining = &symbols[..];
+        let mut packet_sizes = Vec::new();
+        for budget in (1..).map(|i| 128 * i) {
+            let num_encoded = encoder
+                .encode_symbols_within_budget(remaining.iter().cloned(), budget)
+                .unwrap();
+            assert!(encoder.num_bits() <= budget);
+            packet_sizes.push(num_encoded);
+            remaining = &remaining[num_encoded..];
+            if remaining.is_empty() {
+                break;
+            }
+        }
+
+        let mut decoder = encoder.into_decoder().unwrap();
+        let mut remaining = &symbols[..];
+        for num_encoded in packet_sizes {
+            for (symbol, model) in &remaining[..num_encoded] {
+                assert_eq!(decoder.decode_symbol(model).unwrap(), *symbol);
+            }
+            decoder.skip_flush_padding().unwrap();
+            remaining = &remaining[num_encoded..];
+        }
+        assert!(decoder.maybe_exhausted());
+    }
+
+    #[test]
+    fn decode_from_prefix_u32_u64_24() {
+        generic_decode_from_prefix::<u32, u64, u32, 24>();
+    }
+
+    #[test]
+    fn decode_from_prefix_u16_u32_12() {
+        generic_decode_from_prefix::<u16, u32, u16, 12>();
+    }
+
+    #[test]
+    fn decode_from_prefix_u8_u16_8() {
+        generic_decode_from_prefix::<u8, u16, u8, 8>();
+    }
+
+    fn generic_decode_from_prefix<Word, State, Probability, const PRECISION: usize>()
+    where
+        State: BitArray + AsPrimitive<Word>,
+        Word: BitArray + Into<State> + AsPrimitive<Probability>,
+        Probability: BitArray + Into<Word> + AsPrimitive<usize>,
+        usize: AsPrimitive<Probability>,
+        f64: AsPrimitive<Probability>,
+        i32: AsPrimitive<Probability>,
+    {
+        #[cfg(not(miri))]
+        const NUM_SYMBOLS: usize = 500;
+
+        #[cfg(miri)]
+        const NUM_SYMBOLS: usize = 20;
+
+        let quantizer = LeakyQuantizer::<_, _, Probability, PRECISION>::new(-127..=127);
+        let mut rng = Xoshiro256StarStar::seed_from_u64(2024);
+        let symbols = random_gaussian_symbols(quantizer, 30.0, NUM_SYMBOLS, &mut rng);
+
+        // Keep track of how many words the encoder has emitted (or held back) before
+        // encoding each symbol. The decoder reads `State::BITS / Word::BITS` words ahead.
+        let mut encoder = RangeEncoder::<Word, State>::new();
+        let mut num_words_before = Vec::with_capacity(NUM_SYMBOLS);
+        for (symbol, model) in &symbols {
+            let num_held_back = match encoder.situation() {
+                EncoderSituation::Inverted(num_inverted, _) => num_inverted.get(),
+                _ => 0,
+            };
+            num_words_before.push(encoder.bulk().len() + num_held_back);
+            encoder.encode_symbol(symbol, model).unwrap();
+        }
+        let compressed = encoder.into_compressed().unwrap();
+
+        let mut previous_num_decoded = 0;
+        for available in 0..=compressed.len() {
+            let mut decoder =
+                RangeDecoder::<Word, State, _>::from_compressed(&compressed[..available]).unwrap();
+            let decoded = decoder
+                .decode_symbols_from_prefix(symbols.iter().map(|(_, model)| model))
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            for ((symbol, _), decoded) in symbols.iter().zip(&decoded) {
+                assert_eq!(symbol, decoded);
+            }
+            assert!(decoded.len() >= previous_num_decoded);
+            previous_num_decoded = decoded.len();
+
+            // Symbols whose entire window of compressed data is available are always
+            // reported as correct.
+            let num_fully_available = num_words_before
+                .iter()
+                .take_while(|&&num_words| num_words + State::BITS / Word::BITS <= available)
+                .count();
+            assert!(decoded.len() >= num_fully_available);
+
+            // Disassembling and reassembling the decoder retains which bits are unknown.
+            let (bulk, state, point, num_unknown_bits) = decoder.into_raw_parts_with_unknown_bits();
+            let mut decoder = RangeDecoder::from_raw_parts_with_unknown_bits(
+                bulk,
+                state,
+                point,
+                num_unknown_bits,
+            )
+            .unwrap();
+
+            // Decoding stopped without modifying the decoder, so we can probe again.
+            if decoded.len() < symbols.len() {
+                let state = decoder.state();
+                let (_, model) = &symbols[decoded.len()];
+                assert!(decoder.decode_symbol_from_prefix(model).unwrap().is_none());
+                assert_eq!(decoder.state(), state);
+            }
+        }
+        assert_eq!(previous_num_decoded, symbols.len());
+    }
+
+    #[test]
+    fn finish_u32_u64_24() {
+        generic_finish::<u32, u64, u32, 24>();
+    }
+
+    #[test]
+    fn finish_u16_u32_12() {
+        generic_finish::<u16, u32, u16, 12>();
+    }
+
+    #[test]
+    fn finish_u8_u16_8() {
+        generic_finish::<u8, u16, u8, 8>();
+    }
+
+    fn generic_finish<Word, State, Probability, const PRECISION: usize>()
+    where
+        State: BitArray + AsPrimitive<Word>,
+        Word: BitArray + Into<State> + AsPrimitive<Probability>,
+        Probability: BitArray + Into<Word> + AsPrimitive<usize>,
+        usize: AsPrimitive<Probability>,
+        f64: AsPrimitive<Probability>,
+        i32: AsPrimitive<Probability>,
+    {
+        #[cfg(not(miri))]
+        const NUM_SYMBOLS: usize = 200;
+
+        #[cfg(miri)]
+        const NUM_SYMBOLS: usize = 20;
+
+        let quantizer = LeakyQuantizer::<_, _, Probability, PRECISION>::new(-127..=127);
+        let mut rng = Xoshiro256StarStar::seed_from_u64(2025);
+        let symbols = random_gaussian_symbols(quantizer, 30.0, NUM_SYMBOLS, &mut rng);
+
+        let decode = |compressed: &[Word], num_symbols: usize| {
+            let mut decoder = RangeDecoder::<Word, State, _>::from_compressed(compressed).unwrap();
+            for (symbol, model) in &symbols[..num_symbols] {
+                assert_eq!(decoder.decode_symbol(model).unwrap(), *symbol);
+            }
+            decoder.finish()
+        };
+
+        // An empty encoder doesn't write any padding.
+        let compressed = RangeEncoder::<Word, State>::new()
+            .into_compressed()
+            .unwrap();
+        assert!(compressed.is_empty());
+        assert_eq!(decode(&compressed, 0), Ok(()));
+
+        let mut encoder = RangeEncoder::<Word, State>::new();
+        encoder
+            .encode_symbols(symbols.iter().map(|(s, m)| (s, m)))
+            .unwrap();
+        let compressed = encoder.into_compressed().unwrap();
+        assert_eq!(decode(&compressed, NUM_SYMBOLS), Ok(()));
+
+        // Decoding too few symbols.
+        for num_symbols in 0..NUM_SYMBOLS {
+            assert!(decode(&compressed, num_symbols).is_err());
+        }
+
+        // Trailing data.
+        for num_trailing in 1..4 {
+            let mut extended = compressed.clone();
+            extended.resize(compressed.len() + num_trailing, Word::zero());
+            assert_eq!(
+                decode(&extended, NUM_SYMBOLS),
+                Err(FinishError::UnreadWords(num_trailing))
+            );
+        }
+
+        // Truncated padding (the last symbol may still decode correctly since missing
+        // words are treated as zeros).
+        let truncated = &compressed[..compressed.len() - 1];
+        let mut decoder = RangeDecoder::<Word, State, _>::from_compressed(truncated).unwrap();
+        for (_, model) in &symbols {
+            decoder.decode_symbol(model).unwrap();
+        }
+        assert_eq!(decoder.finish(), Err(FinishError::InvalidPadding));
+
+        // Mismatched entropy models. The padding at the end of the compressed data only
+        // carries a few bits of redundancy, so detection is only reliable for large words.
+        if Word::BITS >= 32 {
+            let mut decoder = RangeDecoder::<Word, State, _>::from_compressed(&compressed).unwrap();
+            let detected = symbols
+                .iter()
+                .cycle()
+                .skip(1)
+                .take(NUM_SYMBOLS)
+                .any(|(_, model)| decoder.decode_symbol(model).is_err());
+            assert!(detected || decoder.finish().is_err());
+        }
+
+        // Data that was flushed to a boundary.
+        let (first, second) = symbols.split_at(NUM_SYMBOLS / 2);
+        let mut encoder = RangeEncoder::<Word, State>::new();
+        encoder
+            .encode_symbols(first.iter().map(|(s, m)| (s, m)))
+            .unwrap();
+        encoder.flush_to_boundary().unwrap();
+        encoder
+            .encode_symbols(second.iter().map(|(s, m)| (s, m)))
+            .unwrap();
+        encoder.flush_to_boundary().unwrap();
+        let compressed = encoder.into_compressed().unwrap();
+
+        let mut decoder = RangeDecoder::<Word, State, _>::from_compressed(&compressed).unwrap();
+        for (symbol, model) in first {
+            assert_eq!(decoder.decode_symbol(model).unwrap(), *symbol);
+        }
+        assert!(matches!(decoder.finish(), Err(FinishError::UnreadWords(_))));
+        decoder.skip_flush_padding().unwrap();
+        for (symbol, model) in second {
+            assert_eq!(decoder.decode_symbol(model).unwrap(), *symbol);
+        }
+        decoder.skip_flush_padding().unwrap();
+        assert_eq!(decoder.finish(), Ok(()));
+    }
+
+    #[test]
+    fn checkpoint_and_rollback_u32_u64_24() {
+        generic_checkpoint_and_rollback::<u32, u64, u32, 24>(false);
+    }
+
+    #[test]
+    fn checkpoint_and_rollback_u16_u32_12() {
+        generic_checkpoint_and_rollback::<u16, u32, u16, 12>(false);
+    }
+
+    #[test]
+    fn checkpoint_and_rollback_u8_u16_8() {
+        generic_checkpoint_and_rollback::<u8, u16, u8, 8>(true);
+    }
+
+    fn generic_checkpoint_and_rollback<Word, State, Probability, const PRECISION: usize>(
+        expect_inversions: bool,
+    ) where
+        State: BitArray + AsPrimitive<Word>,
+        Word: BitArray + Into<State> + AsPrimitive<Probability>,
+        Probability: BitArray + Into<Word> + AsPrimitive<usize>,
+        usize: AsPrimitive<Probability>,
+        f64: AsPrimitive<Probability>,
+        i32: AsPrimitive<Probability>,
+    {
+        #[cfg(not(miri))]
+        const NUM_BATCHES: usize = 500;
+
+        #[cfg(miri)]
+        const NUM_BATCHES: usize = 10;
+
+        let quantizer = LeakyQuantizer::<_, _, Probability, PRECISION>::new(-127..=127);
+        let mut rng = Xoshiro256StarStar::seed_from_u64(2022);
+        let mut encoder = RangeEncoder::<Word, State>::new();
+        let mut reference = RangeEncoder::<Word, State>::new();
+        let mut accepted = Vec::new();
+        let mut num_inverted_rollbacks = 0;
+
+        for _ in 0..NUM_BATCHES {
+            let len = rng.next_u32() as usize % 10;
+            let batch = random_gaussian_symbols(quantizer, 30.0, len, &mut rng);
+            let flush = rng.next_u32() % 8 == 0;
+            let encode = |encoder: &mut RangeEncoder<Word, State>| {
+                encoder.encode_symbols(batch.iter().cloned()).unwrap();
+                if flush {
+                    encoder.flush_to_boundary().unwrap();
+                }
+            };
+
+            let checkpoint = encoder.checkpoint();
+            encode(&mut encoder);
+            if rng.next_u32() % 2 == 0 {
+                if matches!(checkpoint.situation, EncoderSituation::Inverted(..))
+                    || matches!(encoder.situation(), EncoderSituation::Inverted(..))
+                {
+                    num_inverted_rollbacks += 1;
+                }
+                encoder.rollback(checkpoint).unwrap();
+            } else {
+                encode(&mut reference);
+                accepted.push((batch, flush));
+            }
+            assert_eq!(encoder.bulk(), reference.bulk());
+            assert_eq!(encoder.checkpoint(), reference.checkpoint());
+        }
+
+        if expect_inversions {
+            assert!(num_inverted_rollbacks > 0);
+        }
+
+        // Rolling back to a checkpoint with more compressed data than present fails.
+        let checkpoint = encoder.checkpoint();
+        let mut empty = RangeEncoder::<Word, State>::new();
+        if !encoder.is_empty() {
+            assert!(empty.rollback(checkpoint).is_err());
+            assert!(empty.is_empty());
+        }
+
+        let mut decoder = encoder.into_decoder().unwrap();
+        for (batch, flush) in &accepted {
+            for (symbol, model) in batch {
+                assert_eq!(decoder.decode_symbol(model).unwrap(), *symbol);
+            }
+            if *flush {
+                decoder.skip_flush_padding().unwrap();
+            }
+        }
+        assert!(decoder.maybe_exhausted());
+    }
 }
 
 #[derive(Debug)]
@@ -12,6 +12,8 @@
 //! allows you to interleave encoding and decoding operations arbitrarily, which is in
 //! contrast to the situation in the sister module [`queue`] and important for advanced
 //! compression techniques such as bits-back coding in hierarchical probabilistic models.
+//! If you only need to encode but find it awkward to provide the symbols in reverse order,
+//! you can use a [`ForwardAnsEncoder`], which buffers symbols until you seal it.
 //!
 //! The parent module contains a more detailed discussion of the [differences between ANS
 //! Coding and Range Coding](super#which-stream-code-should-i-use) .
@@ -31,12 +33,13 @@ use num_traits::AsPrimitive;
 
 use super::{
     model::{DecoderModel, EncoderModel},
-    AsDecoder, Code, Decode, Encode, IntoDecoder, TryCodingError,
+    AsDecoder, Code, Decode, Encode, FinishError, IntoDecoder, TryCodingError,
 };
 use crate::{
     backends::{
-        self, AsReadWords, AsSeekReadWords, BoundedReadWords, Cursor, FallibleIteratorReadWords,
-        IntoReadWords, IntoSeekReadWords, ReadWords, Reverse, WriteWords,
+        self, AsReadWords, AsSeekReadWords, BoundedReadWords, BoundedWriteError, Cursor,
+        FallibleIteratorReadWords, IntoReadWords, IntoSeekReadWords, ReadWords, Reverse,
+        WriteWords,
     },
     bit_array_to_chunks_truncated, BitArray, CoderError, DefaultEncoderError,
     DefaultEncoderFrontendError, NonZeroBitArray, Pos, PosSeek, Seek, Stack, UnwrapInfallible,
@@ -150,6 +153,16 @@ pub type DefaultAnsCoder<Backend = Vec<u32>> = AnsCoder<u32, u64, Backend>;
 /// [`SmallContiguousLookupDecoderModel`]: super::model::SmallContiguousLookupDecoderModel
 pub type SmallAnsCoder<Backend = Vec<u16>> = AnsCoder<u16, u32, Backend>;
 
+/// Type alias for an [`AnsCoder`] for use with high-precision entropy models
+///
+/// This coder has a larger word size and internal state than [`DefaultAnsCoder`], which
+/// allows it to be used with entropy models whose `PRECISION` exceeds 32 bits, such as a
+/// [`LargeContiguousCategoricalEntropyModel`] or a [`LargeLeakyQuantizer`].
+///
+/// [`LargeContiguousCategoricalEntropyModel`]: super::model::LargeContiguousCategoricalEntropyModel
+/// [`LargeLeakyQuantizer`]: super::model::LargeLeakyQuantizer
+pub type LargeAnsCoder<Backend = Vec<u64>> = AnsCoder<u64, u128, Backend>;
+
 impl<Word, State, Backend> Debug for AnsCoder<Word, State, Backend>
 where
     Word: BitArray + Into<State>,
@@ -555,6 +568,72 @@ where
             - 1
     }
 
+    /// Checks that decoding consumed the compressed data exactly.
+    ///
+    /// Call this method after decoding all symbols that you expect to be on the coder. It
+    /// returns `Ok(())` if the backend has no unread words left and if the coder is back in
+    /// the state of a coder created with [`new`](Self::new) (see
+    /// [`is_empty`](Self::is_empty)). Otherwise, it returns one of the following errors:
+    ///
+    /// - [`FinishError::UnreadWords`] if the backend still contains compressed data (the
+    ///   variant holds the number of words that [`num_words`](Self::num_words) reports,
+    ///   i.e., including the words of the internal state); and
+    /// - [`FinishError::InvalidFinalState`] if the backend is exhausted but the internal
+    ///   state is not the initial state.
+    ///
+    /// Either error indicates that you decoded a different number of symbols than what was
+    /// encoded, that the entropy models used for decoding differ from the ones used for
+    /// encoding, or that the compressed data was corrupted.
+    ///
+    /// This check only works if encoding started on a coder created with `new` (or with
+    /// [`from_compressed`](Self::from_compressed) on empty data). An `AnsCoder` created
+    /// with [`from_binary`](Self::from_binary) on empty data starts in the state `1`
+    /// rather than `0`, so `finish` returns `Err(FinishError::InvalidFinalState)` even if
+    /// you decoded exactly the encoded symbols. In this case, check yourself that the
+    /// backend is exhausted and that [`state`](Self::state) returns `1`. (`finish` doesn't
+    /// accept the state `1` because decoding with a wrong entropy model tends to end up in
+    /// this state.)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use constriction::stream::{
+    ///     model::DefaultLeakyQuantizer, stack::DefaultAnsCoder, Decode, FinishError,
+    /// };
+    ///
+    /// let quantizer = DefaultLeakyQuantizer::new(-100..=100);
+    /// let model = quantizer.quantize(probability::distribution::Gaussian::new(0.0, 10.0));
+    /// let symbols = (0..100).map(|i| (i * 7) % 41 - 20).collect::<Vec<i32>>();
+    ///
+    /// let mut ans = DefaultAnsCoder::new();
+    /// ans.encode_iid_symbols_reverse(&symbols, model).unwrap();
+    /// let compressed = ans.into_compressed().unwrap();
+    ///
+    /// // Decoding too few symbols leaves some compressed data on the coder.
+    /// let mut decoder = DefaultAnsCoder::from_compressed(compressed.clone()).unwrap();
+    /// let decoded = decoder.decode_iid_symbols(50, model).collect::<Result<Vec<_>, _>>();
+    /// assert_eq!(decoded.unwrap(), symbols[..50]);
+    /// assert!(matches!(decoder.finish(), Err(FinishError::UnreadWords(_))));
+    ///
+    /// // Decoding exactly the encoded symbols passes the check.
+    /// let mut decoder = DefaultAnsCoder::from_compressed(compressed).unwrap();
+    /// let decoded = decoder.decode_iid_symbols(100, model).collect::<Result<Vec<_>, _>>();
+    /// assert_eq!(decoded.unwrap(), symbols);
+    /// assert_eq!(decoder.finish(), Ok(()));
+    /// ```
+    pub fn finish(&self) -> Result<(), FinishError>
+    where
+        Backend: BoundedReadWords<Word, Stack>,
+    {
+        if !self.bulk.is_exhausted() {
+            Err(FinishError::UnreadWords(self.num_words()))
+        } else if self.state != State::zero() {
+            Err(FinishError::InvalidFinalState)
+        } else {
+            Ok(())
+        }
+    }
+
     pub fn into_decoder(self) -> AnsCoder<Word, State, Backend::IntoReadWords>
     where
         Backend: IntoReadWords<Word, Stack>,
@@ -629,6 +708,16 @@ where
     }
 }
 
+/// A snapshot of an [`AnsCoder`] that can be restored with [`AnsCoder::rollback`].
+///
+/// Obtained from [`AnsCoder::checkpoint`]. A checkpoint doesn't copy any compressed data,
+/// so it is cheap to create.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AnsCoderCheckpoint<State> {
+    num_words: usize,
+    state: State,
+}
+
 impl<Word, State> AnsCoder<Word, State>
 where
     Word: BitArray + Into<State>,
@@ -640,6 +729,166 @@ where
         self.bulk.clear();
         self.state = State::zero();
     }
+
+    /// Records the current state of the coder so that you can undo subsequent encoding
+    /// operations with [`rollback`](Self::rollback).
+    ///
+    /// This is useful, e.g., for rate control: take a checkpoint, encode a candidate batch
+    /// of symbols, inspect [`num_bits`](Self::num_bits), and roll back if the batch turned
+    /// out to be too expensive. Creating a checkpoint is cheap since it doesn't copy any
+    /// compressed data, and you can keep several checkpoints around at the same time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use constriction::stream::{model::DefaultLeakyQuantizer, stack::DefaultAnsCoder, Decode};
+    ///
+    /// let quantizer = DefaultLeakyQuantizer::new(-100..=100);
+    /// let model = quantizer.quantize(probability::distribution::Gaussian::new(0.0, 10.0));
+    ///
+    /// let mut ans = DefaultAnsCoder::new();
+    /// ans.encode_iid_symbols_reverse(&[3, 1, 4], &model).unwrap();
+    /// let checkpoint = ans.checkpoint();
+    ///
+    /// // Encode a candidate batch that turns out to be too expensive, and undo it.
+    /// ans.encode_iid_symbols_reverse(&[-50, 80, 95, -99], &model).unwrap();
+    /// assert!(ans.num_bits() > 64);
+    /// ans.rollback(checkpoint).unwrap();
+    ///
+    /// // Encode a cheaper batch instead.
+    /// ans.encode_iid_symbols_reverse(&[1, 5], &model).unwrap();
+    /// let decoded = ans
+    ///     .decode_iid_symbols(5, &model)
+    ///     .collect::<Result<Vec<_>, _>>()
+    ///     .unwrap();
+    /// assert_eq!(decoded, [1, 5, 3, 1, 4]);
+    /// assert!(ans.is_empty());
+    /// ```
+    pub fn checkpoint(&self) -> AnsCoderCheckpoint<State> {
+        AnsCoderCheckpoint {
+            num_words: self.bulk.len(),
+            state: self.state,
+        }
+    }
+
+    /// Restores the state of the coder at the time when `checkpoint` was created.
+    ///
+    /// This discards all symbols that have been encoded since the call to
+    /// [`checkpoint`](Self::checkpoint). The compressed data is truncated in place, i.e.,
+    /// no compressed data is copied. The same checkpoint can be used for several rollbacks.
+    ///
+    /// You may interleave encoding and decoding operations between the call to
+    /// `checkpoint` and the call to `rollback` as long as you don't decode any symbols
+    /// that were encoded before the checkpoint (since decoding them removes data that the
+    /// checkpoint relies on). This method returns `Err(())` and leaves the coder unchanged
+    /// if it can detect that this requirement was violated, i.e., if there is less
+    /// compressed data on the coder than there was at the time of the checkpoint (this
+    /// also happens if `checkpoint` was created on a different coder). However, not all
+    /// violations can be detected.
+    #[allow(clippy::result_unit_err)]
+    pub fn rollback(&mut self, checkpoint: AnsCoderCheckpoint<State>) -> Result<(), ()> {
+        if self.bulk.len() < checkpoint.num_words {
+            return Err(());
+        }
+        self.bulk.truncate(checkpoint.num_words);
+        self.state = checkpoint.state;
+        Ok(())
+    }
+
+    /// Encodes symbols from an iterator as long as the compressed data fits into a fixed
+    /// budget of `max_num_bits` bits.
+    ///
+    /// Encodes the symbols from `symbols_and_models` in the order in which the iterator
+    /// yields them until encoding the next symbol would make the compressed data exceed
+    /// `max_num_bits` (or until the iterator is exhausted). The symbol that would exceed
+    /// the budget is not encoded, and no further symbols are taken from the iterator.
+    /// Afterwards, [`num_bits`](Self::num_bits) is at most `max_num_bits`, i.e., the data
+    /// returned by [`get_compressed`](Self::get_compressed) or
+    /// [`into_compressed`](Self::into_compressed) fits into the budget and decodes
+    /// correctly. The budget includes any compressed data that was already on the coder
+    /// before calling this method, as reported by [`num_bits`](Self::num_bits).
+    ///
+    /// Returns the number of symbols that were encoded, i.e., the length of the prefix of
+    /// `symbols_and_models` that fit into the budget. Since `AnsCoder` operates as a stack,
+    /// decoding yields these symbols in reverse order. Use a
+    /// [`RangeEncoder`](super::queue::RangeEncoder) if you need to decode the prefix in
+    /// its original order.
+    ///
+    /// Internally, this method takes a [`checkpoint`](Self::checkpoint) before each symbol
+    /// and rolls back the symbol that exceeds the budget, so the result is exact: no symbol
+    /// that would still fit is cut off. Unlike encoding onto a backend with a fixed
+    /// capacity (see [`BoundedWriteWords`]), running out of space is therefore not an
+    /// error, and the compressed data is always left in a decodable state.
+    ///
+    /// # Errors
+    ///
+    /// - Returns `Err(CoderError::Backend(BoundedWriteError::OutOfSpace))` if the
+    ///   compressed data on the coder already exceeds the budget before encoding any
+    ///   symbols. In this case, the coder is left unchanged.
+    /// - Returns `Err(CoderError::Frontend(DefaultEncoderFrontendError::ImpossibleSymbol))`
+    ///   if a symbol has zero probability under its entropy model. In this case, all
+    ///   preceding symbols remain encoded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use constriction::stream::{model::DefaultLeakyQuantizer, stack::DefaultAnsCoder, Decode};
+    ///
+    /// let quantizer = DefaultLeakyQuantizer::new(-100..=100);
+    /// let model = quantizer.quantize(probability::distribution::Gaussian::new(0.0, 10.0));
+    /// let symbols = (0..100).map(|i| (i * 7) % 41 - 20).collect::<Vec<i32>>();
+    ///
+    /// // Fit as many symbols as possible into a packet of 128 bits.
+    /// let mut ans = DefaultAnsCoder::new();
+    /// let num_encoded = ans
+    ///     .encode_symbols_within_budget(symbols.iter().map(|symbol| (symbol, model)), 128)
+    ///     .unwrap();
+    /// assert!(0 < num_encoded && num_encoded < symbols.len());
+    /// assert!(ans.num_bits() <= 128);
+    ///
+    /// // Decoding yields the encoded prefix of `symbols` in reverse order.
+    /// let mut decoded = ans
+    ///     .decode_iid_symbols(num_encoded, model)
+    ///     .collect::<Result<Vec<_>, _>>()
+    ///     .unwrap();
+    /// assert!(ans.is_empty());
+    /// decoded.reverse();
+    /// assert_eq!(decoded, symbols[..num_encoded]);
+    /// ```
+    ///
+    /// [`BoundedWriteWords`]: crate::backends::BoundedWriteWords
+    pub fn encode_symbols_within_budget<S, M, I, const PRECISION: usize>(
+        &mut self,
+        symbols_and_models: I,
+        max_num_bits: usize,
+    ) -> Result<usize, CoderError<DefaultEncoderFrontendError, BoundedWriteError>>
+    where
+        S: Borrow<M::Symbol>,
+        M: EncoderModel<PRECISION>,
+        M::Probability: Into<Word>,
+        Word: AsPrimitive<M::Probability>,
+        I: IntoIterator<Item = (S, M)>,
+    {
+        let max_num_words = max_num_bits / Word::BITS;
+        if self.num_words() > max_num_words {
+            return Err(CoderError::Backend(BoundedWriteError::OutOfSpace));
+        }
+
+        let mut num_encoded = 0;
+        for (symbol, model) in symbols_and_models {
+            let checkpoint = self.checkpoint();
+            self.encode_symbol(symbol, model)
+                .map_err(|err| CoderError::Frontend(err.into_frontend_error()))?;
+            if self.num_words() > max_num_words {
+                self.rollback(checkpoint)
+                    .expect("We only encoded onto the coder since the checkpoint.");
+                break;
+            }
+            num_encoded += 1;
+        }
+
+        Ok(num_encoded)
+    }
 }
 
 impl<'bulk, Word, State> AnsCoder<Word, State, Cursor<Word, &'bulk [Word]>>
@@ -896,6 +1145,40 @@ where
     }
 }
 
+impl<Word, State, Backend> AnsCoder<Word, State, Backend>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+    Backend: WriteWords<Word>,
+{
+    /// Pushes a symbol onto the stack, given its left-sided cumulative and its (nonzero)
+    /// probability, both in fixed-point representation with `PRECISION` bits.
+    ///
+    /// This is the part of [`encode_symbol`](Encode::encode_symbol) that comes after
+    /// the lookup in the entropy model. It is shared with [`ForwardAnsEncoder`], which
+    /// buffers the results of such lookups.
+    #[inline(always)]
+    fn encode_left_cumulative_and_probability<const PRECISION: usize>(
+        &mut self,
+        left_sided_cumulative: Word,
+        probability: Word,
+    ) -> Result<(), Backend::WriteError> {
+        if (self.state >> (State::BITS - PRECISION)) >= probability.into() {
+            self.bulk.write(self.state.as_())?;
+            self.state = self.state >> Word::BITS;
+            // At this point, the invariant on `self.state` (see its doc comment) is
+            // temporarily violated, but it will be restored below.
+        }
+
+        let remainder = (self.state % probability.into()).as_();
+        let prefix = self.state / probability.into();
+        let quantile = left_sided_cumulative + remainder;
+        self.state = prefix << PRECISION | quantile.into();
+
+        Ok(())
+    }
+}
+
 impl<Word, State, Backend, const PRECISION: usize> Encode<PRECISION>
     for AnsCoder<Word, State, Backend>
 where
@@ -943,17 +1226,10 @@ where
             .left_cumulative_and_probability(symbol)
             .ok_or_else(|| DefaultEncoderFrontendError::ImpossibleSymbol.into_coder_error())?;
 
-        if (self.state >> (State::BITS - PRECISION)) >= probability.get().into().into() {
-            self.bulk.write(self.state.as_())?;
-            self.state = self.state >> Word::BITS;
-            // At this point, the invariant on `self.state` (see its doc comment) is
-            // temporarily violated, but it will be restored below.
-        }
-
-        let remainder = (self.state % probability.get().into().into()).as_().as_();
-        let prefix = self.state / probability.get().into().into();
-        let quantile = left_sided_cumulative + remainder;
-        self.state = prefix << PRECISION | quantile.into().into();
+        self.encode_left_cumulative_and_probability::<PRECISION>(
+            left_sided_cumulative.into(),
+            probability.get().into(),
+        )?;
 
         Ok(())
     }
@@ -1142,105 +1418,879 @@ where
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::super::model::{
-        ContiguousCategoricalEntropyModel, DefaultLeakyQuantizer, IterableEntropyModel,
-        LeakyQuantizer,
-    };
-    use super::*;
-    extern crate std;
-    use std::dbg;
-
-    use probability::distribution::{Gaussian, Inverse};
-    use rand_xoshiro::{
-        rand_core::{RngCore, SeedableRng},
-        Xoshiro256StarStar,
-    };
+/// An ANS coder that interleaves `N` independent states on a shared backend.
+///
+/// An [`AnsCoder`] has a single internal state, and each encoding or decoding operation
+/// depends on the result of the previous one. This dependency chain limits throughput on
+/// modern CPUs, which could otherwise execute several (independent) operations in
+/// parallel. An `InterleavedAnsCoder` keeps `N` independent rANS states ("lanes") and
+/// assigns consecutive symbols to the lanes in a round-robin fashion, so that `N`
+/// consecutive encoding or decoding operations are independent of each other (except for
+/// the occasional read from or write to the shared backend). This technique is described
+/// in \[1].
+///
+/// An `InterleavedAnsCoder` implements the same traits ([`Code`], [`Encode`], [`Decode`],
+/// [`Pos`], and [`Seek`]) as an `AnsCoder`, so you can use it as a drop-in replacement in
+/// generic code. It also has stack semantics: decoding returns symbols in reverse order
+/// of encoding, and you can interleave encoding and decoding operations arbitrarily.
+/// However, compressed data is *not* compatible between the two types of coders, nor
+/// between `InterleavedAnsCoder`s with different `N`. Further, an `InterleavedAnsCoder`
+/// has a constant overhead of `N * State::BITS` bits per compressed message since
+/// [`into_compressed`] writes out all `N` states in full. Thus, interleaving pays off
+/// only for long messages.
+///
+/// # Example
+///
+/// ```
+/// use constriction::stream::{
+///     model::DefaultLeakyQuantizer, stack::DefaultInterleavedAnsCoder, Decode,
+/// };
+///
+/// // An `InterleavedAnsCoder` with 4 lanes, `Word = u32`, and `State = u64`.
+/// let mut ans = DefaultInterleavedAnsCoder::<4>::new();
+///
+/// let quantizer = DefaultLeakyQuantizer::new(-100..=100);
+/// let entropy_model = quantizer.quantize(probability::distribution::Gaussian::new(0.0, 10.0));
+///
+/// let symbols = (-50..50).collect::<Vec<_>>();
+/// ans.encode_iid_symbols_reverse(&symbols, &entropy_model).unwrap();
+/// let compressed = ans.into_compressed().unwrap();
+///
+/// let mut ans = DefaultInterleavedAnsCoder::<4>::from_compressed(compressed).unwrap();
+/// let reconstructed = ans
+///     .decode_iid_symbols(100, &entropy_model)
+///     .collect::<Result<Vec<_>, _>>()
+///     .unwrap();
+/// assert_eq!(reconstructed, symbols);
+/// assert!(ans.is_empty());
+/// ```
+///
+/// # References
+///
+/// \[1] Giesen, Fabian. "Interleaved entropy coders." arXiv preprint arXiv:1402.3392
+/// (2014).
+///
+/// [`into_compressed`]: Self::into_compressed
+#[derive(Clone)]
+pub struct InterleavedAnsCoder<Word, State, const N: usize, Backend = Vec<Word>>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+{
+    bulk: Backend,
 
-    #[test]
-    fn compress_none() {
-        let coder1 = DefaultAnsCoder::new();
-        assert!(coder1.is_empty());
-        let compressed = coder1.into_compressed().unwrap();
-        assert!(compressed.is_empty());
+    /// Invariant: each state is `>= State::one() << (State::BITS - Word::BITS)` (unless
+    /// the coder was used to decode past the end of the compressed data).
+    states: [State; N],
 
-        let coder2 = DefaultAnsCoder::from_compressed(compressed).unwrap();
-        assert!(coder2.is_empty());
-    }
+    /// Index of the lane to which the next encoded symbol will be assigned.
+    /// Invariant: `next < N`.
+    next: usize,
 
-    #[test]
-    fn compress_one() {
-        generic_compress_few(core::iter::once(5), 1)
-    }
+    /// We keep track of the `Word` type so that we can statically enforce the invariant
+    /// `Word: Into<State>`.
+    phantom: PhantomData<Word>,
+}
 
-    #[test]
-    fn compress_two() {
-        generic_compress_few([2, 8].iter().cloned(), 1)
-    }
+/// Type alias for an [`InterleavedAnsCoder`] with sane parameters for typical use cases.
+///
+/// Uses the same `Word` and `State` types as a [`DefaultAnsCoder`]. The number of lanes
+/// `N` has to be specified explicitly; `N = 4` is a reasonable choice on most CPUs.
+pub type DefaultInterleavedAnsCoder<const N: usize, Backend = Vec<u32>> =
+    InterleavedAnsCoder<u32, u64, N, Backend>;
+
+/// Type of the internal state used by an [`InterleavedAnsCoder`]. Relevant for
+/// [`Seek`]ing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InterleavedAnsCoderState<State, const N: usize> {
+    states: [State; N],
+    next: usize,
+}
 
-    #[test]
-    fn compress_ten() {
-        generic_compress_few(0..10, 2)
+impl<State, const N: usize> InterleavedAnsCoderState<State, N> {
+    /// Returns the states of all lanes.
+    pub fn states(&self) -> &[State; N] {
+        &self.states
     }
 
-    #[test]
-    fn compress_twenty() {
-        generic_compress_few(-10..10, 4)
+    /// Returns the index of the lane to which the next encoded symbol will be assigned
+    /// (decoding reads from the lane with index `(next_lane() + N - 1) % N`).
+    pub fn next_lane(&self) -> usize {
+        self.next
     }
 
-    fn generic_compress_few<I>(symbols: I, expected_size: usize)
+    /// Checks if `self` and `other` describe the same coder state up to a relabeling of
+    /// lanes.
+    ///
+    /// The lanes of a coder that was created with [`InterleavedAnsCoder::from_compressed`]
+    /// are rotated relative to the lanes of the encoder (see
+    /// [`InterleavedAnsCoder::into_compressed`]), so positions obtained from an encoder
+    /// and from the corresponding decoder generally don't compare equal even if they
+    /// describe the same point in the compressed data. This method compares the states
+    /// of the lanes in the order in which they will be decoded from, which is independent
+    /// of the labeling.
+    pub fn is_equivalent_to(&self, other: &Self) -> bool
     where
-        I: IntoIterator<Item = i32>,
-        I::IntoIter: Clone + DoubleEndedIterator,
+        State: PartialEq,
     {
-        let symbols = symbols.into_iter();
-
-        let mut encoder = DefaultAnsCoder::new();
-        let quantizer = DefaultLeakyQuantizer::new(-127..=127);
-        let model = quantizer.quantize(Gaussian::new(3.2, 5.1));
-
-        // We don't reuse the same encoder for decoding because we want to test
-        // if exporting and re-importing of compressed data works.
-        encoder.encode_iid_symbols(symbols.clone(), model).unwrap();
-        let compressed = encoder.into_compressed().unwrap();
-        assert_eq!(compressed.len(), expected_size);
-
-        let mut decoder = DefaultAnsCoder::from_compressed(compressed).unwrap();
-        for symbol in symbols.rev() {
-            assert_eq!(decoder.decode_symbol(&model).unwrap(), symbol);
-        }
-        assert!(decoder.is_empty());
+        (0..N).all(|i| self.states[(self.next + i) % N] == other.states[(other.next + i) % N])
     }
+}
 
-    #[test]
-    fn compress_many_u32_u64_32() {
-        generic_compress_many::<u32, u64, u32, 32>();
+impl<Word, State, const N: usize, Backend> Debug for InterleavedAnsCoder<Word, State, N, Backend>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+    Backend: Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("InterleavedAnsCoder")
+            .field("bulk", &self.bulk)
+            .field("states", &self.states)
+            .field("next", &self.next)
+            .finish()
     }
+}
 
-    #[test]
-    fn compress_many_u32_u64_24() {
-        generic_compress_many::<u32, u64, u32, 24>();
+impl<Word, State, const N: usize> InterleavedAnsCoder<Word, State, N, Vec<Word>>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+{
+    /// Creates an empty `InterleavedAnsCoder` for encoding.
+    pub fn new() -> Self {
+        Self::default()
     }
+}
 
-    #[test]
-    fn compress_many_u32_u64_16() {
-        generic_compress_many::<u32, u64, u16, 16>();
+impl<Word, State, const N: usize, Backend> Default for InterleavedAnsCoder<Word, State, N, Backend>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+    Backend: Default,
+{
+    fn default() -> Self {
+        Self::with_backend(Default::default())
     }
+}
 
-    #[test]
-    fn compress_many_u32_u64_8() {
-        generic_compress_many::<u32, u64, u8, 8>();
-    }
+impl<Word, State, const N: usize, Backend> InterleavedAnsCoder<Word, State, N, Backend>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+{
+    /// Number of `Word`s that [`into_compressed`](Self::into_compressed) appends per lane.
+    const WORDS_PER_STATE: usize = State::BITS / Word::BITS;
 
-    #[test]
-    fn compress_many_u16_u64_16() {
-        generic_compress_many::<u16, u64, u16, 16>();
+    /// Creates an empty `InterleavedAnsCoder` that writes compressed data to `bulk`.
+    ///
+    /// Any data that is already in `bulk` remains untouched.
+    pub fn with_backend(bulk: Backend) -> Self {
+        Self::assert_valid_types();
+        Self {
+            bulk,
+            states: [Self::lower_bound(); N],
+            next: 0,
+            phantom: PhantomData,
+        }
     }
 
-    #[test]
-    fn compress_many_u16_u64_12() {
-        generic_compress_many::<u16, u64, u16, 12>();
+    /// Low-level constructor that assembles an `InterleavedAnsCoder` from its internal
+    /// components.
+    ///
+    /// The arguments `bulk` and `state` correspond to the two return values of the method
+    /// [`into_raw_parts`](Self::into_raw_parts). The caller must ensure that `state` was
+    /// obtained from an `InterleavedAnsCoder` with the same type parameters, or else the
+    /// coder will behave incorrectly (but not unsafely).
+    pub fn from_raw_parts(bulk: Backend, state: InterleavedAnsCoderState<State, N>) -> Self {
+        Self::assert_valid_types();
+        Self {
+            bulk,
+            states: state.states,
+            next: state.next % N,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Low-level method that disassembles the `InterleavedAnsCoder` into its internal
+    /// components.
+    ///
+    /// Can be used together with [`from_raw_parts`](Self::from_raw_parts).
+    pub fn into_raw_parts(self) -> (Backend, InterleavedAnsCoderState<State, N>) {
+        let state = self.state();
+        (self.bulk, state)
+    }
+
+    /// Creates an `InterleavedAnsCoder` from compressed data that was obtained from
+    /// [`into_compressed`](Self::into_compressed).
+    ///
+    /// If `compressed` is empty, this is equivalent to [`with_backend`](Self::with_backend).
+    /// Otherwise, reads the `N` states off the top of the stack. Returns `Err(compressed)`
+    /// if `compressed` ends before all `N` states could be read or if any of the states is
+    /// invalid. Neither error can occur if `compressed` was obtained from `into_compressed`
+    /// of an `InterleavedAnsCoder` with the same type parameters `Word`, `State`, and `N`.
+    pub fn from_compressed(mut compressed: Backend) -> Result<Self, Backend>
+    where
+        Backend: ReadWords<Word, Stack>,
+    {
+        Self::assert_valid_types();
+        let mut states = [Self::lower_bound(); N];
+
+        for (lane, state) in states.iter_mut().enumerate().rev() {
+            let mut value = State::zero();
+            for i in 0..Self::WORDS_PER_STATE {
+                match compressed.read() {
+                    Ok(Some(word)) => value = value << Word::BITS | word.into(),
+                    Ok(None) if lane == N - 1 && i == 0 => {
+                        // `compressed` is empty.
+                        return Ok(Self::with_backend(compressed));
+                    }
+                    _ => return Err(compressed),
+                }
+            }
+            if value < Self::lower_bound() {
+                return Err(compressed);
+            }
+            *state = value;
+        }
+
+        Ok(Self {
+            bulk: compressed,
+            states,
+            next: 0,
+            phantom: PhantomData,
+        })
+    }
+
+    #[inline(always)]
+    pub fn bulk(&self) -> &Backend {
+        &self.bulk
+    }
+
+    /// Check if no data for decoding is left.
+    ///
+    /// Returns `true` if all lanes are in their initial state and the backend is
+    /// exhausted. This is the case for a newly created coder and after decoding all
+    /// symbols that were encoded onto a newly created coder.
+    pub fn is_empty(&self) -> bool
+    where
+        Backend: BoundedReadWords<Word, Stack>,
+    {
+        self.lanes_in_initial_state() && self.bulk.is_exhausted()
+    }
+
+    fn lanes_in_initial_state(&self) -> bool {
+        self.states
+            .iter()
+            .all(|&state| state == Self::lower_bound())
+    }
+
+    #[inline(always)]
+    fn lower_bound() -> State {
+        State::one() << (State::BITS - Word::BITS)
+    }
+
+    #[inline(always)]
+    fn assert_valid_types() {
+        assert!(N != 0);
+        assert!(State::BITS >= 2 * Word::BITS);
+        assert_eq!(State::BITS % Word::BITS, 0);
+    }
+}
+
+impl<Word, State, const N: usize, Backend> InterleavedAnsCoder<Word, State, N, Backend>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+    Backend: WriteWords<Word>,
+{
+    /// See [`AnsCoder::encode_symbols_reverse`].
+    pub fn encode_symbols_reverse<S, M, I, const PRECISION: usize>(
+        &mut self,
+        symbols_and_models: I,
+    ) -> Result<(), DefaultEncoderError<Backend::WriteError>>
+    where
+        S: Borrow<M::Symbol>,
+        M: EncoderModel<PRECISION>,
+        M::Probability: Into<Word>,
+        Word: AsPrimitive<M::Probability>,
+        I: IntoIterator<Item = (S, M)>,
+        I::IntoIter: DoubleEndedIterator,
+    {
+        self.encode_symbols(symbols_and_models.into_iter().rev())
+    }
+
+    /// See [`AnsCoder::try_encode_symbols_reverse`].
+    pub fn try_encode_symbols_reverse<S, M, E, I, const PRECISION: usize>(
+        &mut self,
+        symbols_and_models: I,
+    ) -> Result<(), TryCodingError<DefaultEncoderError<Backend::WriteError>, E>>
+    where
+        S: Borrow<M::Symbol>,
+        M: EncoderModel<PRECISION>,
+        M::Probability: Into<Word>,
+        Word: AsPrimitive<M::Probability>,
+        I: IntoIterator<Item = core::result::Result<(S, M), E>>,
+        I::IntoIter: DoubleEndedIterator,
+    {
+        self.try_encode_symbols(symbols_and_models.into_iter().rev())
+    }
+
+    /// See [`AnsCoder::encode_iid_symbols_reverse`].
+    pub fn encode_iid_symbols_reverse<S, M, I, const PRECISION: usize>(
+        &mut self,
+        symbols: I,
+        model: M,
+    ) -> Result<(), DefaultEncoderError<Backend::WriteError>>
+    where
+        S: Borrow<M::Symbol>,
+        M: EncoderModel<PRECISION> + Copy,
+        M::Probability: Into<Word>,
+        Word: AsPrimitive<M::Probability>,
+        I: IntoIterator<Item = S>,
+        I::IntoIter: DoubleEndedIterator,
+    {
+        self.encode_iid_symbols(symbols.into_iter().rev(), model)
+    }
+
+    /// Consumes the coder and returns the compressed data.
+    ///
+    /// Appends all `N` states (each one in full, i.e., `State::BITS / Word::BITS` words) to
+    /// the backend. The lanes are rotated such that the lane from which the next symbol
+    /// would be decoded is written last. Thus, [`from_compressed`](Self::from_compressed)
+    /// can restore a coder that is equivalent to `self` without having to store the
+    /// index of the current lane.
+    ///
+    /// As a consequence, the decoder labels its lanes differently than the encoder, and
+    /// positions obtained from the decoder's [`pos`](Pos::pos) method generally don't
+    /// compare equal to the encoder's positions at the same point in the compressed data
+    /// (use [`InterleavedAnsCoderState::is_equivalent_to`] to compare them). Positions
+    /// obtained from the encoder are nevertheless valid arguments for [`seek`](Seek::seek)
+    /// on the decoder since a position captures the states of all lanes.
+    pub fn into_compressed(mut self) -> Result<Backend, Backend::WriteError> {
+        let states = self.states;
+        let next = self.next;
+        self.bulk.extend_from_iter((0..N).flat_map(move |i| {
+            let state = states[(next + i) % N];
+            (0..Self::WORDS_PER_STATE).map(move |j| (state >> (j * Word::BITS)).as_())
+        }))?;
+        Ok(self.bulk)
+    }
+}
+
+impl<Word, State, const N: usize, Backend> Code for InterleavedAnsCoder<Word, State, N, Backend>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+{
+    type Word = Word;
+    type State = InterleavedAnsCoderState<State, N>;
+
+    #[inline(always)]
+    fn state(&self) -> Self::State {
+        InterleavedAnsCoderState {
+            states: self.states,
+            next: self.next,
+        }
+    }
+}
+
+impl<Word, State, const N: usize, Backend, const PRECISION: usize> Encode<PRECISION>
+    for InterleavedAnsCoder<Word, State, N, Backend>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+    Backend: WriteWords<Word>,
+{
+    type FrontendError = DefaultEncoderFrontendError;
+    type BackendError = Backend::WriteError;
+
+    /// Encodes a single symbol onto the current lane and advances to the next lane.
+    ///
+    /// See [`AnsCoder::encode_symbol`](struct.AnsCoder.html#method.encode_symbol).
+    fn encode_symbol<M>(
+        &mut self,
+        symbol: impl Borrow<M::Symbol>,
+        model: M,
+    ) -> Result<(), DefaultEncoderError<Self::BackendError>>
+    where
+        M: EncoderModel<PRECISION>,
+        M::Probability: Into<Self::Word>,
+        Self::Word: AsPrimitive<M::Probability>,
+    {
+        assert!(State::BITS >= Word::BITS + PRECISION);
+
+        let (left_sided_cumulative, probability) = model
+            .left_cumulative_and_probability(symbol)
+            .ok_or_else(|| DefaultEncoderFrontendError::ImpossibleSymbol.into_coder_error())?;
+
+        let mut state = self.states[self.next];
+        if (state >> (State::BITS - PRECISION)) >= probability.get().into().into() {
+            self.bulk.write(state.as_())?;
+            state = state >> Word::BITS;
+        }
+
+        let remainder = (state % probability.get().into().into()).as_().as_();
+        let prefix = state / probability.get().into().into();
+        let quantile = left_sided_cumulative + remainder;
+        self.states[self.next] = prefix << PRECISION | quantile.into().into();
+        self.next = if self.next + 1 == N { 0 } else { self.next + 1 };
+
+        Ok(())
+    }
+
+    fn maybe_full(&self) -> bool {
+        self.bulk.maybe_full()
+    }
+}
+
+impl<Word, State, const N: usize, Backend, const PRECISION: usize> Decode<PRECISION>
+    for InterleavedAnsCoder<Word, State, N, Backend>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+    Backend: ReadWords<Word, Stack>,
+{
+    /// Like an [`AnsCoder`], an `InterleavedAnsCoder` can decode past the end of the
+    /// compressed data (in a deterministic way), so decoding cannot fail.
+    type FrontendError = Infallible;
+
+    type BackendError = Backend::ReadError;
+
+    /// Moves back to the previous lane and decodes a single symbol from it.
+    ///
+    /// Returns the symbol that was last encoded by [`encode_symbol`]. As with an
+    /// [`AnsCoder`], decoding past the end of the compressed data produces symbols in a
+    /// deterministic way, but, unlike with an `AnsCoder`, encoding on top of such a coder
+    /// does not reproduce the original compressed data.
+    ///
+    /// [`encode_symbol`]: #method.encode_symbol
+    #[inline(always)]
+    fn decode_symbol<M>(
+        &mut self,
+        model: M,
+    ) -> Result<M::Symbol, CoderError<Self::FrontendError, Self::BackendError>>
+    where
+        M: DecoderModel<PRECISION>,
+        M::Probability: Into<Self::Word>,
+        Self::Word: AsPrimitive<M::Probability>,
+    {
+        assert!(State::BITS >= Word::BITS + PRECISION);
+
+        self.next = if self.next == 0 { N - 1 } else { self.next - 1 };
+        let mut state = self.states[self.next];
+
+        let quantile = (state % (State::one() << PRECISION)).as_().as_();
+        let (symbol, left_sided_cumulative, probability) = model.quantile_function(quantile);
+        let remainder = quantile - left_sided_cumulative;
+        state = (state >> PRECISION) * probability.get().into().into() + remainder.into().into();
+        if state < Self::lower_bound() {
+            if let Some(word) = self.bulk.read()? {
+                state = (state << Word::BITS) | word.into();
+            }
+        }
+        self.states[self.next] = state;
+
+        Ok(symbol)
+    }
+
+    /// Returns `true` if all lanes are in their initial state and the backend may be
+    /// exhausted.
+    fn maybe_exhausted(&self) -> bool {
+        self.lanes_in_initial_state() && self.bulk.maybe_exhausted()
+    }
+}
+
+impl<Word, State, const N: usize, Backend> PosSeek for InterleavedAnsCoder<Word, State, N, Backend>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+    Backend: PosSeek,
+    Self: Code,
+{
+    type Position = (Backend::Position, <Self as Code>::State);
+}
+
+impl<Word, State, const N: usize, Backend> Seek for InterleavedAnsCoder<Word, State, N, Backend>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+    Backend: Seek,
+{
+    fn seek(&mut self, (pos, state): Self::Position) -> Result<(), ()> {
+        if state.next >= N {
+            return Err(());
+        }
+        self.bulk.seek(pos)?;
+        self.states = state.states;
+        self.next = state.next;
+        Ok(())
+    }
+}
+
+impl<Word, State, const N: usize, Backend> Pos for InterleavedAnsCoder<Word, State, N, Backend>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+    Backend: Pos,
+{
+    fn pos(&self) -> Self::Position {
+        (self.bulk.pos(), self.state())
+    }
+}
+
+/// Encoder that accepts symbols in decoding order and writes them to an [`AnsCoder`].
+///
+/// An [`AnsCoder`] is a stack, so you have to encode symbols in *reverse* order if you
+/// want to decode them in their original order (see, e.g.,
+/// [`AnsCoder::encode_symbols_reverse`]). This can be awkward if the entropy models are
+/// computed on the fly from previously encoded symbols (e.g., in an autoregressive model),
+/// since you then can't simply iterate over the message backwards. A `ForwardAnsEncoder`
+/// lets you call [`encode_symbol`] (and the other methods of the [`Encode`] trait) in the
+/// same order in which you will later decode the symbols. It looks up each symbol in its
+/// entropy model right away and buffers the resulting left-sided cumulative and
+/// probability (the symbol itself isn't needed anymore after the lookup). When you
+/// [`seal`] the encoder, it pushes the buffered entries onto an internal `AnsCoder` in
+/// reverse order.
+///
+/// The resulting compressed data is identical to what you'd get by encoding the same
+/// symbols with an `AnsCoder` in reverse order, so you get the full compression
+/// effectiveness of ANS. The price is memory: a `ForwardAnsEncoder` holds two `Word`s per
+/// symbol until it is sealed.
+///
+/// # Example
+///
+/// ```
+/// use constriction::stream::{
+///     model::DefaultLeakyQuantizer,
+///     stack::{DefaultAnsCoder, DefaultForwardAnsEncoder},
+///     Decode, Encode,
+/// };
+///
+/// let quantizer = DefaultLeakyQuantizer::new(-100..=100);
+/// let symbols = [23i32, -15, 78, 43, -69];
+///
+/// // Encode in decoding order, using a model that depends on the previous symbol.
+/// let mut encoder = DefaultForwardAnsEncoder::new();
+/// let mut previous = 0i32;
+/// for &symbol in &symbols {
+///     let model = quantizer.quantize(probability::distribution::Gaussian::new(
+///         previous as f64,
+///         30.0,
+///     ));
+///     encoder.encode_symbol(symbol, model).unwrap();
+///     previous = symbol;
+/// }
+/// let compressed = encoder.into_compressed().unwrap();
+///
+/// // Decode in the same order.
+/// let mut decoder = DefaultAnsCoder::from_compressed(compressed).unwrap();
+/// let mut previous = 0i32;
+/// for &expected in &symbols {
+///     let model = quantizer.quantize(probability::distribution::Gaussian::new(
+///         previous as f64,
+///         30.0,
+///     ));
+///     let symbol = decoder.decode_symbol(model).unwrap();
+///     assert_eq!(symbol, expected);
+///     previous = symbol;
+/// }
+/// assert!(decoder.is_empty());
+/// ```
+///
+/// [`encode_symbol`]: Encode::encode_symbol
+/// [`seal`]: Self::seal
+#[derive(Clone)]
+pub struct ForwardAnsEncoder<Word, State, const PRECISION: usize, Backend = Vec<Word>>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+{
+    /// Pairs `(left_sided_cumulative, probability)` in the order in which the symbols were
+    /// encoded. Invariant: each `probability` is nonzero.
+    buffer: Vec<(Word, Word)>,
+
+    inner: AnsCoder<Word, State, Backend>,
+}
+
+/// Type alias for a [`ForwardAnsEncoder`] with sane parameters for typical use cases.
+///
+/// Uses the same `Word` and `State` types as a [`DefaultAnsCoder`], and the same
+/// `PRECISION` as a [`DefaultLeakyQuantizer`].
+///
+/// [`DefaultLeakyQuantizer`]: super::model::DefaultLeakyQuantizer
+pub type DefaultForwardAnsEncoder<Backend = Vec<u32>> = ForwardAnsEncoder<u32, u64, 24, Backend>;
+
+/// Type alias for a [`ForwardAnsEncoder`] that produces data for a [`SmallAnsCoder`].
+pub type SmallForwardAnsEncoder<Backend = Vec<u16>> = ForwardAnsEncoder<u16, u32, 12, Backend>;
+
+impl<Word, State, const PRECISION: usize, Backend> Debug
+    for ForwardAnsEncoder<Word, State, PRECISION, Backend>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+    AnsCoder<Word, State, Backend>: Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ForwardAnsEncoder")
+            .field("inner", &self.inner)
+            .field("num_buffered", &self.buffer.len())
+            .finish()
+    }
+}
+
+impl<Word, State, const PRECISION: usize> ForwardAnsEncoder<Word, State, PRECISION, Vec<Word>>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+{
+    /// Creates an empty `ForwardAnsEncoder`.
+    ///
+    /// To avoid type parameters in common use cases, `new` is only implemented for a `Vec`
+    /// backend. To create an empty encoder with a different backend, call
+    /// [`Default::default`] or [`from_coder`](Self::from_coder) instead.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<Word, State, const PRECISION: usize, Backend> Default
+    for ForwardAnsEncoder<Word, State, PRECISION, Backend>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+    Backend: Default,
+{
+    fn default() -> Self {
+        Self::from_coder(Default::default())
+    }
+}
+
+impl<Word, State, const PRECISION: usize, Backend>
+    ForwardAnsEncoder<Word, State, PRECISION, Backend>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+{
+    /// Creates a `ForwardAnsEncoder` that will push its symbols on top of `coder`.
+    ///
+    /// Upon [`seal`](Self::seal)ing, the symbols encoded on the `ForwardAnsEncoder` end
+    /// up on top of any data that `coder` already contains. Thus, a decoder will first
+    /// decode the symbols encoded on the `ForwardAnsEncoder` (in the order in which they
+    /// were encoded), and then the data that was already on `coder`.
+    pub fn from_coder(coder: AnsCoder<Word, State, Backend>) -> Self {
+        Self {
+            buffer: Vec::new(),
+            inner: coder,
+        }
+    }
+
+    /// Returns the number of symbols that have been encoded but not yet written to the
+    /// internal `AnsCoder`.
+    pub fn num_buffered(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Returns `true` if no symbols are buffered and the internal `AnsCoder` is empty.
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty() && self.inner.is_empty()
+    }
+}
+
+impl<Word, State, const PRECISION: usize, Backend>
+    ForwardAnsEncoder<Word, State, PRECISION, Backend>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+    Backend: WriteWords<Word>,
+{
+    /// Writes all buffered symbols to the internal `AnsCoder` and returns it.
+    ///
+    /// The buffered symbols are pushed in reverse order, so that decoding from the
+    /// returned `AnsCoder` yields them in the order in which they were encoded on the
+    /// `ForwardAnsEncoder`.
+    pub fn seal(mut self) -> Result<AnsCoder<Word, State, Backend>, Backend::WriteError> {
+        for &(left_sided_cumulative, probability) in self.buffer.iter().rev() {
+            self.inner
+                .encode_left_cumulative_and_probability::<PRECISION>(
+                    left_sided_cumulative,
+                    probability,
+                )?;
+        }
+        Ok(self.inner)
+    }
+
+    /// Seals the encoder (see [`seal`](Self::seal)) and returns the compressed data.
+    ///
+    /// This is equivalent to `encoder.seal()?.into_compressed()`.
+    pub fn into_compressed(self) -> Result<Backend, Backend::WriteError> {
+        self.seal()?.into_compressed()
+    }
+}
+
+impl<Word, State, const PRECISION: usize, Backend> Code
+    for ForwardAnsEncoder<Word, State, PRECISION, Backend>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+{
+    type Word = Word;
+    type State = State;
+
+    /// Returns the state of the internal `AnsCoder`.
+    ///
+    /// Note that buffered symbols don't affect the state until the encoder is
+    /// [`seal`](ForwardAnsEncoder::seal)ed.
+    #[inline(always)]
+    fn state(&self) -> Self::State {
+        self.inner.state()
+    }
+}
+
+impl<Word, State, const PRECISION: usize, Backend> Encode<PRECISION>
+    for ForwardAnsEncoder<Word, State, PRECISION, Backend>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+{
+    type FrontendError = DefaultEncoderFrontendError;
+
+    /// Buffering can't fail. Errors from the backend can only occur when the encoder is
+    /// [`seal`](ForwardAnsEncoder::seal)ed.
+    type BackendError = Infallible;
+
+    /// Looks up `symbol` in `model` and buffers the result.
+    ///
+    /// Returns [`Err(ImpossibleSymbol)`] right away if `symbol` has zero probability
+    /// under `model`, i.e., you don't have to wait until sealing to find out.
+    ///
+    /// [`Err(ImpossibleSymbol)`]: DefaultEncoderFrontendError::ImpossibleSymbol
+    fn encode_symbol<M>(
+        &mut self,
+        symbol: impl Borrow<M::Symbol>,
+        model: M,
+    ) -> Result<(), DefaultEncoderError<Self::BackendError>>
+    where
+        M: EncoderModel<PRECISION>,
+        M::Probability: Into<Self::Word>,
+        Self::Word: AsPrimitive<M::Probability>,
+    {
+        assert!(State::BITS >= Word::BITS + PRECISION);
+
+        let (left_sided_cumulative, probability) = model
+            .left_cumulative_and_probability(symbol)
+            .ok_or_else(|| DefaultEncoderFrontendError::ImpossibleSymbol.into_coder_error())?;
+        self.buffer
+            .push((left_sided_cumulative.into(), probability.get().into()));
+
+        Ok(())
+    }
+
+    fn maybe_full(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::model::{
+        ContiguousCategoricalEntropyModel, DefaultLeakyQuantizer, IterableEntropyModel,
+        LeakyQuantizer,
+    };
+    use super::super::tests::random_gaussian_symbols;
+    use super::*;
+    extern crate std;
+    use std::dbg;
+
+    use probability::distribution::{Gaussian, Inverse};
+    use rand_xoshiro::{
+        rand_core::{RngCore, SeedableRng},
+        Xoshiro256StarStar,
+    };
+
+    #[test]
+    fn compress_none() {
+        let coder1 = DefaultAnsCoder::new();
+        assert!(coder1.is_empty());
+        let compressed = coder1.into_compressed().unwrap();
+        assert!(compressed.is_empty());
+
+        let coder2 = DefaultAnsCoder::from_compressed(compressed).unwrap();
+        assert!(coder2.is_empty());
+    }
+
+    #[test]
+    fn compress_one() {
+        generic_compress_few(core::iter::once(5), 1)
+    }
+
+    #[test]
+    fn compress_two() {
+        generic_compress_few([2, 8].iter().cloned(), 1)
+    }
+
+    #[test]
+    fn compress_ten() {
+        generic_compress_few(0..10, 2)
+    }
+
+    #[test]
+    fn compress_twenty() {
+        generic_compress_few(-10..10, 4)
+    }
+
+    fn generic_compress_few<I>(symbols: I, expected_size: usize)
+    where
+        I: IntoIterator<Item = i32>,
+        I::IntoIter: Clone + DoubleEndedIterator,
+    {
+        let symbols = symbols.into_iter();
+
+        let mut encoder = DefaultAnsCoder::new();
+        let quantizer = DefaultLeakyQuantizer::new(-127..=127);
+        let model = quantizer.quantize(Gaussian::new(3.2, 5.1));
+
+        // We don't reuse the same encoder for decoding because we want to test
+        // if exporting and re-importing of compressed data works.
+        encoder.encode_iid_symbols(symbols.clone(), model).unwrap();
+        let compressed = encoder.into_compressed().unwrap();
+        assert_eq!(compressed.len(), expected_size);
+
+        let mut decoder = DefaultAnsCoder::from_compressed(compressed).unwrap();
+        for symbol in symbols.rev() {
+            assert_eq!(decoder.decode_symbol(&model).unwrap(), symbol);
+        }
+        assert!(decoder.is_empty());
+    }
+
+    #[test]
+    fn compress_many_u32_u64_32() {
+        generic_compress_many::<u32, u64, u32, 32>();
+    }
+
+    #[test]
+    fn compress_many_u32_u64_24() {
+        generic_compress_many::<u32, u64, u32, 24>();
+    }
+
+    #[test]
+    fn compress_many_u32_u64_16() {
+        generic_compress_many::<u32, u64, u16, 16>();
+    }
+
+    #[test]
+    fn compress_many_u32_u64_8() {
+        generic_compress_many::<u32, u64, u8, 8>();
+    }
+
+    #[test]
+    fn compress_many_u16_u64_16() {
+        generic_compress_many::<u16, u64, u16, 16>();
+    }
+
+    #[test]
+    fn compress_many_u16_u64_12() {
+        generic_compress_many::<u16, u64, u16, 12>();
     }
 
     #[test]
@@ -1376,6 +2426,93 @@ mod tests {
         assert_eq!(symbols_categorical, reconstructed_categorical);
     }
 
+    #[test]
+    fn compress_many_u64_u128_48() {
+        generic_compress_many_large::<48>();
+    }
+
+    #[test]
+    fn compress_many_u64_u128_64() {
+        generic_compress_many_large::<64>();
+    }
+
+    fn generic_compress_many_large<const PRECISION: usize>() {
+        #[cfg(not(miri))]
+        const AMT: usize = 1000;
+
+        #[cfg(miri)]
+        const AMT: usize = 100;
+
+        let mut rng = Xoshiro256StarStar::seed_from_u64(PRECISION as u64);
+
+        // A very skewed distribution, which can't be represented faithfully with a
+        // precision of only 32 bits.
+        let probabilities = [1e-13, 0.3, 2e-11, 0.5, 1e-15, 0.2];
+        let categorical =
+            ContiguousCategoricalEntropyModel::<u64, _, PRECISION>::from_floating_point_probabilities(
+                &probabilities,
+            )
+            .unwrap();
+        let max_probability = u64::MAX >> (64 - PRECISION);
+        let symbols_categorical = (0..AMT)
+            .map(|_| {
+                categorical
+                    .quantile_function(rng.next_u64() & max_probability)
+                    .0
+            })
+            .collect::<Vec<_>>();
+
+        let mut ans = AnsCoder::<u64, u128>::new();
+        ans.encode_iid_symbols_reverse(&symbols_categorical, &categorical)
+            .unwrap();
+
+        let information_content = symbols_categorical
+            .iter()
+            .map(|&symbol| {
+                let (_, probability) = categorical.left_cumulative_and_probability(symbol).unwrap();
+                PRECISION as f64 - (probability.get() as f64).log2()
+            })
+            .sum::<f64>();
+        assert!(ans.num_valid_bits() as f64 >= information_content);
+        assert!((ans.num_valid_bits() as f64) < information_content + 128.0);
+
+        // `LeakyQuantizer`s with `f64` can only represent precisions of up to 53 bits.
+        let means = (0..AMT)
+            .map(|_| (200.0 / u32::MAX as f64) * rng.next_u32() as f64 - 100.0)
+            .collect::<Vec<_>>();
+        let symbols_gaussian = means
+            .iter()
+            .map(|&mean| (mean + (rng.next_u32() % 5) as f64 - 2.0).round() as i32)
+            .collect::<Vec<_>>();
+        let quantizer =
+            (PRECISION <= 53).then(|| LeakyQuantizer::<_, _, u64, PRECISION>::new(-127..=127));
+        if let Some(quantizer) = &quantizer {
+            ans.encode_symbols_reverse(
+                symbols_gaussian
+                    .iter()
+                    .zip(&means)
+                    .map(|(&symbol, &mean)| (symbol, quantizer.quantize(Gaussian::new(mean, 1.5)))),
+            )
+            .unwrap();
+        }
+
+        let compressed = ans.into_compressed().unwrap();
+        let mut ans = LargeAnsCoder::from_compressed(compressed).unwrap();
+
+        if let Some(quantizer) = &quantizer {
+            for (&symbol, &mean) in symbols_gaussian.iter().zip(&means) {
+                let model = quantizer.quantize(Gaussian::new(mean, 1.5));
+                assert_eq!(ans.decode_symbol(model).unwrap(), symbol);
+            }
+        }
+        let reconstructed_categorical = ans
+            .decode_iid_symbols(AMT, &categorical)
+            .collect::<Result<Vec<_>, CoderError<Infallible, Infallible>>>()
+            .unwrap();
+        assert_eq!(symbols_categorical, reconstructed_categorical);
+        assert!(ans.is_empty());
+    }
+
     #[test]
     fn seek() {
         #[cfg(not(miri))]
@@ -1469,4 +2606,512 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn interleaved_compress_none() {
+        let coder = DefaultInterleavedAnsCoder::<4>::new();
+        assert!(coder.is_empty());
+        let compressed = coder.into_compressed().unwrap();
+        assert_eq!(compressed.len(), 8);
+        let coder = DefaultInterleavedAnsCoder::<4>::from_compressed(compressed).unwrap();
+        assert!(coder.is_empty());
+
+        let coder = DefaultInterleavedAnsCoder::<4>::from_compressed(Vec::new()).unwrap();
+        assert!(coder.is_empty());
+        assert!(DefaultInterleavedAnsCoder::<4>::from_compressed(alloc::vec![1; 7]).is_err());
+        assert!(DefaultInterleavedAnsCoder::<4>::from_compressed(alloc::vec![1, 0, 1, 1]).is_err());
+    }
+
+    #[test]
+    fn interleaved_compress_many() {
+        generic_interleaved_compress_many::<u32, u64, u32, 24, 1>();
+        generic_interleaved_compress_many::<u32, u64, u32, 24, 4>();
+        generic_interleaved_compress_many::<u32, u64, u16, 16, 7>();
+        generic_interleaved_compress_many::<u16, u32, u16, 12, 2>();
+        generic_interleaved_compress_many::<u16, u64, u16, 16, 5>();
+        generic_interleaved_compress_many::<u8, u32, u8, 8, 3>();
+    }
+
+    fn generic_interleaved_compress_many<
+        Word,
+        State,
+        Probability,
+        const PRECISION: usize,
+        const N: usize,
+    >()
+    where
+        State: BitArray + AsPrimitive<Word>,
+        Word: BitArray + Into<State> + AsPrimitive<Probability>,
+        Probability: BitArray + Into<Word> + AsPrimitive<usize> + Into<f64>,
+        u32: AsPrimitive<Probability>,
+        usize: AsPrimitive<Probability>,
+        f64: AsPrimitive<Probability>,
+        i32: AsPrimitive<Probability>,
+    {
+        #[cfg(not(miri))]
+        const AMT: usize = 1000;
+
+        #[cfg(miri)]
+        const AMT: usize = 100;
+
+        let mut rng = Xoshiro256StarStar::seed_from_u64(
+            (Word::BITS as u64).rotate_left(3 * 16)
+                ^ (State::BITS as u64).rotate_left(2 * 16)
+                ^ (PRECISION as u64).rotate_left(16)
+                ^ N as u64,
+        );
+
+        let quantizer = LeakyQuantizer::<_, _, Probability, PRECISION>::new(-127..=127);
+        let mut params_gaussian = Vec::with_capacity(AMT);
+        let mut symbols_gaussian = Vec::with_capacity(AMT);
+        for _ in 0..AMT {
+            let mean = (200.0 / u32::MAX as f64) * rng.next_u32() as f64 - 100.0;
+            let std_dev = (10.0 / u32::MAX as f64) * rng.next_u32() as f64 + 0.001;
+            let quantile = (rng.next_u32() as f64 + 0.5) / (1u64 << 32) as f64;
+            let symbol =
+                (Gaussian::new(mean, std_dev).inverse(quantile).round() as i32).clamp(-127, 127);
+            params_gaussian.push((mean, std_dev));
+            symbols_gaussian.push(symbol);
+        }
+
+        let categorical_probabilities = [0.01, 0.2, 0.0001, 0.3, 0.15, 0.1, 0.2389, 0.0001];
+        let categorical =
+            ContiguousCategoricalEntropyModel::<Probability, _, PRECISION>::from_floating_point_probabilities(
+                &categorical_probabilities,
+            )
+            .unwrap();
+        let max_probability = Probability::max_value() >> (Probability::BITS - PRECISION);
+        let symbols_categorical = (0..AMT)
+            .map(|_| {
+                categorical
+                    .quantile_function(rng.next_u32().as_() & max_probability)
+                    .0
+            })
+            .collect::<Vec<_>>();
+
+        let mut ans = InterleavedAnsCoder::<Word, State, N>::new();
+        ans.encode_iid_symbols_reverse(&symbols_categorical, &categorical)
+            .unwrap();
+        ans.encode_symbols_reverse(symbols_gaussian.iter().zip(&params_gaussian).map(
+            |(&symbol, &(mean, std_dev))| {
+                (symbol, quantizer.quantize(Gaussian::new(mean, std_dev)))
+            },
+        ))
+        .unwrap();
+
+        let compressed = ans.into_compressed().unwrap();
+        let mut ans = InterleavedAnsCoder::<Word, State, N>::from_compressed(compressed).unwrap();
+
+        let reconstructed_gaussian = ans
+            .decode_symbols(
+                params_gaussian
+                    .iter()
+                    .map(|&(mean, std_dev)| quantizer.quantize(Gaussian::new(mean, std_dev))),
+            )
+            .collect::<Result<Vec<_>, CoderError<Infallible, Infallible>>>()
+            .unwrap();
+        let reconstructed_categorical = ans
+            .decode_iid_symbols(AMT, &categorical)
+            .collect::<Result<Vec<_>, CoderError<Infallible, Infallible>>>()
+            .unwrap();
+
+        assert!(ans.is_empty());
+        assert_eq!(symbols_gaussian, reconstructed_gaussian);
+        assert_eq!(symbols_categorical, reconstructed_categorical);
+    }
+
+    #[test]
+    fn interleaved_in_generic_code() {
+        /// Pushes and pops symbols in an interleaved way (as in bits-back coding), using
+        /// only the `Encode` and `Decode` traits.
+        fn push_and_pop<C>(coder: &mut C) -> Vec<i32>
+        where
+            C: Code<Word = u32> + Encode<24> + Decode<24>,
+            <C as Encode<24>>::BackendError: Debug,
+            <C as Decode<24>>::FrontendError: Debug,
+            <C as Decode<24>>::BackendError: Debug,
+        {
+            let quantizer = DefaultLeakyQuantizer::new(-100..=100);
+            let wide = quantizer.quantize(Gaussian::new(0.0, 30.0));
+            let narrow = quantizer.quantize(Gaussian::new(5.0, 2.0));
+
+            coder.encode_iid_symbols(-50..50, wide).unwrap();
+            let sampled = coder
+                .decode_iid_symbols(20, narrow)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            coder
+                .encode_iid_symbols(sampled.iter().rev(), narrow)
+                .unwrap();
+            coder.encode_iid_symbols(10..20, wide).unwrap();
+
+            let mut decoded = coder
+                .decode_iid_symbols(130, wide)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            decoded.truncate(10);
+            decoded
+        }
+
+        let expected = (10..20).rev().collect::<Vec<_>>();
+        assert_eq!(push_and_pop(&mut DefaultAnsCoder::new()), expected);
+        assert_eq!(
+            push_and_pop(&mut DefaultInterleavedAnsCoder::<1>::new()),
+            expected
+        );
+        assert_eq!(
+            push_and_pop(&mut DefaultInterleavedAnsCoder::<4>::new()),
+            expected
+        );
+        assert_eq!(
+            push_and_pop(&mut DefaultInterleavedAnsCoder::<5>::new()),
+            expected
+        );
+    }
+
+    #[test]
+    fn interleaved_seek() {
+        #[cfg(not(miri))]
+        let (num_chunks, symbols_per_chunk) = (100, 101);
+
+        #[cfg(miri)]
+        let (num_chunks, symbols_per_chunk) = (10, 11);
+
+        let quantizer = DefaultLeakyQuantizer::new(-100..=100);
+        let model = quantizer.quantize(Gaussian::new(0.0, 10.0));
+
+        let mut encoder = DefaultInterleavedAnsCoder::<4>::new();
+
+        let mut rng = Xoshiro256StarStar::seed_from_u64(1234);
+        let mut symbols = Vec::with_capacity(num_chunks);
+        let mut jump_table = Vec::with_capacity(num_chunks);
+        let initial_pos = encoder.pos();
+
+        for _ in 0..num_chunks {
+            let chunk = (0..symbols_per_chunk)
+                .map(|_| model.quantile_function(rng.next_u32() % (1 << 24)).0)
+                .collect::<Vec<_>>();
+            encoder.encode_iid_symbols_reverse(&chunk, &model).unwrap();
+            symbols.push(chunk);
+            jump_table.push(encoder.pos());
+        }
+
+        let compressed = encoder.into_compressed().unwrap();
+        let mut seekable_decoder = DefaultInterleavedAnsCoder::<4, _>::from_compressed(
+            Cursor::new_at_write_end(&compressed[..]),
+        )
+        .unwrap();
+
+        // Positions obtained from the encoder are valid seek targets on the decoder even
+        // though the decoder labels its lanes differently (see `into_compressed`).
+        for (chunk, (pos, state)) in symbols.iter().zip(&jump_table).rev() {
+            assert_eq!(seekable_decoder.pos().0, *pos);
+            assert!(seekable_decoder.state().is_equivalent_to(state));
+            let decoded = seekable_decoder
+                .decode_iid_symbols(symbols_per_chunk, &model)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            assert_eq!(&decoded, chunk)
+        }
+        assert_eq!(seekable_decoder.pos().0, initial_pos.0);
+        assert!(seekable_decoder.is_empty());
+
+        for _ in 0..100 {
+            let chunk_index = rng.next_u32() as usize % num_chunks;
+            let pos = jump_table[chunk_index];
+            seekable_decoder.seek(pos).unwrap();
+            let decoded = seekable_decoder
+                .decode_iid_symbols(symbols_per_chunk, &model)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            assert_eq!(&decoded, &symbols[chunk_index])
+        }
+    }
+
+    #[test]
+    fn forward_encoder() {
+        #[cfg(not(miri))]
+        let amt = 1000;
+
+        #[cfg(miri)]
+        let amt = 100;
+
+        let quantizer = DefaultLeakyQuantizer::new(-127..=127);
+        let mut rng = Xoshiro256StarStar::seed_from_u64(4321);
+        let symbols_and_models = (0..amt)
+            .map(|_| {
+                let mean = 100.0 * (rng.next_u32() as f64 / u32::MAX as f64) - 50.0;
+                let std_dev = 0.1 + 20.0 * (rng.next_u32() as f64 / u32::MAX as f64);
+                let model = quantizer.quantize(Gaussian::new(mean, std_dev));
+                let symbol = model.quantile_function(rng.next_u32() % (1 << 24)).0;
+                (symbol, model)
+            })
+            .collect::<Vec<_>>();
+
+        // Some existing data that should end up *below* the forward encoded symbols.
+        let prefix_model = quantizer.quantize(Gaussian::new(0.0, 10.0));
+        let prefix = (-20..20).collect::<Vec<_>>();
+
+        let mut reference = DefaultAnsCoder::new();
+        reference
+            .encode_iid_symbols_reverse(&prefix, prefix_model)
+            .unwrap();
+        let mut forward = DefaultForwardAnsEncoder::from_coder(reference.clone());
+        reference
+            .encode_symbols_reverse(symbols_and_models.iter().map(|(s, m)| (*s, m)))
+            .unwrap();
+
+        let state_before = forward.state();
+        forward
+            .encode_symbols(symbols_and_models.iter().map(|(s, m)| (*s, m)))
+            .unwrap();
+        assert_eq!(forward.num_buffered(), amt);
+        assert_eq!(forward.state(), state_before);
+
+        // Symbols outside of the support of a quantized distribution are impossible.
+        assert!(matches!(
+            forward.encode_symbol(1000, prefix_model),
+            Err(CoderError::Frontend(
+                DefaultEncoderFrontendError::ImpossibleSymbol
+            ))
+        ));
+        assert_eq!(forward.num_buffered(), amt);
+
+        let compressed = forward.into_compressed().unwrap();
+        assert_eq!(compressed, reference.into_compressed().unwrap());
+
+        let mut decoder = DefaultAnsCoder::from_compressed(compressed).unwrap();
+        for (symbol, model) in &symbols_and_models {
+            assert_eq!(decoder.decode_symbol(model).unwrap(), *symbol);
+        }
+        let decoded_prefix = decoder
+            .decode_iid_symbols(prefix.len(), prefix_model)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(decoded_prefix, prefix);
+        assert!(decoder.is_empty());
+    }
+
+    #[test]
+    fn forward_encoder_empty_and_small() {
+        let encoder = DefaultForwardAnsEncoder::new();
+        assert!(encoder.is_empty());
+        assert!(encoder.into_compressed().unwrap().is_empty());
+
+        let quantizer = LeakyQuantizer::<_, _, u16, 12>::new(-100..=100);
+        let model = quantizer.quantize(Gaussian::new(3.0, 15.0));
+        let symbols = (-40..40).collect::<Vec<_>>();
+
+        let mut encoder = SmallForwardAnsEncoder::new();
+        encoder.encode_iid_symbols(&symbols, model).unwrap();
+        assert!(!encoder.is_empty());
+        let coder = encoder.seal().unwrap();
+
+        let mut reference = SmallAnsCoder::new();
+        reference
+            .encode_iid_symbols_reverse(&symbols, model)
+            .unwrap();
+        assert_eq!(coder.bulk(), reference.bulk());
+        assert_eq!(coder.state(), reference.state());
+
+        let mut decoder = coder.into_decoder();
+        let decoded = decoder
+            .decode_iid_symbols(symbols.len(), model)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(decoded, symbols);
+        assert!(decoder.is_empty());
+    }
+
+    #[test]
+    fn checkpoint_and_rollback() {
+        #[cfg(not(miri))]
+        let (num_batches, batch_size) = (100, 50);
+
+        #[cfg(miri)]
+        let (num_batches, batch_size) = (5, 10);
+
+        let quantizer = LeakyQuantizer::<_, _, u16, 12>::new(-100..=100);
+        let model = quantizer.quantize(Gaussian::new(0.0, 10.0));
+        let mut rng = Xoshiro256StarStar::seed_from_u64(1234);
+
+        let mut coder = SmallAnsCoder::new();
+        let mut reference = SmallAnsCoder::new();
+        let initial_checkpoint = coder.checkpoint();
+        let mut accepted = Vec::new();
+
+        for _ in 0..num_batches {
+            let batch = (0..batch_size)
+                .map(|_| {
+                    model
+                        .quantile_function((rng.next_u32() % (1 << 12)) as u16)
+                        .0
+                })
+                .collect::<Vec<_>>();
+            let checkpoint = coder.checkpoint();
+            coder.encode_iid_symbols_reverse(&batch, model).unwrap();
+
+            if rng.next_u32() % 2 == 0 {
+                coder.rollback(checkpoint).unwrap();
+                assert_eq!(coder.checkpoint(), checkpoint);
+            } else {
+                reference.encode_iid_symbols_reverse(&batch, model).unwrap();
+                accepted.push(batch);
+            }
+            assert_eq!(coder.bulk(), reference.bulk());
+            assert_eq!(coder.state(), reference.state());
+        }
+
+        // Decoding symbols that were encoded after a checkpoint doesn't invalidate it.
+        let checkpoint = coder.checkpoint();
+        coder.encode_symbol(5, model).unwrap();
+        assert_eq!(coder.decode_symbol(model).unwrap(), 5);
+        coder.encode_iid_symbols_reverse([1, 2, 3], model).unwrap();
+        coder.rollback(checkpoint).unwrap();
+        assert_eq!(coder.bulk(), reference.bulk());
+        assert_eq!(coder.state(), reference.state());
+
+        let mut decoder = coder.clone();
+        for batch in accepted.iter().rev() {
+            let decoded = decoder
+                .decode_iid_symbols(batch_size, model)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            assert_eq!(&decoded, batch);
+        }
+        assert!(decoder.is_empty());
+
+        // Rolling back to a checkpoint with more compressed data than present fails.
+        let mut small = SmallAnsCoder::new();
+        if coder.bulk().len() > 1 {
+            assert!(small.rollback(checkpoint).is_err());
+            assert!(small.is_empty());
+        }
+        coder.rollback(initial_checkpoint).unwrap();
+        assert!(coder.is_empty());
+    }
+
+    #[test]
+    fn encode_within_budget() {
+        #[cfg(not(miri))]
+        let (num_symbols, max_budget) = (1000, 2048);
+
+        #[cfg(miri)]
+        let (num_symbols, max_budget) = (50, 128);
+
+        let quantizer = LeakyQuantizer::<_, _, u16, 12>::new(-100..=100);
+        let mut rng = Xoshiro256StarStar::seed_from_u64(2023);
+        let symbols = random_gaussian_symbols(quantizer, 30.0, num_symbols, &mut rng);
+
+        for max_num_bits in (0..max_budget).step_by(13) {
+            let mut coder = SmallAnsCoder::new();
+            let num_encoded = coder
+                .encode_symbols_within_budget(symbols.iter().cloned(), max_num_bits)
+                .unwrap();
+            assert!(coder.num_bits() <= max_num_bits);
+
+            // The cut is exact: encoding one more symbol would have exceeded the budget.
+            if num_encoded < symbols.len() {
+                let mut reference = SmallAnsCoder::new();
+                reference
+                    .encode_symbols(symbols[..=num_encoded].iter().cloned())
+                    .unwrap();
+                assert!(reference.num_bits() > max_num_bits);
+            }
+
+            let mut decoder =
+                SmallAnsCoder::from_compressed(coder.into_compressed().unwrap()).unwrap();
+            for (symbol, model) in symbols[..num_encoded].iter().rev() {
+                assert_eq!(decoder.decode_symbol(model).unwrap(), *symbol);
+            }
+            assert!(decoder.is_empty());
+        }
+
+        // If the data on the coder already exceeds the budget, nothing is encoded.
+        let mut coder = SmallAnsCoder::new();
+        coder.encode_symbols(symbols[..10].iter().cloned()).unwrap();
+        let num_bits = coder.num_bits();
+        let compressed = coder.clone().into_compressed().unwrap();
+        assert_eq!(
+            coder.encode_symbols_within_budget(symbols[10..].iter().cloned(), num_bits - 1),
+            Err(CoderError::Backend(BoundedWriteError::OutOfSpace))
+        );
+        assert_eq!(coder.num_bits(), num_bits);
+        assert_eq!(coder.into_compressed().unwrap(), compressed);
+    }
+
+    #[test]
+    fn finish() {
+        let quantizer = LeakyQuantizer::<_, _, u32, 24>::new(-100..=100);
+        let model = quantizer.quantize(Gaussian::new(0.0, 10.0));
+        let other_model = quantizer.quantize(Gaussian::new(3.0, 20.0));
+        let mut rng = Xoshiro256StarStar::seed_from_u64(2024);
+        let symbols = (0..100)
+            .map(|_| model.quantile_function(rng.next_u32() % (1 << 24)).0)
+            .collect::<Vec<_>>();
+
+        assert_eq!(DefaultAnsCoder::new().finish(), Ok(()));
+        assert_eq!(
+            DefaultAnsCoder::from_compressed(Vec::new())
+                .unwrap()
+                .finish(),
+            Ok(())
+        );
+
+        let mut encoder = DefaultAnsCoder::new();
+        encoder.encode_iid_symbols_reverse(&symbols, model).unwrap();
+        let compressed = encoder.into_compressed().unwrap();
+
+        let mut decoder = DefaultAnsCoder::from_compressed(compressed.clone()).unwrap();
+        for (i, &symbol) in symbols.iter().enumerate() {
+            // Once the bulk is exhausted, only the state still holds undecoded symbols.
+            if decoder.bulk().is_empty() {
+                assert_eq!(decoder.finish(), Err(FinishError::InvalidFinalState));
+            } else {
+                assert_eq!(
+                    decoder.finish(),
+                    Err(FinishError::UnreadWords(decoder.num_words()))
+                );
+            }
+            assert_eq!(decoder.decode_symbol(model).unwrap(), symbol, "i = {i}");
+        }
+        assert_eq!(decoder.finish(), Ok(()));
+
+        // `finish` doesn't recognize the initial state of `from_binary` on empty data.
+        let mut encoder = DefaultAnsCoder::from_binary(Vec::new()).unwrap();
+        assert_eq!(encoder.state(), 1);
+        assert_eq!(encoder.finish(), Err(FinishError::InvalidFinalState));
+        encoder.encode_iid_symbols_reverse(&symbols, model).unwrap();
+        let mut decoder =
+            DefaultAnsCoder::from_compressed(encoder.into_compressed().unwrap()).unwrap();
+        let decoded = decoder
+            .decode_iid_symbols(symbols.len(), model)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(decoded, symbols);
+        assert_eq!(decoder.finish(), Err(FinishError::InvalidFinalState));
+        assert!(decoder.bulk().is_empty());
+        assert_eq!(decoder.state(), 1);
+
+        // Data underneath the encoded symbols is detected.
+        let mut encoder =
+            DefaultAnsCoder::from_compressed(alloc::vec![0xdead_beef, 0x1234_5678, 0x0000_0001])
+                .unwrap();
+        encoder.encode_iid_symbols_reverse(&symbols, model).unwrap();
+        let mut decoder =
+            DefaultAnsCoder::from_compressed(encoder.into_compressed().unwrap()).unwrap();
+        let decoded = decoder
+            .decode_iid_symbols(symbols.len(), model)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(decoded, symbols);
+        assert_eq!(decoder.finish(), Err(FinishError::UnreadWords(3)));
+
+        // A mismatched entropy model is detected.
+        let mut decoder = DefaultAnsCoder::from_compressed(compressed.clone()).unwrap();
+        for _ in 0..symbols.len() {
+            decoder.decode_symbol(other_model).unwrap();
+        }
+        assert!(decoder.finish().is_err());
+    }
 }
@@ -0,0 +1,1200 @@
+//! Table-based Asymmetric Numeral Systems (tANS) on a stack
+//!
+//! This module provides the [`TansCoder`], an entropy coder that implements the
+//! table-based variant of Asymmetric Numeral Systems (tANS) \[1], which is also known as
+//! Finite State Entropy (FSE) and used, e.g., in the zstd compression format. Like the
+//! [`AnsCoder`] from the sister module [`stack`], the `TansCoder` operates as a *stack*
+//! ("last in first out"), i.e., it decodes symbols in reverse order compared to how they
+//! were encoded.
+//!
+//! # Comparison to sister module `stack`
+//!
+//! An [`AnsCoder`] implements *range* ANS (rANS), which performs a multiplication and a
+//! division for each encoded symbol but can use a different entropy model for each symbol
+//! at no extra cost. By contrast, a `TansCoder` precomputes state transition tables (see
+//! [`TansTables`]) for a single *static* entropy model so that encoding and decoding
+//! become simple table lookups and bit manipulations. This pays off if you encode lots
+//! of symbols with the same categorical entropy model over a small alphabet (up to a few
+//! thousand symbols). The price is a slightly lower compression effectiveness since the
+//! probabilities of the entropy model get quantized to the (relatively small) size of the
+//! tables, and the setup cost of building the tables.
+//!
+//! # Example
+//!
+//! ```
+//! use constriction::stream::{
+//!     model::DefaultContiguousCategoricalEntropyModel,
+//!     tans::{DefaultTansCoder, DefaultTansTables},
+//!     Decode,
+//! };
+//!
+//! let probabilities = vec![0.03, 0.07, 0.1, 0.1, 0.2, 0.2, 0.1, 0.15, 0.05];
+//! let model = DefaultContiguousCategoricalEntropyModel
+//!     ::from_floating_point_probabilities(&probabilities).unwrap();
+//!
+//! // Build tables with `2^11` entries. This is the expensive part, so reuse the tables for
+//! // as many symbols as possible.
+//! let tables = DefaultTansTables::from_model(&model, 11).unwrap();
+//!
+//! let symbols = vec![8, 2, 0, 7, 4, 4, 5, 3, 1, 6];
+//! let mut coder = DefaultTansCoder::new(&tables);
+//! coder.encode_iid_symbols_reverse(&symbols, &model).unwrap();
+//! let compressed = coder.into_compressed().unwrap();
+//!
+//! let mut coder = DefaultTansCoder::from_compressed(&tables, compressed).unwrap();
+//! let reconstructed = coder
+//!     .decode_iid_symbols(10, &model)
+//!     .collect::<Result<Vec<_>, _>>()
+//!     .unwrap();
+//! assert_eq!(reconstructed, symbols);
+//! assert!(coder.is_empty());
+//! ```
+//!
+//! # References
+//!
+//! \[1] Duda, Jarek. "Asymmetric numeral systems: entropy coding combining speed of Huffman
+//! coding with compression rate of arithmetic coding." arXiv preprint arXiv:1311.2540
+//! (2013).
+//!
+//! [`AnsCoder`]: super::stack::AnsCoder
+//! [`stack`]: super::stack
+
+use alloc::vec::Vec;
+use core::{
+    borrow::Borrow,
+    cmp::Reverse,
+    convert::Infallible,
+    fmt::{Debug, Display},
+    marker::PhantomData,
+};
+
+use num_traits::{AsPrimitive, NumCast, ToPrimitive};
+
+use super::{
+    model::{DecoderModel, EncoderModel, IterableEntropyModel},
+    Code, Decode, Encode,
+};
+use crate::{
+    backends::{BoundedReadWords, ReadWords, WriteWords},
+    BitArray, CoderError, DefaultEncoderError, DefaultEncoderFrontendError, NonZeroBitArray, Queue,
+    Stack,
+};
+
+/// The largest supported value for the `table_log` argument of [`TansTables::from_model`].
+pub const MAX_TABLE_LOG: usize = 16;
+
+/// Precomputed state transition tables for a [`TansCoder`].
+///
+/// The tables are built for a single entropy model (see [`from_model`]), whose
+/// probabilities get quantized to `2^table_log` so that each one of the `2^table_log`
+/// states of the coder can be assigned to a symbol. Larger tables approximate the entropy
+/// model more closely but are more expensive to build and less cache friendly.
+///
+/// Tables can be serialized into a sequence of `u32` words with [`serialize`] and restored
+/// with [`deserialize`] so that you can transmit them alongside the compressed data. The
+/// serialized representation contains the (quantized) entropy model, from which the
+/// tables are rebuilt deterministically.
+///
+/// [`from_model`]: Self::from_model
+/// [`serialize`]: Self::serialize
+/// [`deserialize`]: Self::deserialize
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TansTables<const PRECISION: usize> {
+    table_log: usize,
+
+    /// One entry per symbol with nonzero probability, sorted by `left_cumulative`.
+    symbols: Vec<SymbolEntry>,
+
+    /// Indexed by `state - (1 << table_log)`.
+    decoder_table: Vec<DecoderEntry>,
+
+    /// For symbol index `i`, the entries `encoder_table[symbols[i].encoder_offset..]`
+    /// map `u - symbols[i].count` to the new state, where
+    /// `symbols[i].count <= u < 2 * symbols[i].count`.
+    encoder_table: Vec<u32>,
+}
+
+/// Type alias for [`TansTables`] that are compatible with the default entropy models
+/// (e.g., [`DefaultContiguousCategoricalEntropyModel`]).
+///
+/// [`DefaultContiguousCategoricalEntropyModel`]:
+/// super::model::DefaultContiguousCategoricalEntropyModel
+pub type DefaultTansTables = TansTables<24>;
+
+/// Type alias for [`TansTables`] that are compatible with the "small" entropy models
+/// (e.g., [`SmallContiguousCategoricalEntropyModel`]).
+///
+/// [`SmallContiguousCategoricalEntropyModel`]:
+/// super::model::SmallContiguousCategoricalEntropyModel
+pub type SmallTansTables = TansTables<12>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SymbolEntry {
+    /// Left cumulative of the symbol under the original entropy model.
+    left_cumulative: u32,
+
+    /// Probability of the symbol under the original entropy model.
+    probability: u32,
+
+    /// Quantized probability, in units of `1 / (1 << table_log)`.
+    count: u32,
+
+    encoder_offset: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct DecoderEntry {
+    symbol_index: u32,
+    num_bits: u32,
+
+    /// The state before encoding the symbol, except for its `num_bits` least significant
+    /// bits, which the decoder reads from the compressed data.
+    base: u32,
+}
+
+/// Error type for constructing or deserializing [`TansTables`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TansTablesError {
+    /// The argument `table_log` is zero or larger than [`MAX_TABLE_LOG`].
+    InvalidTableLog,
+
+    /// The entropy model has more symbols with nonzero probability than the tables have
+    /// entries. Use a larger `table_log`.
+    TooManySymbols,
+
+    /// The serialized tables are invalid or were serialized for a different `PRECISION`.
+    InvalidData,
+}
+
+impl Display for TansTablesError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::InvalidTableLog => write!(
+                f,
+                "Table log must be between 1 and {} (inclusively).",
+                MAX_TABLE_LOG
+            ),
+            Self::TooManySymbols => {
+                write!(
+                    f,
+                    "Entropy model has more symbols than the tables have entries."
+                )
+            }
+            Self::InvalidData => write!(f, "Invalid serialized tANS tables."),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TansTablesError {}
+
+impl<const PRECISION: usize> TansTables<PRECISION> {
+    /// Builds tables with `2^table_log` entries for the provided entropy model.
+    ///
+    /// The probabilities of all symbols are quantized to multiples of `2^-table_log`. Each
+    /// symbol with nonzero probability under `model` gets assigned a nonzero quantized
+    /// probability. Thus, the method returns `Err(TansTablesError::TooManySymbols)` if the
+    /// model has more than `2^table_log` symbols with nonzero probability.
+    ///
+    /// Encoding or decoding with the resulting tables requires that you provide the same
+    /// `model` (see [`TansCoder`]).
+    pub fn from_model<'m, M>(model: &'m M, table_log: usize) -> Result<Self, TansTablesError>
+    where
+        M: IterableEntropyModel<'m, PRECISION>,
+    {
+        assert!(PRECISION > 0 && PRECISION <= 32);
+        if table_log == 0 || table_log > MAX_TABLE_LOG {
+            return Err(TansTablesError::InvalidTableLog);
+        }
+
+        let mut symbols = model
+            .symbol_table()
+            .map(|(_, left_cumulative, probability)| {
+                (
+                    left_cumulative.to_u32().expect("PRECISION <= 32"),
+                    probability.get().to_u32().expect("PRECISION <= 32"),
+                )
+            })
+            .collect::<Vec<_>>();
+        symbols.sort_unstable();
+        if symbols.len() > 1 << table_log {
+            return Err(TansTablesError::TooManySymbols);
+        }
+
+        let counts = quantize_probabilities::<PRECISION>(&symbols, table_log);
+        Ok(Self::from_parts(
+            table_log,
+            symbols
+                .iter()
+                .zip(counts)
+                .map(|(&(left_cumulative, probability), count)| SymbolEntry {
+                    left_cumulative,
+                    probability,
+                    count,
+                    encoder_offset: 0,
+                })
+                .collect(),
+        ))
+    }
+
+    /// Assumes that `symbols` is sorted and that the `count`s are nonzero and sum up to
+    /// `1 << table_log`; sets the `encoder_offset`s.
+    fn from_parts(table_log: usize, mut symbols: Vec<SymbolEntry>) -> Self {
+        let table_size = 1u32 << table_log;
+        let mask = table_size - 1;
+        let step = ((table_size >> 1) + (table_size >> 3) + 3) | 1;
+
+        // Spread the symbols over the table (the same way as in zstd's FSE) so that each
+        // symbol's states are distributed roughly uniformly.
+        let mut spread = alloc::vec![0u32; table_size as usize];
+        let mut pos = 0;
+        let mut offset = 0;
+        for (symbol_index, symbol) in symbols.iter_mut().enumerate() {
+            symbol.encoder_offset = offset;
+            offset += symbol.count;
+            for _ in 0..symbol.count {
+                spread[pos as usize] = symbol_index as u32;
+                pos = (pos + step) & mask;
+            }
+        }
+        debug_assert_eq!(pos, 0);
+        debug_assert_eq!(offset, table_size);
+
+        let mut next_u = symbols
+            .iter()
+            .map(|symbol| symbol.count)
+            .collect::<Vec<_>>();
+        let mut encoder_table = alloc::vec![0u32; table_size as usize];
+        let decoder_table = spread
+            .iter()
+            .enumerate()
+            .map(|(index, &symbol_index)| {
+                let symbol = &symbols[symbol_index as usize];
+                let u = next_u[symbol_index as usize];
+                next_u[symbol_index as usize] += 1;
+                encoder_table[(symbol.encoder_offset + u - symbol.count) as usize] =
+                    table_size + index as u32;
+
+                let num_bits = table_log as u32 - (31 - u.leading_zeros());
+                DecoderEntry {
+                    symbol_index,
+                    num_bits,
+                    base: u << num_bits,
+                }
+            })
+            .collect();
+
+        Self {
+            table_log,
+            symbols,
+            decoder_table,
+            encoder_table,
+        }
+    }
+
+    /// Returns the binary logarithm of the number of states.
+    pub fn table_log(&self) -> usize {
+        self.table_log
+    }
+
+    /// Returns the number of symbols with nonzero probability.
+    pub fn num_symbols(&self) -> usize {
+        self.symbols.len()
+    }
+
+    /// Iterates over the quantized probabilities (in units of `2^-table_log`) of all symbols
+    /// with nonzero probability, in order of their left cumulatives under the original
+    /// entropy model.
+    pub fn quantized_probabilities(&self) -> impl Iterator<Item = u32> + '_ {
+        self.symbols.iter().map(|symbol| symbol.count)
+    }
+
+    /// Writes the tables to `sink` in a compact representation.
+    ///
+    /// The representation consists of `3 * (self.num_symbols() + 1)` words. It can be read
+    /// back with [`deserialize`](Self::deserialize).
+    pub fn serialize<B: WriteWords<u32>>(&self, sink: &mut B) -> Result<(), B::WriteError> {
+        sink.write(self.table_log as u32)?;
+        sink.write(PRECISION as u32)?;
+        sink.write(self.symbols.len() as u32)?;
+        sink.extend_from_iter(self.symbols.iter().flat_map(|symbol| {
+            IntoIterator::into_iter([symbol.left_cumulative, symbol.probability, symbol.count])
+        }))
+    }
+
+    /// Reads tables that were written with [`serialize`](Self::serialize).
+    ///
+    /// Returns `Err(CoderError::Frontend(TansTablesError::InvalidData))` if `source` does
+    /// not contain valid serialized tables for the same `PRECISION`.
+    pub fn deserialize<B: ReadWords<u32, Queue>>(
+        source: &mut B,
+    ) -> Result<Self, CoderError<TansTablesError, B::ReadError>> {
+        let mut read = || {
+            source
+                .read()?
+                .ok_or(CoderError::Frontend(TansTablesError::InvalidData))
+        };
+
+        let table_log = read()? as usize;
+        if table_log == 0 || table_log > MAX_TABLE_LOG {
+            return Err(CoderError::Frontend(TansTablesError::InvalidTableLog));
+        }
+        if read()? as usize != PRECISION {
+            return Err(CoderError::Frontend(TansTablesError::InvalidData));
+        }
+        let num_symbols = read()? as usize;
+        if num_symbols == 0 || num_symbols > 1 << table_log {
+            return Err(CoderError::Frontend(TansTablesError::InvalidData));
+        }
+
+        let mut symbols = Vec::with_capacity(num_symbols);
+        let mut min_left_cumulative = 0u64;
+        let mut total_count = 0u64;
+        for _ in 0..num_symbols {
+            let left_cumulative = read()?;
+            let probability = read()?;
+            let count = read()?;
+            if (left_cumulative as u64) < min_left_cumulative || probability == 0 || count == 0 {
+                return Err(CoderError::Frontend(TansTablesError::InvalidData));
+            }
+            min_left_cumulative = left_cumulative as u64 + probability as u64;
+            total_count += count as u64;
+            symbols.push(SymbolEntry {
+                left_cumulative,
+                probability,
+                count,
+                encoder_offset: 0,
+            });
+        }
+        if min_left_cumulative > 1 << PRECISION || total_count != 1 << table_log {
+            return Err(CoderError::Frontend(TansTablesError::InvalidData));
+        }
+
+        Ok(Self::from_parts(table_log, symbols))
+    }
+
+    #[inline(always)]
+    fn symbol_index(&self, left_cumulative: u32) -> Option<usize> {
+        self.symbols
+            .binary_search_by_key(&left_cumulative, |symbol| symbol.left_cumulative)
+            .ok()
+    }
+}
+
+/// Quantizes the probabilities of `symbols` (which sum up to `1 << PRECISION`) to nonzero
+/// integers that sum up to `1 << table_log`.
+fn quantize_probabilities<const PRECISION: usize>(
+    symbols: &[(u32, u32)],
+    table_log: usize,
+) -> Vec<u32> {
+    if table_log >= PRECISION {
+        return symbols
+            .iter()
+            .map(|&(_, probability)| probability << (table_log - PRECISION))
+            .collect();
+    }
+
+    let shift = PRECISION - table_log;
+    let mut counts = symbols
+        .iter()
+        .map(|&(_, probability)| core::cmp::max(probability >> shift, 1))
+        .collect::<Vec<_>>();
+    let total = counts.iter().map(|&count| count as u64).sum::<u64>();
+    let target = 1u64 << table_log;
+
+    if total < target {
+        // Largest remainder method; the deficit is smaller than `symbols.len()`.
+        let mut order = (0..symbols.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&i| {
+            let probability = symbols[i].1;
+            let remainder = if probability >> shift == 0 {
+                0 // Was already rounded up.
+            } else {
+                probability & ((1 << shift) - 1)
+            };
+            (Reverse(remainder), i)
+        });
+        for &i in &order[..(target - total) as usize] {
+            counts[i] += 1;
+        }
+    } else if total > target {
+        // Some symbols were rounded up to one. Take the excess away from the symbols with
+        // the largest counts, for which the relative change is smallest.
+        let mut excess = total - target;
+        let mut order = (0..symbols.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&i| (Reverse(counts[i]), i));
+        while excess != 0 {
+            for &i in &order {
+                if excess == 0 {
+                    break;
+                }
+                if counts[i] > 1 {
+                    counts[i] -= 1;
+                    excess -= 1;
+                }
+            }
+        }
+    }
+
+    counts
+}
+
+/// Entropy coder for both encoding and decoding on a stack with precomputed tables.
+///
+/// See [module level documentation](self) for a discussion and an example.
+///
+/// A `TansCoder` holds a reference to [`TansTables`], which were built for a specific
+/// entropy model. The methods of the [`Encode`] and [`Decode`] traits still expect you to
+/// pass in an entropy model for each symbol, but the coder only uses it to map symbols
+/// to their left cumulatives and back. Thus, you must always pass in the same entropy
+/// model from which the tables were built (or an equivalent one); encoding a symbol with a
+/// different entropy model either fails with an
+/// [`ImpossibleSymbol`](DefaultEncoderFrontendError::ImpossibleSymbol) error or encodes a
+/// different symbol. If you already know the index of each symbol (i.e., its position
+/// among all symbols with nonzero probability, sorted by left cumulative), then
+/// [`encode_symbol_index`](Self::encode_symbol_index) and
+/// [`decode_symbol_index`](Self::decode_symbol_index) avoid this mapping.
+///
+/// The compressed data is a stack of bits, which is stored in a backend of `Word`s. The
+/// `State` type parameter is the type of a small bit buffer and has to be wide enough to
+/// hold a `Word` plus the bits of one symbol (i.e., `State::BITS >= Word::BITS +
+/// table_log` and `State::BITS >= 2 * Word::BITS`).
+#[derive(Clone)]
+pub struct TansCoder<'t, Word, State, const PRECISION: usize, Backend = Vec<Word>>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+{
+    bulk: Backend,
+    tables: &'t TansTables<PRECISION>,
+
+    /// The top of the stack of bits that hasn't been flushed to `bulk` yet. The least
+    /// significant bit is the top of the stack. All bits at positions
+    /// `>= num_buffered_bits` are zero.
+    buffer: State,
+
+    /// Invariant: `num_buffered_bits <= State::BITS`.
+    num_buffered_bits: usize,
+
+    /// Invariant: `1 << table_log <= state < 2 << table_log`.
+    state: u32,
+
+    phantom: PhantomData<Word>,
+}
+
+/// Type alias for a [`TansCoder`] with sane parameters for typical use cases.
+///
+/// Compatible with [`DefaultTansTables`] and, therefore, with the default entropy models.
+pub type DefaultTansCoder<'t, Backend = Vec<u32>> = TansCoder<'t, u32, u64, 24, Backend>;
+
+/// Type alias for a [`TansCoder`] with a smaller word size.
+///
+/// Compatible with [`SmallTansTables`] and, therefore, with the "small" entropy models.
+pub type SmallTansCoder<'t, Backend = Vec<u16>> = TansCoder<'t, u16, u32, 12, Backend>;
+
+/// Type of the internal state of a [`TansCoder`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TansCoderState<State> {
+    state: u32,
+    buffer: State,
+    num_buffered_bits: usize,
+}
+
+impl<State: Copy> TansCoderState<State> {
+    /// Returns the state of the tANS automaton, which is in the range
+    /// `(1 << table_log)..(2 << table_log)`.
+    pub fn tans_state(&self) -> u32 {
+        self.state
+    }
+
+    /// Returns the bits that haven't been flushed to the backend yet and their number.
+    pub fn buffered_bits(&self) -> (State, usize) {
+        (self.buffer, self.num_buffered_bits)
+    }
+}
+
+impl<Word, State, const PRECISION: usize, Backend> Debug
+    for TansCoder<'_, Word, State, PRECISION, Backend>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+    Backend: Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("TansCoder")
+            .field("bulk", &self.bulk)
+            .field("state", &self.state)
+            .field("buffer", &self.buffer)
+            .field("num_buffered_bits", &self.num_buffered_bits)
+            .finish()
+    }
+}
+
+impl<'t, Word, State, const PRECISION: usize> TansCoder<'t, Word, State, PRECISION, Vec<Word>>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+{
+    /// Creates an empty `TansCoder` for encoding.
+    pub fn new(tables: &'t TansTables<PRECISION>) -> Self {
+        Self::with_backend(tables, Vec::new())
+    }
+}
+
+impl<'t, Word, State, const PRECISION: usize, Backend>
+    TansCoder<'t, Word, State, PRECISION, Backend>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+{
+    /// Creates an empty `TansCoder` that writes compressed data to `bulk`.
+    ///
+    /// Any data that is already in `bulk` remains untouched.
+    pub fn with_backend(tables: &'t TansTables<PRECISION>, bulk: Backend) -> Self {
+        assert!(State::BITS >= 2 * Word::BITS);
+        assert!(State::BITS >= Word::BITS + tables.table_log);
+
+        Self {
+            bulk,
+            tables,
+            buffer: State::zero(),
+            num_buffered_bits: 0,
+            state: 1 << tables.table_log,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Creates a `TansCoder` from compressed data that was obtained from
+    /// [`into_compressed`](Self::into_compressed) of a `TansCoder` with the same `tables`.
+    ///
+    /// If `compressed` is empty, this is equivalent to [`with_backend`](Self::with_backend).
+    /// Returns `Err(compressed)` if `compressed` ends in a zero word or if it is too short.
+    pub fn from_compressed(
+        tables: &'t TansTables<PRECISION>,
+        compressed: Backend,
+    ) -> Result<Self, Backend>
+    where
+        Backend: ReadWords<Word, Stack>,
+    {
+        let mut coder = Self::with_backend(tables, compressed);
+        match coder.bulk.read() {
+            Ok(Some(word)) if word != Word::zero() => {
+                coder.num_buffered_bits = Word::BITS - 1 - word.leading_zeros() as usize;
+                coder.buffer = (word ^ (Word::one() << coder.num_buffered_bits)).into();
+            }
+            Ok(None) => return Ok(coder),
+            _ => return Err(coder.bulk),
+        }
+
+        let table_log = tables.table_log;
+        match coder.pop_bits(table_log) {
+            Ok(Some(bits)) => {
+                coder.state = (1 << table_log) | bits;
+                Ok(coder)
+            }
+            _ => Err(coder.bulk),
+        }
+    }
+
+    #[inline(always)]
+    pub fn bulk(&self) -> &Backend {
+        &self.bulk
+    }
+
+    /// Returns the tables that the coder uses.
+    #[inline(always)]
+    pub fn tables(&self) -> &'t TansTables<PRECISION> {
+        self.tables
+    }
+
+    /// Check if no data for decoding is left.
+    pub fn is_empty(&self) -> bool
+    where
+        Backend: BoundedReadWords<Word, Stack>,
+    {
+        self.state == 1 << self.tables.table_log
+            && self.num_buffered_bits == 0
+            && self.bulk.is_exhausted()
+    }
+
+    /// Decodes the index of a symbol (i.e., the position of the symbol among all symbols
+    /// with nonzero probability, sorted by left cumulative) and pops it off the stack.
+    ///
+    /// Decoding past the end of the compressed data is allowed and returns symbol indices
+    /// in a deterministic way.
+    #[inline(always)]
+    pub fn decode_symbol_index(&mut self) -> Result<usize, Backend::ReadError>
+    where
+        Backend: ReadWords<Word, Stack>,
+    {
+        let table_size = 1u32 << self.tables.table_log;
+        let entry = self.tables.decoder_table[(self.state - table_size) as usize];
+        let bits = self.pop_bits(entry.num_bits as usize)?.unwrap_or(0);
+        self.state = entry.base | bits;
+        Ok(entry.symbol_index as usize)
+    }
+
+    /// Pops `num_bits` bits off the stack. Returns `Ok(None)` if there are fewer than
+    /// `num_bits` bits left, in which case it pops off all remaining bits.
+    #[inline(always)]
+    fn pop_bits(&mut self, num_bits: usize) -> Result<Option<u32>, Backend::ReadError>
+    where
+        Backend: ReadWords<Word, Stack>,
+    {
+        // We may have to read more than one word if `num_bits > Word::BITS`. This can't
+        // overflow the buffer since `num_bits <= table_log <= State::BITS - Word::BITS`.
+        let mut complete = true;
+        while self.num_buffered_bits < num_bits {
+            if let Some(word) = self.bulk.read()? {
+                self.buffer = self.buffer | (word.into() << self.num_buffered_bits);
+                self.num_buffered_bits += Word::BITS;
+            } else {
+                complete = false;
+                break;
+            }
+        }
+
+        let mask = (State::one() << num_bits) - State::one();
+        let bits = (self.buffer & mask).to_u32().expect("num_bits <= 16");
+        self.buffer = self.buffer >> num_bits;
+        self.num_buffered_bits = self.num_buffered_bits.saturating_sub(num_bits);
+        Ok(if complete { Some(bits) } else { None })
+    }
+}
+
+impl<Word, State, const PRECISION: usize, Backend> TansCoder<'_, Word, State, PRECISION, Backend>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+    Backend: WriteWords<Word>,
+{
+    /// Encodes a symbol, identified by its index, and pushes it on the stack.
+    ///
+    /// The index of a symbol is its position among all symbols with nonzero probability,
+    /// sorted by left cumulative. Panics if `symbol_index >= self.tables().num_symbols()`.
+    #[inline(always)]
+    pub fn encode_symbol_index(&mut self, symbol_index: usize) -> Result<(), Backend::WriteError> {
+        let symbol = self.tables.symbols[symbol_index];
+
+        // Find the `num_bits` for which `symbol.count <= state >> num_bits < 2 * symbol.count`.
+        let mut num_bits = symbol.count.leading_zeros() - self.state.leading_zeros();
+        if self.state >> num_bits < symbol.count {
+            num_bits -= 1;
+        }
+
+        self.push_bits(self.state & ((1 << num_bits) - 1), num_bits as usize)?;
+        let u = self.state >> num_bits;
+        self.state = self.tables.encoder_table[(symbol.encoder_offset + u - symbol.count) as usize];
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn push_bits(&mut self, bits: u32, num_bits: usize) -> Result<(), Backend::WriteError> {
+        // We may have to flush more than one word if `num_bits > Word::BITS`.
+        while self.num_buffered_bits + num_bits > State::BITS {
+            self.flush_word()?;
+        }
+        self.buffer = self.buffer << num_bits | State::from(bits).expect("num_bits <= 16");
+        self.num_buffered_bits += num_bits;
+        Ok(())
+    }
+
+    /// Writes the oldest `Word::BITS` buffered bits to `bulk`.
+    #[inline(always)]
+    fn flush_word(&mut self) -> Result<(), Backend::WriteError> {
+        self.num_buffered_bits -= Word::BITS;
+        self.bulk
+            .write((self.buffer >> self.num_buffered_bits).as_())?;
+        self.buffer = self.buffer & ((State::one() << self.num_buffered_bits) - State::one());
+        Ok(())
+    }
+
+    /// Encodes a sequence of symbols, each with its individual entropy model, in reverse
+    /// order.
+    ///
+    /// A `TansCoder` is a stack, i.e., decoding pops symbols off in the reverse order in
+    /// which they were encoded. This method encodes `symbols_and_models` from back to
+    /// front, so that a subsequent call to [`decode_symbols`](Decode::decode_symbols) with
+    /// the same models decodes the symbols in their original order. This is the same
+    /// contract as for [`AnsCoder::encode_symbols_reverse`].
+    ///
+    /// [`AnsCoder::encode_symbols_reverse`]:
+    ///     crate::stream::stack::AnsCoder::encode_symbols_reverse
+    pub fn encode_symbols_reverse<S, M, I>(
+        &mut self,
+        symbols_and_models: I,
+    ) -> Result<(), DefaultEncoderError<Backend::WriteError>>
+    where
+        S: Borrow<M::Symbol>,
+        M: EncoderModel<PRECISION>,
+        M::Probability: Into<Word>,
+        Word: AsPrimitive<M::Probability>,
+        I: IntoIterator<Item = (S, M)>,
+        I::IntoIter: DoubleEndedIterator,
+    {
+        self.encode_symbols(symbols_and_models.into_iter().rev())
+    }
+
+    /// Encodes a sequence of symbols, all with the same entropy model, in reverse order.
+    ///
+    /// This is the i.i.d. counterpart of [`encode_symbols_reverse`]: it encodes `symbols`
+    /// from back to front, so that a subsequent call to
+    /// [`decode_iid_symbols`](Decode::decode_iid_symbols) with the same `model` decodes the
+    /// symbols in their original order. See also [`AnsCoder::encode_iid_symbols_reverse`].
+    ///
+    /// [`encode_symbols_reverse`]: Self::encode_symbols_reverse
+    /// [`AnsCoder::encode_iid_symbols_reverse`]:
+    ///     crate::stream::stack::AnsCoder::encode_iid_symbols_reverse
+    pub fn encode_iid_symbols_reverse<S, M, I>(
+        &mut self,
+        symbols: I,
+        model: M,
+    ) -> Result<(), DefaultEncoderError<Backend::WriteError>>
+    where
+        S: Borrow<M::Symbol>,
+        M: EncoderModel<PRECISION> + Copy,
+        M::Probability: Into<Word>,
+        Word: AsPrimitive<M::Probability>,
+        I: IntoIterator<Item = S>,
+        I::IntoIter: DoubleEndedIterator,
+    {
+        self.encode_iid_symbols(symbols.into_iter().rev(), model)
+    }
+
+    /// Consumes the coder and returns the compressed data.
+    ///
+    /// Pushes the state of the tANS automaton on the stack, flushes the buffered bits, and
+    /// terminates the data with a word that contains a single `1` bit above the remaining
+    /// buffered bits. The returned data can be decoded by passing it to
+    /// [`from_compressed`](TansCoder::from_compressed).
+    pub fn into_compressed(mut self) -> Result<Backend, Backend::WriteError> {
+        let table_log = self.tables.table_log;
+        self.push_bits(self.state ^ (1 << table_log), table_log)?;
+        while self.num_buffered_bits >= Word::BITS {
+            self.flush_word()?;
+        }
+        let last_word = self.buffer | (State::one() << self.num_buffered_bits);
+        self.bulk.write(last_word.as_())?;
+        Ok(self.bulk)
+    }
+}
+
+impl<Word, State, const PRECISION: usize, Backend> Code
+    for TansCoder<'_, Word, State, PRECISION, Backend>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+{
+    type Word = Word;
+    type State = TansCoderState<State>;
+
+    #[inline(always)]
+    fn state(&self) -> Self::State {
+        TansCoderState {
+            state: self.state,
+            buffer: self.buffer,
+            num_buffered_bits: self.num_buffered_bits,
+        }
+    }
+}
+
+impl<Word, State, const PRECISION: usize, Backend> Encode<PRECISION>
+    for TansCoder<'_, Word, State, PRECISION, Backend>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+    Backend: WriteWords<Word>,
+{
+    type FrontendError = DefaultEncoderFrontendError;
+    type BackendError = Backend::WriteError;
+
+    /// Encodes a single symbol and pushes it on the stack.
+    ///
+    /// The `model` must be the entropy model from which the tables were built (see
+    /// [struct level documentation](TansCoder)).
+    #[inline(always)]
+    fn encode_symbol<M>(
+        &mut self,
+        symbol: impl Borrow<M::Symbol>,
+        model: M,
+    ) -> Result<(), DefaultEncoderError<Self::BackendError>>
+    where
+        M: EncoderModel<PRECISION>,
+        M::Probability: Into<Self::Word>,
+        Self::Word: AsPrimitive<M::Probability>,
+    {
+        let symbol_index = model
+            .left_cumulative_and_probability(symbol)
+            .and_then(|(left_cumulative, probability)| {
+                let index = self.tables.symbol_index(left_cumulative.to_u32()?)?;
+                debug_assert_eq!(
+                    probability.get().to_u32(),
+                    Some(self.tables.symbols[index].probability),
+                    "`model` differs from the one the tables were built for."
+                );
+                Some(index)
+            })
+            .ok_or_else(|| DefaultEncoderFrontendError::ImpossibleSymbol.into_coder_error())?;
+
+        Ok(self.encode_symbol_index(symbol_index)?)
+    }
+
+    fn maybe_full(&self) -> bool {
+        self.bulk.maybe_full()
+    }
+}
+
+impl<Word, State, const PRECISION: usize, Backend> Decode<PRECISION>
+    for TansCoder<'_, Word, State, PRECISION, Backend>
+where
+    Word: BitArray + Into<State>,
+    State: BitArray + AsPrimitive<Word>,
+    Backend: ReadWords<Word, Stack>,
+{
+    /// Like rANS, tANS is surjective, so decoding cannot fail (except for backend errors).
+    type FrontendError = Infallible;
+
+    type BackendError = Backend::ReadError;
+
+    /// Decodes a single symbol and pops it off the stack.
+    ///
+    /// The `model` must be the entropy model from which the tables were built (see
+    /// [struct level documentation](TansCoder)).
+    #[inline(always)]
+    fn decode_symbol<M>(
+        &mut self,
+        model: M,
+    ) -> Result<M::Symbol, CoderError<Self::FrontendError, Self::BackendError>>
+    where
+        M: DecoderModel<PRECISION>,
+        M::Probability: Into<Self::Word>,
+        Self::Word: AsPrimitive<M::Probability>,
+    {
+        let symbol_index = self.decode_symbol_index()?;
+        let left_cumulative = self.tables.symbols[symbol_index].left_cumulative;
+        let quantile = <M::Probability as NumCast>::from(left_cumulative)
+            .expect("`model` has the same `PRECISION` as the tables.");
+        Ok(model.quantile_function(quantile).0)
+    }
+
+    fn maybe_exhausted(&self) -> bool {
+        self.state == 1 << self.tables.table_log
+            && self.num_buffered_bits == 0
+            && self.bulk.maybe_exhausted()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::model::{
+        ContiguousCategoricalEntropyModel, DefaultContiguousCategoricalEntropyModel,
+        DefaultLeakyQuantizer, IterableEntropyModel, SmallContiguousCategoricalEntropyModel,
+    };
+    use super::*;
+    use crate::{backends::Cursor, stream::stack::DefaultAnsCoder};
+
+    use probability::distribution::Gaussian;
+    use rand_xoshiro::{
+        rand_core::{RngCore, SeedableRng},
+        Xoshiro256StarStar,
+    };
+
+    #[test]
+    fn compress_none() {
+        let model = DefaultContiguousCategoricalEntropyModel::from_floating_point_probabilities(&[
+            0.2, 0.5, 0.3,
+        ])
+        .unwrap();
+        let tables = DefaultTansTables::from_model(&model, 5).unwrap();
+
+        let coder = DefaultTansCoder::new(&tables);
+        assert!(coder.is_empty());
+        let compressed = coder.into_compressed().unwrap();
+        assert_eq!(compressed, [1 << 5]);
+
+        let coder = DefaultTansCoder::from_compressed(&tables, compressed).unwrap();
+        assert!(coder.is_empty());
+        let coder = DefaultTansCoder::from_compressed(&tables, Vec::new()).unwrap();
+        assert!(coder.is_empty());
+        assert!(DefaultTansCoder::from_compressed(&tables, alloc::vec![5, 0]).is_err());
+    }
+
+    #[test]
+    fn quantization() {
+        let probabilities = [1e-6, 0.3, 1e-7, 0.2, 0.4999, 1e-5, 0.0001, 1e-6];
+        let model = DefaultContiguousCategoricalEntropyModel::from_floating_point_probabilities(
+            &probabilities,
+        )
+        .unwrap();
+
+        for table_log in 3..=MAX_TABLE_LOG {
+            let tables = DefaultTansTables::from_model(&model, table_log).unwrap();
+            let counts = tables.quantized_probabilities().collect::<Vec<_>>();
+            assert_eq!(counts.len(), probabilities.len());
+            assert!(counts.iter().all(|&count| count != 0));
+            assert_eq!(counts.iter().sum::<u32>(), 1 << table_log);
+        }
+
+        assert_eq!(
+            DefaultTansTables::from_model(&model, 2),
+            Err(TansTablesError::TooManySymbols)
+        );
+        assert_eq!(
+            DefaultTansTables::from_model(&model, MAX_TABLE_LOG + 1),
+            Err(TansTablesError::InvalidTableLog)
+        );
+
+        // Table is larger than the precision of the model.
+        let model = SmallContiguousCategoricalEntropyModel::from_floating_point_probabilities(&[
+            0.1, 0.6, 0.3,
+        ])
+        .unwrap();
+        let tables = SmallTansTables::from_model(&model, 14).unwrap();
+        assert!(tables
+            .quantized_probabilities()
+            .zip(model.symbol_table())
+            .all(|(count, (_, _, probability))| count == (probability.get() as u32) << 2));
+    }
+
+    #[test]
+    fn compress_many() {
+        generic_compress_many::<u32, u64, u32, 24>(200, 11);
+        generic_compress_many::<u32, u64, u32, 24>(200, 16);
+        generic_compress_many::<u32, u64, u32, 24>(100, 7);
+        generic_compress_many::<u16, u32, u16, 12>(200, 12);
+        generic_compress_many::<u16, u32, u16, 12>(200, 9);
+        generic_compress_many::<u16, u64, u16, 16>(1000, 14);
+        generic_compress_many::<u8, u32, u8, 8>(20, 10);
+    }
+
+    fn generic_compress_many<Word, State, Probability, const PRECISION: usize>(
+        num_symbols: usize,
+        table_log: usize,
+    ) where
+        State: BitArray + AsPrimitive<Word>,
+        Word: BitArray + Into<State> + AsPrimitive<Probability>,
+        Probability: BitArray + Into<Word> + AsPrimitive<usize> + Into<f64>,
+        u32: AsPrimitive<Probability>,
+        usize: AsPrimitive<Probability>,
+        f64: AsPrimitive<Probability>,
+    {
+        #[cfg(not(miri))]
+        const AMT: usize = 10000;
+
+        #[cfg(miri)]
+        const AMT: usize = 100;
+
+        let mut rng = Xoshiro256StarStar::seed_from_u64(
+            (Word::BITS as u64).rotate_left(3 * 16)
+                ^ (State::BITS as u64).rotate_left(2 * 16)
+                ^ (PRECISION as u64).rotate_left(16)
+                ^ table_log as u64,
+        );
+
+        let probabilities = (0..num_symbols)
+            .map(|i| {
+                if i % 17 == 3 {
+                    1e-9
+                } else {
+                    (rng.next_u32() % 1000) as f64 + 1.0
+                }
+            })
+            .collect::<Vec<_>>();
+        let model =
+            ContiguousCategoricalEntropyModel::<Probability, _, PRECISION>::from_floating_point_probabilities(
+                &probabilities,
+            )
+            .unwrap();
+        let tables = TansTables::from_model(&model, table_log).unwrap();
+
+        let max_quantile = (1u32 << PRECISION) - 1;
+        let symbols = (0..AMT)
+            .map(|_| {
+                model
+                    .quantile_function((rng.next_u32() & max_quantile).as_())
+                    .0
+            })
+            .collect::<Vec<_>>();
+
+        let mut coder = TansCoder::<Word, State, PRECISION>::new(&tables);
+        coder.encode_iid_symbols_reverse(&symbols, &model).unwrap();
+        let compressed = coder.into_compressed().unwrap();
+
+        // Compare to the information content under the quantized probabilities.
+        let counts = tables.quantized_probabilities().collect::<Vec<_>>();
+        let information_content = symbols
+            .iter()
+            .map(|&symbol| table_log as f64 - (counts[symbol] as f64).log2())
+            .sum::<f64>();
+        let num_bits = (compressed.len() * Word::BITS) as f64;
+        assert!(num_bits < 1.01 * information_content + 64.0);
+        assert!(information_content < 1.05 * model.entropy_base2::<f64>() * AMT as f64);
+
+        let mut coder = TansCoder::<Word, State, PRECISION, _>::from_compressed(
+            &tables,
+            Cursor::new_at_write_end(&compressed[..]),
+        )
+        .unwrap();
+        let decoded = coder
+            .decode_iid_symbols(AMT, &model)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(decoded, symbols);
+        assert!(coder.maybe_exhausted());
+    }
+
+    #[test]
+    fn rare_symbols() {
+        generic_rare_symbols::<u8, u32>(10);
+        generic_rare_symbols::<u8, u32>(12);
+        generic_rare_symbols::<u8, u16>(8);
+        generic_rare_symbols::<u16, u32>(16);
+        generic_rare_symbols::<u32, u64>(16);
+    }
+
+    /// Encodes symbols whose number of bits exceeds `Word::BITS`.
+    fn generic_rare_symbols<Word, State>(table_log: usize)
+    where
+        Word: BitArray + Into<State>,
+        State: BitArray + AsPrimitive<Word>,
+    {
+        #[cfg(not(miri))]
+        const AMT: usize = 1000;
+
+        #[cfg(miri)]
+        const AMT: usize = 100;
+
+        let model = DefaultContiguousCategoricalEntropyModel::from_floating_point_probabilities(&[
+            1e-5, 0.5, 0.49999,
+        ])
+        .unwrap();
+        let tables = DefaultTansTables::from_model(&model, table_log).unwrap();
+        assert_eq!(tables.quantized_probabilities().next(), Some(1));
+
+        // Sample the rare symbol much more often than its probability would suggest.
+        let mut rng = Xoshiro256StarStar::seed_from_u64(table_log as u64);
+        let symbol_indices = (0..AMT)
+            .map(|_| rng.next_u32() as usize % 3)
+            .collect::<Vec<_>>();
+
+        for len in [0, 1, 2, 3, 10, AMT] {
+            let mut coder = TansCoder::<Word, State, 24>::new(&tables);
+            for &symbol_index in symbol_indices[..len].iter().rev() {
+                coder.encode_symbol_index(symbol_index).unwrap();
+            }
+            let compressed = coder.into_compressed().unwrap();
+
+            let mut coder =
+                TansCoder::<Word, State, 24>::from_compressed(&tables, compressed).unwrap();
+            for &symbol_index in &symbol_indices[..len] {
+                assert_eq!(coder.decode_symbol_index().unwrap(), symbol_index);
+            }
+            assert!(coder.is_empty());
+        }
+    }
+
+    #[test]
+    fn leaky_quantizer_and_interleaving() {
+        let quantizer = DefaultLeakyQuantizer::new(-50..=50);
+        let model = quantizer.quantize(Gaussian::new(3.2, 9.1));
+        let tables = DefaultTansTables::from_model(&model, 12).unwrap();
+
+        let mut coder = DefaultTansCoder::new(&tables);
+        coder.encode_iid_symbols_reverse(-50..=50, model).unwrap();
+
+        // Decode some symbols and encode them again (as in bits-back coding).
+        let decoded = coder
+            .decode_iid_symbols(20, model)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(decoded, (-50..-30).collect::<Vec<_>>());
+        let state = coder.state();
+        coder
+            .encode_iid_symbols_reverse([7, 7, 7].iter(), model)
+            .unwrap();
+        assert_eq!(
+            coder
+                .decode_iid_symbols(3, model)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap(),
+            [7, 7, 7]
+        );
+        assert_eq!(coder.state(), state);
+
+        let compressed = coder.into_compressed().unwrap();
+        let mut coder =
+            DefaultTansCoder::from_compressed(&tables, Cursor::new_at_write_end(&compressed[..]))
+                .unwrap();
+        let decoded = coder
+            .decode_iid_symbols(81, model)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(decoded, (-30..=50).collect::<Vec<_>>());
+        assert!(coder.is_empty());
+
+        // Encoding a symbol outside of the support fails.
+        let mut coder = DefaultTansCoder::new(&tables);
+        assert!(coder.encode_symbol(51, model).is_err());
+    }
+
+    #[test]
+    fn similar_to_rans() {
+        let probabilities = [0.03, 0.07, 0.1, 0.1, 0.2, 0.2, 0.1, 0.15, 0.05];
+        let model = DefaultContiguousCategoricalEntropyModel::from_floating_point_probabilities(
+            &probabilities,
+        )
+        .unwrap();
+        let tables = DefaultTansTables::from_model(&model, 12).unwrap();
+        let symbols = (0..5000u32)
+            .map(|i| (i.wrapping_mul(0x6979_E2F3) >> 7) as usize % probabilities.len())
+            .collect::<Vec<_>>();
+
+        let mut tans = DefaultTansCoder::new(&tables);
+        tans.encode_iid_symbols_reverse(&symbols, &model).unwrap();
+        let tans_len = tans.into_compressed().unwrap().len();
+
+        let mut rans = DefaultAnsCoder::new();
+        rans.encode_iid_symbols_reverse(&symbols, &model).unwrap();
+        let rans_len = rans.into_compressed().unwrap().len();
+
+        assert!(tans_len as f64 <= 1.01 * rans_len as f64 + 2.0);
+    }
+
+    #[test]
+    fn serialization() {
+        let quantizer = DefaultLeakyQuantizer::new(-100..=100);
+        let model = quantizer.quantize(Gaussian::new(-7.2, 23.4));
+        let tables = DefaultTansTables::from_model(&model, 10).unwrap();
+
+        let mut serialized = Vec::new();
+        tables.serialize(&mut serialized).unwrap();
+        assert_eq!(serialized.len(), 3 * (tables.num_symbols() + 1));
+
+        let mut source = Cursor::new_at_write_beginning(&serialized[..]);
+        let deserialized = DefaultTansTables::deserialize(&mut source).unwrap();
+        assert_eq!(deserialized, tables);
+
+        // Wrong precision.
+        let mut source = Cursor::new_at_write_beginning(&serialized[..]);
+        assert_eq!(
+            SmallTansTables::deserialize(&mut source).unwrap_err(),
+            CoderError::Frontend(TansTablesError::InvalidData)
+        );
+
+        // Truncated.
+        let mut source = Cursor::new_at_write_beginning(&serialized[..serialized.len() - 1]);
+        assert_eq!(
+            DefaultTansTables::deserialize(&mut source).unwrap_err(),
+            CoderError::Frontend(TansTablesError::InvalidData)
+        );
+
+        // Counts don't add up.
+        let mut corrupted = serialized.clone();
+        corrupted[5] += 1;
+        let mut source = Cursor::new_at_write_beginning(&corrupted[..]);
+        assert_eq!(
+            DefaultTansTables::deserialize(&mut source).unwrap_err(),
+            CoderError::Frontend(TansTablesError::InvalidData)
+        );
+    }
+}
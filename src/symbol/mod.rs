@@ -22,7 +22,9 @@ use core::{
 use smallvec::SmallVec;
 
 use crate::{
-    backends::{AsReadWords, BoundedReadWords, Cursor, IntoReadWords, ReadWords, WriteWords},
+    backends::{
+        AsReadWords, BoundedReadWords, Cursor, IntoReadWords, PushReadWords, ReadWords, WriteWords,
+    },
     BitArray, CoderError, DefaultEncoderError, Queue, Semantics, Stack, UnwrapInfallible,
 };
 
@@ -185,6 +187,10 @@ pub struct QueueDecoder<Word: BitArray, B> {
 
     /// If zero then `current_word` is meaningless and has to be read in from `backend`.
     mask_next_to_read: Word,
+
+    /// Total number of words read from `backend` (wrapping), so that `decode_symbol` can
+    /// [unread](ReadWords::unread) the words of a codeword if reading fails halfway.
+    num_words_read: usize,
 }
 
 pub type DefaultQueueEncoder = QueueEncoder<u32, Vec<u32>>;
@@ -411,6 +417,7 @@ impl<Word: BitArray, B> QueueDecoder<Word, B> {
             backend: compressed,
             current_word: Word::zero(),
             mask_next_to_read: Word::zero(),
+            num_words_read: 0,
         }
     }
 
@@ -425,15 +432,59 @@ impl<Word: BitArray, B> QueueDecoder<Word, B> {
     }
 }
 
+impl<Word: BitArray> QueueDecoder<Word, PushReadWords<Word>> {
+    /// Appends the `Word`s from `words` to the end of the compressed data.
+    ///
+    /// Use this method to resume decoding after [`decode_symbol`] returned
+    /// `Err(CoderError::Backend(NeedMoreInput))`. See [`PushReadWords`] for details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`close_input`](Self::close_input) has been called before.
+    ///
+    /// [`decode_symbol`]: ReadBitStream::decode_symbol
+    pub fn push_words(&mut self, words: impl IntoIterator<Item = Word>) {
+        self.backend.extend(words);
+    }
+
+    /// Indicates that no more compressed data will arrive.
+    pub fn close_input(&mut self) {
+        self.backend.close();
+    }
+}
+
 impl<Word: BitArray, B: ReadWords<Word, Queue>> ReadBitStream<Queue> for QueueDecoder<Word, B> {
     type ReadError = B::ReadError;
 
+    /// Decodes a single symbol.
+    ///
+    /// If the backend returns an error and it supports [undoing reads](ReadWords::unread)
+    /// (e.g., a [`PushReadWords`]) then this method leaves the decoder unchanged. Thus, if
+    /// the backend error is recoverable (e.g., a [`NeedMoreInput`]), you can retry decoding
+    /// the same symbol later.
+    ///
+    /// [`NeedMoreInput`]: crate::backends::NeedMoreInput
     #[inline(always)]
     fn decode_symbol<C: DecoderCodebook>(
         &mut self,
         codebook: C,
     ) -> Result<C::Symbol, CoderError<SymbolCodeError<C::InvalidCodeword>, Self::ReadError>> {
-        codebook.decode_symbol(self)
+        let current_word = self.current_word;
+        let mask_next_to_read = self.mask_next_to_read;
+        let num_words_read = self.num_words_read;
+
+        let result = codebook.decode_symbol(&mut *self);
+
+        if let Err(CoderError::Backend(_)) = result {
+            let num_fetched = self.num_words_read.wrapping_sub(num_words_read);
+            if self.backend.unread(num_fetched) {
+                self.current_word = current_word;
+                self.mask_next_to_read = mask_next_to_read;
+                self.num_words_read = num_words_read;
+            }
+        }
+
+        result
     }
 
     fn read_bit(&mut self) -> Result<Option<bool>, Self::ReadError> {
@@ -442,6 +493,7 @@ impl<Word: BitArray, B: ReadWords<Word, Queue>> ReadBitStream<Queue> for QueueDe
                 Ok(Some(next_word)) => {
                     self.current_word = next_word;
                     self.mask_next_to_read = Word::one();
+                    self.num_words_read = self.num_words_read.wrapping_add(1);
                 }
                 Ok(None) => return Ok(None),
                 Err(err) => return Err(err),
@@ -803,7 +855,7 @@ mod tests {
         *,
     };
 
-    use crate::UnwrapInfallible;
+    use crate::{backends::NeedMoreInput, UnwrapInfallible};
 
     use rand_xoshiro::{
         rand_core::{RngCore, SeedableRng},
@@ -960,4 +1012,82 @@ mod tests {
         assert!(reconstructed.eq(iter_probs_and_symbols(amt).map(|(_, symbol)| symbol)));
         assert!(decoder.maybe_exhausted());
     }
+
+    #[test]
+    fn resumable_queue_decoder() {
+        #[cfg(not(miri))]
+        let amt = 1000;
+
+        #[cfg(miri)]
+        let amt = 100;
+
+        // Very skewed probabilities lead to codewords that span up to three `u8` words.
+        let probabilities = (0..20).map(|i| 1u32 << i).collect::<Vec<_>>();
+        let encoder_codebook = EncoderHuffmanTree::from_probabilities::<u32, _>(&probabilities);
+        let decoder_codebook = DecoderHuffmanTree::from_probabilities::<u32, _>(&probabilities);
+
+        let mut rng = Xoshiro256StarStar::seed_from_u64(2021);
+        let symbols = (0..amt)
+            .map(|_| (rng.next_u32() % 20) as usize)
+            .collect::<Vec<_>>();
+
+        let mut encoder = QueueEncoder::<u8>::new();
+        encoder
+            .encode_iid_symbols(&symbols, &encoder_codebook)
+            .unwrap();
+        let compressed = encoder.into_compressed().unwrap_infallible();
+
+        // Decode through a generic `ReadBitStream` to make sure that we use the trait method.
+        fn decode_symbol<D: ReadBitStream<Queue>>(
+            decoder: &mut D,
+            codebook: &DecoderHuffmanTree,
+        ) -> Result<usize, CoderError<SymbolCodeError<Infallible>, D::ReadError>> {
+            decoder.decode_symbol(codebook)
+        }
+
+        // Push one word at a time, and only when the decoder asks for it.
+        let mut words = compressed.iter().copied();
+        let mut decoder = QueueDecoder::from_compressed(PushReadWords::new());
+        let mut push_next_word = |decoder: &mut QueueDecoder<u8, _>| match words.next() {
+            Some(word) => decoder.push_words([word]),
+            None => decoder.close_input(),
+        };
+        let mut decoded = Vec::with_capacity(amt);
+        let mut num_retries = 0;
+
+        // Decode the first half of the symbols one by one ...
+        while decoded.len() != amt / 2 {
+            match decode_symbol(&mut decoder, &decoder_codebook) {
+                Ok(symbol) => decoded.push(symbol),
+                Err(CoderError::Backend(NeedMoreInput)) => {
+                    num_retries += 1;
+                    push_next_word(&mut decoder);
+                }
+                Err(err) => panic!("{:?}", err),
+            }
+        }
+
+        // ... and the second half with an iterator, which we restart after each error.
+        while decoded.len() != amt {
+            let mut need_more_input = false;
+            for result in decoder.decode_iid_symbols(amt - decoded.len(), &decoder_codebook) {
+                match result {
+                    Ok(symbol) => decoded.push(symbol),
+                    Err(CoderError::Backend(NeedMoreInput)) => {
+                        need_more_input = true;
+                        break;
+                    }
+                    Err(err) => panic!("{:?}", err),
+                }
+            }
+            if need_more_input {
+                num_retries += 1;
+                push_next_word(&mut decoder);
+            }
+        }
+
+        assert_eq!(decoded, symbols);
+        assert!(num_retries >= compressed.len());
+        assert!(decoder.maybe_exhausted());
+    }
 }